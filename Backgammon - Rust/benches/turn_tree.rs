@@ -0,0 +1,90 @@
+//! Times turn generation, which is most of the work in simulated games,
+//! for picking a turn with Rando and Smarty and for validating the last of the legal turns.
+//! Rolls of doubles build the largest turn trees.
+//! Run with `cargo bench --bench turn_tree`,
+//! `-- --save <file>` keeps the timings and `-- --baseline <file>` compares against kept timings
+
+use std::{collections::HashMap, env, fs, time::Instant};
+
+use backgammon_lib::{
+    board::Board,
+    player::{Player, PlayerColor},
+    start_position::StartPosition,
+    strategy::{rando::Rando, smarty::Smarty},
+};
+
+const ROLLS: usize = 2000;
+const COLUMNS: [&str; 3] = ["rando picking", "smarty picking", "validating"];
+
+fn time<F: FnMut()>(mut f: F) -> f64 {
+    //! mean microseconds per call
+    let started = Instant::now();
+    for _ in 0..ROLLS {
+        f();
+    }
+    started.elapsed().as_secs_f64() * 1e6 / ROLLS as f64
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let flag = |name: &str| args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1));
+    let baseline: Option<HashMap<String, Vec<f64>>> = flag("--baseline").map(|path| {
+        let saved = fs::read_to_string(path).expect("couldn't read the baseline");
+        serde_json::from_str(&saved).expect("baseline isn't saved timings")
+    });
+
+    let positions = vec!(
+        ("standard", Board::new()),
+        ("nackgammon", StartPosition::Nackgammon.board()),
+        ("back game", StartPosition::BackGame.board()),
+        ("priming", StartPosition::PrimingBattle.board()),
+    );
+    let rolls: Vec<Vec<u8>> = vec!(vec!(6, 5), vec!(3, 1), vec!(1, 1, 1, 1), vec!(2, 2, 2, 2), vec!(3, 3, 3, 3), vec!(4, 4, 4, 4));
+    let mut rando = Player::new("rando".to_string(), Rando);
+    rando.start_game(PlayerColor::White, "opponent".to_string()).unwrap();
+    let mut smarty = Player::new("smarty".to_string(), Smarty::new());
+    smarty.start_game(PlayerColor::White, "opponent".to_string()).unwrap();
+
+    let mut timings = HashMap::new();
+    let mut totals = [0.0; 3];
+    let mut before_totals = [0.0; 3];
+    let width = if baseline.is_some() { 32 } else { 14 };
+    println!("{:<40} {}", "", COLUMNS.map(|column| format!("{:>1$}", column, width)).join(" "));
+    for (name, board) in positions.iter() {
+        for dice in rolls.iter() {
+            let turns = rando.legal_turns(board, dice);
+            // the administrator checks every turn it's sent
+            let last = turns.last().cloned().unwrap_or_default();
+            let row = [
+                time(|| { rando.get_turn(board, dice); }),
+                time(|| { smarty.get_turn(board, dice); }),
+                time(|| assert!(rando.validate_turn(board, dice, &last))),
+            ];
+            let key = format!("{} {:?}", name, dice);
+            let cells: Vec<String> = match baseline.as_ref().and_then(|baseline| baseline.get(&key)) {
+                Some(before) => row.iter().zip(before).enumerate().map(|(i, (after, before))| {
+                    before_totals[i] += before;
+                    format!("{:>9.1} -> {:>9.1} us {:>+5.0}%", before, after, (after / before - 1.0) * 100.0)
+                }).collect(),
+                None => row.iter().map(|us| format!("{:>11.1} us", us)).collect(),
+            };
+            for (total, us) in totals.iter_mut().zip(row.iter()) {
+                *total += us;
+            }
+            println!("{:<12} {:<14} {:>6} turns {}", name, format!("{:?}", dice), turns.len(), cells.join(" "));
+            timings.insert(key, row.to_vec());
+        }
+    }
+    for (i, column) in COLUMNS.iter().enumerate() {
+        if baseline.is_some() {
+            println!("total {:<14} {:>9.1} -> {:>9.1} us {:>+5.0}%",
+                     column, before_totals[i], totals[i], (totals[i] / before_totals[i] - 1.0) * 100.0);
+        } else {
+            println!("total {:<14} {:>9.1} us", column, totals[i]);
+        }
+    }
+
+    if let Some(path) = flag("--save") {
+        fs::write(path, serde_json::to_string(&timings).unwrap()).expect("couldn't save the timings");
+    }
+}
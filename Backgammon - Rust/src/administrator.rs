@@ -2,7 +2,9 @@ use serde::Deserialize;
 use serde_json::{Value};
 
 use crate::{
+    cheat::{CheatReason, Infraction},
     strategy::{
+        any_strat::AnyStrat,
         bopsy::Bopsy,
         rando::Rando,
     },
@@ -10,42 +12,66 @@ use crate::{
     board::Board,
     local_remote::{LocalPlayer, RemotePlayer, IPlayer},
     r#move::Move,
-    r#mod::HOME,
-    net_config::NetConfig
+    net_config::NetConfig,
+    observer::AdminObserver,
+    rules::{any_rules::AnyRules, base::Rules},
+    start_position::StartPosition,
 };
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::fmt;
 use std::net::{TcpStream, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 pub struct Administrator {
     white_player: Box<dyn IPlayer>,
     black_player: Box<dyn IPlayer>,
     current_board: Board,
     player_one_color: PlayerColor,
+    rng: StdRng,
+    num_turns: usize,
+    observers: Vec<Box<dyn AdminObserver>>,
+    setup: GameSetup,
+    /// Some if the game hit a limit before anyone won
+    cutoff: Option<Cutoff>,
+    /// filled in by an observer, so cheaters are remembered after they are replaced
+    infractions: Arc<Mutex<Vec<Infraction>>>,
 }
 
-unsafe impl Send for Administrator {}
-
 impl Administrator {
     pub fn new(player_one: Box<dyn IPlayer>, player_two: Box<dyn IPlayer>) -> Administrator {
-        let mut rng = rand::thread_rng();
-        let r1: u8 = rng.gen_range(0..2);
-
-        let admin = if r1 == 0 {
-            Administrator {
-                white_player: player_one,
-                black_player: player_two,
-                current_board: Board::new(),
-                player_one_color: PlayerColor::White,
-            }
+        let mut rng = StdRng::from_entropy();
+        let player_one_color = if rng.gen_range(0..2) == 0 {
+            PlayerColor::White
         } else {
-            Administrator {
-                white_player: player_two,
-                black_player: player_one,
-                current_board: Board::new(),
-                player_one_color: PlayerColor::Black,
-            }
+            PlayerColor::Black
+        };
+        Administrator::with_rng(player_one, player_two, player_one_color, rng)
+    }
+
+    pub fn seeded(player_one: Box<dyn IPlayer>, player_two: Box<dyn IPlayer>, player_one_color: PlayerColor, seed: u64) -> Administrator {
+        //! creates administrator with fixed colors whose dice are reproducible from seed
+        Administrator::with_rng(player_one, player_two, player_one_color, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(player_one: Box<dyn IPlayer>, player_two: Box<dyn IPlayer>, player_one_color: PlayerColor, rng: StdRng) -> Administrator {
+        let (white_player, black_player) = match player_one_color {
+            PlayerColor::White => (player_one, player_two),
+            PlayerColor::Black => (player_two, player_one),
         };
-        admin
+        let infractions = Arc::new(Mutex::new(Vec::new()));
+        Administrator {
+            white_player,
+            black_player,
+            current_board: Board::new(),
+            player_one_color,
+            rng,
+            num_turns: 0,
+            observers: vec!(Box::new(InfractionLog(infractions.clone())) as Box<dyn AdminObserver>),
+            setup: GameSetup::default(),
+            cutoff: None,
+            infractions,
+        }
     }
 
     pub fn get_winning_player(&self) -> Option<Box<dyn IPlayer>> {
@@ -88,100 +114,199 @@ impl Administrator {
                 PlayerColor::Black => Winner::PlayerOne,
                 PlayerColor::White => Winner::PlayerTwo,
             }
-        } else if is_over(&self.current_board) {
-            debug_assert!(self.current_board.black[0] == HOME || self.current_board.white[0] == HOME,
-                          "neither player won, but neither player cheated");
-            debug_assert!(!(self.current_board.black[0] == HOME && self.current_board.white[0] == HOME),
-                          "both players have all their pieces in home, this shouldn't be possible");
-            //if no cheating occurred, a player must have all their pieces in HOME
-            if self.current_board.black[0] == HOME {
-                match self.player_one_color {
-                    PlayerColor::Black => Winner::PlayerOne,
-                    PlayerColor::White => Winner::PlayerTwo,
-                }
-            } else {
-                match self.player_one_color {
-                    PlayerColor::Black => Winner::PlayerTwo,
-                    PlayerColor::White => Winner::PlayerOne,
-                }
+        } else if let Some(cutoff) = &self.cutoff {
+            match &cutoff.winner {
+                None => Winner::None,
+                Some(color) if *color == self.player_one_color => Winner::PlayerOne,
+                Some(_) => Winner::PlayerTwo,
             }
+        } else if let Some(winner) = self.setup.rules.winner(&self.current_board) {
+            //if no cheating occurred, a player must have won by the rules
+            if winner == self.player_one_color { Winner::PlayerOne } else { Winner::PlayerTwo }
         } else {
             panic!("no player cheated, no player won");
         }
     }
 
     pub fn moderate_game(&mut self, hc: HandleCheater) {
-        match hc {
-            HandleCheater::Replace => AdminReplace::moderate_game(&mut self.black_player, &mut self.white_player, &mut self.current_board),
-            HandleCheater::EndGame => AdminEndGame::moderate_game(&mut self.black_player, &mut self.white_player, &mut self.current_board),
-        }
+        let (num_turns, cutoff) = match hc {
+            HandleCheater::Replace => AdminReplace::moderate_game(&mut self.black_player, &mut self.white_player, &mut self.current_board, &mut self.rng, &self.setup, &mut self.observers),
+            HandleCheater::EndGame => AdminEndGame::moderate_game(&mut self.black_player, &mut self.white_player, &mut self.current_board, &mut self.rng, &self.setup, &mut self.observers),
+        };
+        self.num_turns = num_turns;
+        self.cutoff = cutoff;
     }
+
+    #[inline]
+    pub fn get_cutoff(&self) -> Option<&Cutoff> { self.cutoff.as_ref() }
+
+    pub fn get_infractions(&self) -> Vec<Infraction> {
+        //! every time a player was caught cheating, in the order it happened
+        self.infractions.lock().unwrap().clone()
+    }
+
+    pub fn set_replacement(&mut self, replacement: Replacement) {
+        //! who takes over for a cheater under HandleCheater::Replace
+        self.setup.replacement = replacement;
+    }
+
+    pub fn set_limits(&mut self, limits: GameLimits) {
+        //! stops games that go on too long, none by default
+        self.setup.limits = limits;
+    }
+
+    pub fn set_rules(&mut self, rules: AnyRules) {
+        //! the game played, backgammon by default. Also starts the game where rules start it
+        self.setup.rules = rules;
+        self.set_start(rules.start());
+    }
+
+    pub fn set_start(&mut self, start: StartPosition) {
+        //! where the checkers are when the game starts, the standard setup by default
+        self.current_board = start.board();
+        self.setup.start = start;
+    }
+
+    pub fn add_observer(&mut self, observer: Box<dyn AdminObserver>) {
+        //! observers are notified in the order they were added
+        self.observers.push(observer);
+    }
+
+    #[inline]
+    pub fn get_board(&self) -> &Board { &self.current_board }
+
+    #[inline]
+    pub fn get_num_turns(&self) -> usize { self.num_turns }
+
+    #[inline]
+    pub fn get_player_one_color(&self) -> PlayerColor { self.player_one_color.clone() }
 }
 
 pub trait Admin {
-    fn moderate_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &mut Board);
-    fn start_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>);
-    fn handle_turn(current_player: &mut Box<dyn IPlayer>, board: &Board) -> Vec<Move>;
+    /// plays one game to completion or a limit, returning the number of turns taken and the limit if one was hit
+    fn moderate_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &mut Board, rng: &mut StdRng,
+                     setup: &GameSetup, observer: &mut dyn AdminObserver) -> (usize, Option<Cutoff>);
+    /// tells both players where the game starts, returning the names of whoever ends up in each seat
+    fn start_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, setup: &GameSetup, observer: &mut dyn AdminObserver) -> SeatNames;
+    fn handle_turn(current_player: &mut Box<dyn IPlayer>, names: &mut SeatNames, board: &Board, rng: &mut StdRng, setup: &GameSetup, observer: &mut dyn AdminObserver) -> Vec<Move>;
+}
+
+/**
+The names of the players in each seat, asked for once when the game starts.
+Cheaters are never asked again, so these are what reports and replacements use.
+*/
+#[derive(Clone, Default, Debug)]
+pub struct SeatNames {
+    pub black: String,
+    pub white: String,
+}
+
+impl SeatNames {
+    pub fn of(&self, color: &PlayerColor) -> &str {
+        match color {
+            PlayerColor::Black => &self.black,
+            PlayerColor::White => &self.white,
+        }
+    }
+
+    pub fn opponent_of(&self, color: &PlayerColor) -> &str {
+        self.of(&swap_turn(color.clone()))
+    }
+
+    fn set(&mut self, color: &PlayerColor, name: String) {
+        match color {
+            PlayerColor::Black => self.black = name,
+            PlayerColor::White => self.white = name,
+        }
+    }
 }
 
 pub struct AdminEndGame;
 
 impl Admin for AdminEndGame {
-    fn moderate_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &mut Board) {
-        let mut current_turn = decide_first();
-        AdminEndGame::start_game(black_player, white_player);
+    fn moderate_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &mut Board, rng: &mut StdRng,
+                     setup: &GameSetup, observer: &mut dyn AdminObserver) -> (usize, Option<Cutoff>) {
+        let started = Instant::now();
+        let mut current_turn = decide_first(rng);
+        let mut num_turns = 0;
+        let mut names = AdminEndGame::start_game(black_player, white_player, setup, observer);
         if black_player.has_cheated() {
+            report_cheater(PlayerColor::Black, &names, black_player, observer);
             if white_player.has_cheated() {
-                return;
+                report_cheater(PlayerColor::White, &names, white_player, observer);
+                observer.game_over(None, board);
+                return (num_turns, None);
             }
-            white_player.end_game(&board, true);
-            return;
+            forfeit(PlayerColor::White, white_player, board, observer);
+            return (num_turns, None);
         } else if white_player.has_cheated() {
-            black_player.end_game(&board, true);
-            return;
+            report_cheater(PlayerColor::White, &names, white_player, observer);
+            forfeit(PlayerColor::Black, black_player, board, observer);
+            return (num_turns, None);
         }
         loop {
             let moves = match current_turn {
-                PlayerColor::Black => AdminEndGame::handle_turn(black_player, board),
-                PlayerColor::White => AdminEndGame::handle_turn(white_player, board),
+                PlayerColor::Black => AdminEndGame::handle_turn(black_player, &mut names, board, rng, setup, observer),
+                PlayerColor::White => AdminEndGame::handle_turn(white_player, &mut names, board, rng, setup, observer),
             };
+            num_turns += 1;
             if black_player.has_cheated() {
-                white_player.end_game(&board, true);
-                return;
+                report_cheater(PlayerColor::Black, &names, black_player, observer);
+                forfeit(PlayerColor::White, white_player, board, observer);
+                return (num_turns, None);
             } else if white_player.has_cheated() {
-                black_player.end_game(&board, true);
-                return;
+                report_cheater(PlayerColor::White, &names, white_player, observer);
+                forfeit(PlayerColor::Black, black_player, board, observer);
+                return (num_turns, None);
             }
-            process_moves(board, &current_turn, moves);
-            if is_over(board) {
+            process_moves(board, &current_turn, &moves, &setup.rules);
+            observer.turn_submitted(&current_turn, &moves, board);
+            if is_over(board, &setup.rules) {
                 break;
             }
+            if let Some(cutoff) = setup.limits.check(num_turns, started, board) {
+                cut_short(black_player, white_player, board, &cutoff);
+                AdminEndGame::report_end_game_cheaters(black_player, white_player, &names, observer);
+                let winner = after_cheating(black_player.as_ref(), white_player.as_ref(), cutoff.winner.clone());
+                observer.game_over(winner.as_ref(), board);
+                return (num_turns, Some(cutoff));
+            }
             current_turn = swap_turn(current_turn);
         }
 
         let white_win = matches!(current_turn, PlayerColor::White);
         white_player.end_game(&board, white_win);
         black_player.end_game(&board, !white_win);
+        AdminEndGame::report_end_game_cheaters(black_player, white_player, &names, observer);
+        let winner = after_cheating(black_player.as_ref(), white_player.as_ref(), Some(current_turn));
+        observer.game_over(winner.as_ref(), board);
+        (num_turns, None)
     }
 
-    fn start_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>) {
-        let black_name = black_player.get_name();
-        let white_name = white_player.get_name();
+    fn start_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, setup: &GameSetup, observer: &mut dyn AdminObserver) -> SeatNames {
+        let names = SeatNames {
+            black: black_player.get_name().to_string(),
+            white: white_player.get_name().to_string(),
+        };
+        observer.game_started(&names.black, &names.white);
 
         if black_player.has_cheated() || white_player.has_cheated() {
-            return;
+            return names;
         }
 
-        black_player.start_game(PlayerColor::Black, white_name.to_string());
-        white_player.start_game(PlayerColor::White, black_name.to_string());
+        black_player.start_game_from(PlayerColor::Black, names.white.clone(), &setup.start, setup.rules);
+        white_player.start_game_from(PlayerColor::White, names.black.clone(), &setup.start, setup.rules);
+        names
     }
 
-    fn handle_turn(current_player: &mut Box<dyn IPlayer>, board: &Board) -> Vec<Move> {
+    fn handle_turn(current_player: &mut Box<dyn IPlayer>, _names: &mut SeatNames, board: &Board, rng: &mut StdRng, _setup: &GameSetup, observer: &mut dyn AdminObserver) -> Vec<Move> {
         if current_player.has_cheated() {
             return vec!();
         }
 
-        let dice = roll_dice();
+        let dice = roll_dice(rng);
+        let color = current_player.get_color();
+        observer.dice_rolled(&color, &dice);
         let moves = current_player.get_turn(board, &dice);
 
         if !current_player.has_cheated() {
@@ -190,26 +315,50 @@ impl Admin for AdminEndGame {
             }
         }
 
+        observer.turn_rejected(&color, &moves);
         vec!()
     }
 }
 
+impl AdminEndGame {
+    fn report_end_game_cheaters(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, names: &SeatNames, observer: &mut dyn AdminObserver) {
+        // cheating here still costs the game, once get_winner is asked
+        if black_player.has_cheated() {
+            report_cheater(PlayerColor::Black, names, black_player, observer);
+        }
+        if white_player.has_cheated() {
+            report_cheater(PlayerColor::White, names, white_player, observer);
+        }
+    }
+}
+
 pub struct AdminReplace;
 
 impl Admin for AdminReplace {
-    fn moderate_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &mut Board) {
-        let mut current_turn = decide_first();
-        AdminReplace::start_game(black_player, white_player);
-        AdminReplace::handle_cheater(black_player, white_player);
+    fn moderate_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &mut Board, rng: &mut StdRng,
+                     setup: &GameSetup, observer: &mut dyn AdminObserver) -> (usize, Option<Cutoff>) {
+        let started = Instant::now();
+        let mut current_turn = decide_first(rng);
+        let mut num_turns = 0;
+        let mut names = AdminReplace::start_game(black_player, white_player, setup, observer);
+        AdminReplace::handle_cheater(black_player, white_player, &mut names, board, setup, observer);
         loop {
             let moves = match current_turn {
-                PlayerColor::Black => AdminReplace::handle_turn(black_player, board),
-                PlayerColor::White => AdminReplace::handle_turn(white_player, board),
+                PlayerColor::Black => AdminReplace::handle_turn(black_player, &mut names, board, rng, setup, observer),
+                PlayerColor::White => AdminReplace::handle_turn(white_player, &mut names, board, rng, setup, observer),
             };
-            process_moves(board, &current_turn, moves);
-            if is_over(board) {
+            num_turns += 1;
+            process_moves(board, &current_turn, &moves, &setup.rules);
+            observer.turn_submitted(&current_turn, &moves, board);
+            if is_over(board, &setup.rules) {
                 break;
             }
+            if let Some(cutoff) = setup.limits.check(num_turns, started, board) {
+                cut_short(black_player, white_player, board, &cutoff);
+                AdminReplace::handle_cheater(black_player, white_player, &mut names, board, setup, observer);
+                observer.game_over(cutoff.winner.as_ref(), board);
+                return (num_turns, Some(cutoff));
+            }
             current_turn = swap_turn(current_turn);
         }
         match current_turn {
@@ -222,34 +371,46 @@ impl Admin for AdminReplace {
                 black_player.end_game(&board, true);
             }
         };
-        AdminReplace::handle_cheater(black_player, white_player);
+        AdminReplace::handle_cheater(black_player, white_player, &mut names, board, setup, observer);
+        observer.game_over(Some(&current_turn), board);
+        (num_turns, None)
     }
 
-    fn start_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>) {
-        let mut black_name = black_player.get_name();
-        let mut white_name = white_player.get_name();
+    fn start_game(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, setup: &GameSetup, observer: &mut dyn AdminObserver) -> SeatNames {
+        let replacement = &setup.replacement;
+        let mut names = SeatNames {
+            black: black_player.get_name().to_string(),
+            white: white_player.get_name().to_string(),
+        };
 
         if black_player.has_cheated() {
-            *black_player = replacement_player();
-            black_name = black_player.get_name();
+            report_cheater(PlayerColor::Black, &names, black_player, observer);
+            *black_player = replacement.player(setup.rules);
+            names.black = replacement.name.clone();
+            observer.player_replaced(&PlayerColor::Black, &names.black);
         }
         if white_player.has_cheated() {
-            *white_player = replacement_player();
-            white_name = white_player.get_name();
+            report_cheater(PlayerColor::White, &names, white_player, observer);
+            *white_player = replacement.player(setup.rules);
+            names.white = replacement.name.clone();
+            observer.player_replaced(&PlayerColor::White, &names.white);
         }
+        observer.game_started(&names.black, &names.white);
 
-        black_player.start_game(PlayerColor::Black, white_name.to_string());
-        white_player.start_game(PlayerColor::White, black_name.to_string());
+        black_player.start_game_from(PlayerColor::Black, names.white.clone(), &setup.start, setup.rules);
+        white_player.start_game_from(PlayerColor::White, names.black.clone(), &setup.start, setup.rules);
+        names
     }
 
-    fn handle_turn(current_player: &mut Box<dyn IPlayer>, board: &Board) -> Vec<Move> {
+    fn handle_turn(current_player: &mut Box<dyn IPlayer>, names: &mut SeatNames, board: &Board, rng: &mut StdRng, setup: &GameSetup, observer: &mut dyn AdminObserver) -> Vec<Move> {
         if current_player.has_cheated() {
             let col = current_player.get_color();
-            *current_player = replacement_player();
-            current_player.start_game(col, "opp".to_string());
+            AdminReplace::replace(col, current_player, names, board, setup, observer);
         }
 
-        let dice = roll_dice();
+        let dice = roll_dice(rng);
+        let col = current_player.get_color();
+        observer.dice_rolled(&col, &dice);
         let moves = current_player.get_turn(board, &dice);
 
         if !current_player.has_cheated() {
@@ -258,28 +419,62 @@ impl Admin for AdminReplace {
             }
         }
 
-        let col = current_player.get_color();
-        *current_player = replacement_player();
-        current_player.start_game(col, "opp".to_string());
+        observer.turn_rejected(&col, &moves);
+        AdminReplace::replace(col, current_player, names, board, setup, observer);
         current_player.get_turn(board, &dice)
     }
 }
 
 impl AdminReplace {
-    fn handle_cheater(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>) {
+    fn handle_cheater(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, names: &mut SeatNames, board: &Board, setup: &GameSetup, observer: &mut dyn AdminObserver) {
         if black_player.has_cheated() {
-            *black_player = replacement_player();
-            black_player.start_game(PlayerColor::Black, white_player.get_name().to_string());
+            AdminReplace::replace(PlayerColor::Black, black_player, names, board, setup, observer);
         }
         if white_player.has_cheated() {
-            *white_player = replacement_player();
-            white_player.start_game(PlayerColor::White, black_player.get_name().to_string());
+            AdminReplace::replace(PlayerColor::White, white_player, names, board, setup, observer);
         }
     }
+
+    fn replace(color: PlayerColor, cheater: &mut Box<dyn IPlayer>, names: &mut SeatNames, board: &Board, setup: &GameSetup, observer: &mut dyn AdminObserver) {
+        //! reports the cheater, then hands their seat to the replacement mid game
+        let replacement = &setup.replacement;
+        report_cheater(color.clone(), names, cheater, observer);
+        *cheater = replacement.take_over(color.clone(), names.opponent_of(&color), board, setup.rules);
+        names.set(&color, replacement.name.clone());
+        observer.player_replaced(&color, names.of(&color));
+    }
 }
 
-fn roll_dice() -> Vec<u8> {
-    let mut rng = rand::thread_rng();
+fn report_cheater(color: PlayerColor, names: &SeatNames, cheater: &mut Box<dyn IPlayer>, observer: &mut dyn AdminObserver) {
+    //! tells observers the player cheated and why, naming them from the seat since cheaters aren't asked again
+    observer.player_cheated(&color);
+    let reason = cheater.cheat_reason().unwrap_or(CheatReason::Unexplained);
+    observer.infraction(&Infraction { name: names.of(&color).to_string(), color, reason });
+}
+
+fn cut_short(black_player: &mut Box<dyn IPlayer>, white_player: &mut Box<dyn IPlayer>, board: &Board, cutoff: &Cutoff) {
+    //! ends a game that hit a limit, a void game is lost by both players
+    black_player.end_game(board, cutoff.winner == Some(PlayerColor::Black));
+    white_player.end_game(board, cutoff.winner == Some(PlayerColor::White));
+}
+
+fn after_cheating(black_player: &dyn IPlayer, white_player: &dyn IPlayer, winner: Option<PlayerColor>) -> Option<PlayerColor> {
+    //! the winner once cheating is counted, like get_winner, None if both players cheated
+    match (black_player.has_cheated(), white_player.has_cheated()) {
+        (true, true) => None,
+        (true, false) => Some(PlayerColor::White),
+        (false, true) => Some(PlayerColor::Black),
+        (false, false) => winner,
+    }
+}
+
+fn forfeit(winner_color: PlayerColor, winner: &mut Box<dyn IPlayer>, board: &Board, observer: &mut dyn AdminObserver) {
+    //! ends the game in favour of the player who didn't cheat
+    winner.end_game(board, true);
+    observer.game_over(Some(&winner_color), board);
+}
+
+fn roll_dice(rng: &mut StdRng) -> Vec<u8> {
     let r1: u8 = rng.gen_range(1..7);
     let r2: u8 = rng.gen_range(1..7);
     if r1 == r2 {
@@ -289,10 +484,10 @@ fn roll_dice() -> Vec<u8> {
     }
 }
 
-fn decide_first() -> PlayerColor {
-    let mut dice = roll_dice();
+fn decide_first(rng: &mut StdRng) -> PlayerColor {
+    let mut dice = roll_dice(rng);
     while dice.len() == 4 {
-        dice = roll_dice();
+        dice = roll_dice(rng);
     }
     if dice[0] < dice[1] {
         PlayerColor::Black
@@ -301,8 +496,119 @@ fn decide_first() -> PlayerColor {
     }
 }
 
-fn replacement_player() -> Box<dyn IPlayer> {
-    Box::new(LocalPlayer::new("Malnati".to_string(), Rando))
+/// Remembers every infraction for Administrator::get_infractions
+struct InfractionLog(Arc<Mutex<Vec<Infraction>>>);
+
+impl AdminObserver for InfractionLog {
+    fn infraction(&mut self, infraction: &Infraction) {
+        self.0.lock().unwrap().push(infraction.clone());
+    }
+}
+
+/// How an Administrator plays each game, beyond who is playing it
+#[derive(Clone, Debug, Default)]
+pub struct GameSetup {
+    pub replacement: Replacement,
+    pub limits: GameLimits,
+    pub start: StartPosition,
+    pub rules: AnyRules,
+}
+
+/// Stops games that aren't progressing, such as when a strategy or rule edge case means nobody can bear off
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct GameLimits {
+    /// turns taken by both players together
+    pub max_turns: Option<usize>,
+    /// checked between turns, so a turn that never ends needs a response timeout too
+    pub time: Option<Duration>,
+    pub result: LimitResult,
+}
+
+/// How a game that hit a limit is decided
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum LimitResult {
+    /// the player with fewer pips left to travel wins, a tie is void
+    #[default]
+    PipCount,
+    Void,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Limit {
+    Turns,
+    Time,
+}
+
+/// A game that was stopped by a limit
+#[derive(Clone, PartialEq, Debug)]
+pub struct Cutoff {
+    pub limit: Limit,
+    /// None if the game is void
+    pub winner: Option<PlayerColor>,
+    /// black's and white's pip counts when the game stopped
+    pub pips: (u32, u32),
+}
+
+impl GameLimits {
+    fn check(&self, num_turns: usize, started: Instant, board: &Board) -> Option<Cutoff> {
+        //! decides the game if num_turns or the time since started are over the limits
+        let limit = if self.max_turns.is_some_and(|max| num_turns >= max) {
+            Limit::Turns
+        } else if self.time.is_some_and(|time| started.elapsed() >= time) {
+            Limit::Time
+        } else {
+            return None;
+        };
+        let pips = (board.pip_count(&PlayerColor::Black), board.pip_count(&PlayerColor::White));
+        let winner = match self.result {
+            LimitResult::Void => None,
+            LimitResult::PipCount if pips.0 < pips.1 => Some(PlayerColor::Black),
+            LimitResult::PipCount if pips.1 < pips.0 => Some(PlayerColor::White),
+            LimitResult::PipCount => None,
+        };
+        Some(Cutoff { limit, winner, pips })
+    }
+}
+
+impl fmt::Display for Cutoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limit = match self.limit {
+            Limit::Turns => "turn limit",
+            Limit::Time => "time limit",
+        };
+        match &self.winner {
+            Some(PlayerColor::Black) => write!(f, "black won on pips {} - {} at the {}", self.pips.0, self.pips.1, limit),
+            Some(PlayerColor::White) => write!(f, "white won on pips {} - {} at the {}", self.pips.0, self.pips.1, limit),
+            None => write!(f, "void at the {}, pips {} - {}", limit, self.pips.0, self.pips.1),
+        }
+    }
+}
+
+/// Who takes over for a cheater under HandleCheater::Replace
+#[derive(Clone, Debug)]
+pub struct Replacement {
+    pub name: String,
+    pub strategy: AnyStrat,
+}
+
+impl Default for Replacement {
+    fn default() -> Replacement {
+        Replacement { name: "Malnati".to_string(), strategy: AnyStrat::Rando(Rando) }
+    }
+}
+
+impl Replacement {
+    fn player(&self, rules: AnyRules) -> Box<dyn IPlayer> {
+        //! a replacement for a player who cheated before the game started
+        Box::new(LocalPlayer::new(self.name.clone(), self.strategy.clone()).with_rules(rules))
+    }
+
+    fn take_over(&self, color: PlayerColor, opponent: &str, board: &Board, rules: AnyRules) -> Box<dyn IPlayer> {
+        //! a replacement for a player who cheated mid game, told who they are playing and the board so far
+        let mut player = self.player(rules);
+        player.resume_game(color, opponent.to_string(), board);
+        player
+    }
 }
 
 fn swap_turn(turn: PlayerColor) -> PlayerColor {
@@ -312,15 +618,15 @@ fn swap_turn(turn: PlayerColor) -> PlayerColor {
     }
 }
 
-fn process_moves(board: &mut Board, current_turn: &PlayerColor, moves: Vec<Move>) {
+fn process_moves(board: &mut Board, current_turn: &PlayerColor, moves: &[Move], rules: &AnyRules) {
     for mve in moves.iter() {
-        board.make_move(current_turn, mve)
+        rules.make_move(board, current_turn, mve);
     }
 }
 
 #[inline]
-fn is_over(board: &Board) -> bool {
-    board.black[0] == HOME || board.white[0] == HOME
+fn is_over(board: &Board, rules: &AnyRules) -> bool {
+    rules.winner(board).is_some()
 }
 
 #[derive(Deserialize)]
@@ -359,6 +665,7 @@ pub enum Winner {
     None,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum HandleCheater {
     Replace,
     EndGame,
@@ -367,12 +674,14 @@ pub enum HandleCheater {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::r#mod::HOME;
 
     #[test]
     fn dice_rolls() {
         //! tests that dice are valid
+        let mut rng = StdRng::from_entropy();
         for _ in 0..1_000 {
-            let dice = roll_dice();
+            let dice = roll_dice(&mut rng);
             assert!(dice.len() == 2 || dice.len() == 4);
             for die in dice.iter() {
                 assert!(die <= &6u8 && die != &0);
@@ -390,9 +699,10 @@ mod tests {
     #[test]
     fn decide() {
         //! tests that decide_first varies in output
-        let first = decide_first();
+        let mut rng = StdRng::from_entropy();
+        let first = decide_first(&mut rng);
         for _ in 0..1_000 {
-            let second = decide_first();
+            let second = decide_first(&mut rng);
             match first {
                 PlayerColor::Black => {
                     if matches!(second, PlayerColor::White) { return; }
@@ -404,4 +714,94 @@ mod tests {
         }
         panic!("always made same decision");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn seeded_dice() {
+        //! tests that equal seeds roll equal dice
+        let mut rng_1 = StdRng::seed_from_u64(7);
+        let mut rng_2 = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            assert_eq!(roll_dice(&mut rng_1), roll_dice(&mut rng_2));
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let game = |limits: GameLimits| {
+            let mut admin = Administrator::seeded(
+                Box::new(LocalPlayer::new("rando".to_string(), Rando)),
+                Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+                PlayerColor::Black, 3);
+            admin.set_limits(limits);
+            admin.moderate_game(HandleCheater::EndGame);
+            admin
+        };
+        let admin = game(GameLimits { max_turns: Some(4), ..GameLimits::default() });
+        assert_eq!(admin.get_num_turns(), 4);
+        let cutoff = admin.get_cutoff().unwrap();
+        assert_eq!(cutoff.limit, Limit::Turns);
+        let board = admin.get_board();
+        assert_eq!(cutoff.pips, (board.pip_count(&PlayerColor::Black), board.pip_count(&PlayerColor::White)));
+        let expected = match cutoff.pips.0.cmp(&cutoff.pips.1) {
+            std::cmp::Ordering::Less => Winner::PlayerOne,
+            std::cmp::Ordering::Greater => Winner::PlayerTwo,
+            std::cmp::Ordering::Equal => Winner::None,
+        };
+        assert_eq!(admin.get_winner(), expected);
+
+        let admin = game(GameLimits { max_turns: Some(4), result: LimitResult::Void, ..GameLimits::default() });
+        assert_eq!(admin.get_winner(), Winner::None);
+        assert!(admin.get_winning_player().is_none());
+
+        let admin = game(GameLimits { time: Some(Duration::ZERO), ..GameLimits::default() });
+        assert_eq!((admin.get_num_turns(), admin.get_cutoff().unwrap().limit), (1, Limit::Time));
+
+        let admin = game(GameLimits::default());
+        assert!(admin.get_cutoff().is_none() && is_over(admin.get_board(), &AnyRules::default()));
+    }
+
+    #[test]
+    fn start() {
+        let mut black = [HOME; crate::r#mod::MAX_CHECKERS];
+        black[0] = 1;
+        let custom = StartPosition::custom(Board::from_positions(black, [2; crate::r#mod::MAX_CHECKERS])).unwrap();
+        let mut admin = Administrator::seeded(
+            Box::new(LocalPlayer::new("rando".to_string(), Rando)),
+            Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+            PlayerColor::Black, 5);
+        admin.set_start(custom.clone());
+        assert_eq!(*admin.get_board(), custom.board());
+        // black bears off its last checker on its first turn
+        admin.moderate_game(HandleCheater::EndGame);
+        assert!(admin.get_num_turns() <= 2);
+        assert_eq!(admin.get_winner(), Winner::PlayerOne);
+
+        let mut admin = Administrator::seeded(
+            Box::new(LocalPlayer::new("rando".to_string(), Rando)),
+            Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+            PlayerColor::White, 5);
+        admin.set_start(StartPosition::Hypergammon);
+        admin.moderate_game(HandleCheater::EndGame);
+        assert!(is_over(admin.get_board(), &AnyRules::default()));
+        assert_eq!(admin.get_board().num_checkers(), 3);
+    }
+
+    #[test]
+    fn other_rules() {
+        for rules in ["plakoto", "fevga"] {
+            let rules: AnyRules = rules.parse().unwrap();
+            for seed in 0..3 {
+                let mut admin = Administrator::seeded(
+                    Box::new(LocalPlayer::new("rando".to_string(), Rando)),
+                    Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+                    PlayerColor::Black, seed);
+                admin.set_rules(rules);
+                assert_eq!(*admin.get_board(), StartPosition::Stacked.board());
+                admin.moderate_game(HandleCheater::EndGame);
+                // both players keep to the rules, so the game ends with a winner by them
+                assert!(rules.winner(admin.get_board()).is_some());
+                assert!(admin.get_winning_player().is_some());
+            }
+        }
+    }
+}
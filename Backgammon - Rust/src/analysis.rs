@@ -0,0 +1,126 @@
+use std::cmp::Reverse;
+
+use serde_json::{json, Value};
+
+use crate::{
+    board::Board,
+    player::PlayerColor,
+    player_board::{PlayerBoard, PlayerStrat},
+    r#move::Move,
+    rules::{any_rules::AnyRules, base::Rules},
+};
+
+/// A legal turn as judged by a strategy, in world coordinates
+#[derive(Clone)]
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct TurnAnalysis {
+    pub moves: Vec<Move>,
+    /// board after the moves are made
+    pub board: Board,
+    pub score: usize,
+    /// 1 is best, turns with equal scores share a rank
+    pub rank: usize,
+}
+
+impl TurnAnalysis {
+    pub fn to_json(&self) -> Value {
+        let moves: Vec<Value> = self.moves.iter().map(|mve| mve.to_json()).collect();
+        json!({
+            "turn": moves,
+            "board": self.board,
+            "score": self.score,
+            "rank": self.rank,
+        })
+    }
+}
+
+pub fn analyze<S: PlayerStrat>(board: &Board, color: &PlayerColor, dice: &[u8], strategy: &S) -> Vec<TurnAnalysis> {
+    /*!
+    Lists every legal turn for color, best first, scored by strategy.
+    If no move is possible, the only legal turn is the empty one.
+    */
+    analyze_with_rules(board, color, dice, strategy, AnyRules::default())
+}
+
+pub fn analyze_with_rules<S: PlayerStrat>(board: &Board, color: &PlayerColor, dice: &[u8], strategy: &S, rules: AnyRules) -> Vec<TurnAnalysis> {
+    //! like analyze, for a game played by rules
+    let pl_board = PlayerBoard::new(color, board, strategy, rules);
+    let mut scored = pl_board.score_turns(dice);
+    if scored.is_empty() {
+        scored.push((vec!(), 0));
+    }
+    scored.sort_by_key(|(_, score)| Reverse(*score));
+
+    let mut analysis: Vec<TurnAnalysis> = Vec::with_capacity(scored.len());
+    for (i, (moves, score)) in scored.into_iter().enumerate() {
+        let rank = match analysis.last() {
+            Some(prev) if prev.score == score => prev.rank,
+            _ => i + 1,
+        };
+        let mut new_board = board.clone();
+        for mve in moves.iter() {
+            rules.make_move(&mut new_board, color, mve);
+        }
+        analysis.push(TurnAnalysis { moves, board: new_board, score, rank });
+    }
+    analysis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player::Player,
+        strategy::{rando::Rando, smarty::Smarty},
+    };
+
+    #[test]
+    fn ranks_sorted() {
+        let board = Board::new();
+        let dice = vec!(3, 1);
+        let analysis = analyze(&board, &PlayerColor::Black, &dice, &Smarty::new());
+        assert!(analysis.len() > 1);
+        assert_eq!(analysis[0].rank, 1);
+        for pair in analysis.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+            assert!(pair[0].rank <= pair[1].rank);
+            assert_eq!(pair[0].rank == pair[1].rank, pair[0].score == pair[1].score);
+        }
+
+        let mut pl = Player::new("hint".to_string(), Rando);
+        pl.start_game(PlayerColor::Black, "opp".to_string()).ok();
+        for turn in analysis.iter() {
+            assert_eq!(turn.moves.len(), 2);
+            assert!(pl.validate_turn(&board, &dice, &turn.moves));
+            let mut cmp_board = board.clone();
+            for mve in turn.moves.iter() {
+                cmp_board.make_move(&PlayerColor::Black, mve);
+            }
+            assert!(cmp_board == turn.board);
+        }
+    }
+
+    #[test]
+    fn best_is_chosen() {
+        let board = Board::new();
+        let dice = vec!(6, 4);
+        let analysis = analyze(&board, &PlayerColor::White, &dice, &Smarty::new());
+        let mut pl = Player::new("smarty".to_string(), Smarty::new());
+        pl.start_game(PlayerColor::White, "opp".to_string()).ok();
+        let chosen = pl.get_turn(&board, &dice);
+        let chosen_analysis = analysis.iter().find(|t| t.moves == chosen).unwrap();
+        assert_eq!(chosen_analysis.rank, 1);
+    }
+
+    #[test]
+    fn no_moves() {
+        let board = Board::from_positions(
+            [0, 0, 3, 6, 9, 9, 12, 12, 13, 13, 13, 23, 23, 24, 24],
+            [5, 10, 11, 14, 14, 16, 16, 17, 18, 19, 20, 20, 21, 21, 22]);
+        let analysis = analyze(&board, &PlayerColor::Black, &vec!(4, 5), &Smarty::new());
+        assert_eq!(analysis.len(), 1);
+        assert!(analysis[0].moves.is_empty());
+        assert!(analysis[0].board == board);
+        assert_eq!(analysis[0].to_json()["turn"], json!([]));
+    }
+}
@@ -0,0 +1,89 @@
+use std::process;
+
+use serde_json::json;
+
+use backgammon_lib::{
+    net_config::NetConfig,
+    network_player::NetworkPlayer,
+    player::Player,
+    strategy::any_strat::AnyStrat,
+    tcp_handler::TcpHandler,
+};
+
+const USAGE: &str = "usage: backgammon-client <host> <port> <name> [strategy] [--secret SECRET] [--websocket]
+  strategies: Rando, Bopsy, Smarty (default)
+  joins the server as a player and plays until the server closes the connection";
+
+struct Args {
+    net: NetConfig,
+    name: String,
+    strategy: AnyStrat,
+    secret: Option<String>,
+    websocket: bool,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let player = Player::new(args.name, args.strategy);
+    let connected = if args.websocket {
+        args.net.connect_websocket().map(|stream| NetworkPlayer::new(player, stream))
+    } else {
+        args.net.connect().map(|stream| NetworkPlayer::new(player, TcpHandler::new(stream)))
+    };
+    let mut client = match connected {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("failed to connect: {}", e);
+            process::exit(1);
+        }
+    };
+    if let Some(secret) = args.secret {
+        client = client.with_secret(secret);
+    }
+    if let Err(e) = client.handle_stream() {
+        eprintln!("bad message from server: {}", e);
+        process::exit(1);
+    }
+    println!("server closed the connection");
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut positional: Vec<&String> = Vec::with_capacity(4);
+    let mut secret = None;
+    let mut websocket = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--secret" => secret = Some(iter.next().ok_or("--secret expects a value")?.to_string()),
+            "--websocket" => websocket = true,
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() < 3 || positional.len() > 4 {
+        return Err(format!("expected host, port, name and an optional strategy, given {} arguments", positional.len()));
+    }
+    let port: u16 = positional[1].parse().map_err(|_| format!("'{}' is not a port", positional[1]))?;
+    let strategy = match positional.get(3) {
+        Some(strategy) => strategy.parse()?,
+        None => "Smarty".parse()?,
+    };
+    Ok(Args {
+        net: NetConfig::new(json!(positional[0]), json!(port)),
+        name: positional[2].to_string(),
+        strategy,
+        secret,
+        websocket,
+    })
+}
@@ -0,0 +1,50 @@
+use std::process;
+
+use backgammon_lib::{
+    administrator::{Administrator, HandleCheater, Winner},
+    human_player::HumanPlayer,
+    local_remote::LocalPlayer,
+    strategy::any_strat::AnyStrat,
+};
+
+const USAGE: &str = "usage: backgammon-play [strategy] [--name NAME]
+  strategies: Rando, Bopsy, Smarty (default)
+  plays a game in the terminal against the given strategy, enter 'help' during a turn for commands";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (name, strategy) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let opponent = LocalPlayer::new(strategy.name().to_string(), strategy);
+    let mut admin = Administrator::new(Box::new(HumanPlayer::new(name)), Box::new(opponent));
+    admin.moderate_game(HandleCheater::EndGame);
+    match admin.get_winner() {
+        Winner::PlayerOne => println!("game over after {} turns, you won", admin.get_num_turns()),
+        Winner::PlayerTwo => println!("game over after {} turns, you lost", admin.get_num_turns()),
+        Winner::None => println!("game abandoned"),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(String, AnyStrat), String> {
+    let mut name = "human".to_string();
+    let mut strategy: Option<AnyStrat> = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--name" => name = iter.next().ok_or("--name expects a value")?.to_string(),
+            _ if strategy.is_none() => strategy = Some(arg.parse()?),
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+    Ok((name, strategy.map_or_else(|| "Smarty".parse(), Ok)?))
+}
@@ -0,0 +1,55 @@
+use std::process;
+
+use backgammon_lib::{
+    simulation::{simulate, SimConfig},
+    strategy::any_strat::AnyStrat,
+};
+
+const USAGE: &str = "usage: backgammon-sim <strategy> <strategy> [--games N] [--threads N] [--seed N]
+  strategies: Rando, Bopsy, Smarty
+  plays N games between the two strategies, swapping colors every game,
+  and reports results from the first strategy's perspective";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (strat_a, strat_b, config) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let stats = simulate(&strat_a, &strat_b, &config);
+    println!("{}", stats.report());
+}
+
+fn parse_args(args: &[String]) -> Result<(AnyStrat, AnyStrat, SimConfig), String> {
+    let mut strats: Vec<AnyStrat> = Vec::with_capacity(2);
+    let mut config = SimConfig::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--games" => config.games = parse_value(arg, iter.next())?,
+            "--threads" => config.threads = parse_value(arg, iter.next())?,
+            "--seed" => config.seed = parse_value(arg, iter.next())?,
+            _ => strats.push(arg.parse()?),
+        }
+    }
+    if strats.len() != 2 {
+        return Err(format!("expected 2 strategies, given {}", strats.len()));
+    }
+    let strat_b = strats.pop().unwrap();
+    let strat_a = strats.pop().unwrap();
+    Ok((strat_a, strat_b, config))
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("{} expects a non-negative integer", flag))
+}
@@ -0,0 +1,82 @@
+use std::process;
+
+use backgammon_lib::strategy::{
+    any_strat::AnyStrat,
+    smarty::SmartyWeights,
+    tuning::{tune, TuningConfig},
+};
+
+const USAGE: &str = "usage: backgammon-tune [--baseline STRATEGY] [--start FILE] [--out FILE]
+                      [--population N] [--generations N] [--games N] [--seed N] [--threads N]
+  tunes Smarty's weights by playing candidates against the baseline (default Smarty)
+  and writes the best weights found to --out (default smarty_weights.json)";
+
+struct TuneArgs {
+    baseline: AnyStrat,
+    start: SmartyWeights,
+    out: String,
+    config: TuningConfig,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let tune_args = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = tune(&tune_args.baseline, &tune_args.start, &tune_args.config, &mut |generation, best| {
+        eprintln!("generation {}: best win rate {:.3}", generation, best.stats.win_rate());
+    });
+
+    if let Err(e) = result.weights.to_file(&tune_args.out) {
+        eprintln!("failed to write weights to '{}': {}", tune_args.out, e);
+        process::exit(1);
+    }
+    println!("{}", result.stats.report());
+}
+
+fn parse_args(args: &[String]) -> Result<TuneArgs, String> {
+    let mut tune_args = TuneArgs {
+        baseline: "Smarty".parse()?,
+        start: SmartyWeights::default(),
+        out: "smarty_weights.json".to_string(),
+        config: TuningConfig::default(),
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--baseline" => tune_args.baseline = next_arg(arg, iter.next())?.parse()?,
+            "--start" => {
+                let path = next_arg(arg, iter.next())?;
+                tune_args.start = SmartyWeights::from_file(path)
+                    .map_err(|e| format!("failed to load weights from '{}': {}", path, e))?;
+            }
+            "--out" => tune_args.out = next_arg(arg, iter.next())?.to_string(),
+            "--population" => tune_args.config.population = parse_value(arg, iter.next())?,
+            "--generations" => tune_args.config.generations = parse_value(arg, iter.next())?,
+            "--games" => tune_args.config.games_per_eval = parse_value(arg, iter.next())?,
+            "--seed" => tune_args.config.seed = parse_value(arg, iter.next())?,
+            "--threads" => tune_args.config.threads = parse_value(arg, iter.next())?,
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+    Ok(tune_args)
+}
+
+fn next_arg<'a>(flag: &str, value: Option<&'a String>) -> Result<&'a str, String> {
+    value.map(|v| v.as_str()).ok_or_else(|| format!("{} expects a value", flag))
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("{} expects a non-negative integer", flag))
+}
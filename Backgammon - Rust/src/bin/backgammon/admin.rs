@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use backgammon_lib::{
+    administrator::{GameLimits, HandleCheater, LimitResult, Replacement},
+    parser::get_admin_config,
+    record::GameRecorder,
+    rules::any_rules::AnyRules,
+    start_position::StartPosition,
+};
+
+use crate::{flag_value, parse_flag, read_json, CliError};
+
+pub const USAGE: &str = "usage: backgammon admin [--config FILE] [--record FILE] [--replace-cheater [STRATEGY]] [--replacement-name NAME]
+                       [--max-turns N] [--time-limit SECS] [--void-at-limit] [--rules RULES] [--start START]
+  hosts one game between a local strategy and the first remote player to connect,
+  configured by the JSON in FILE, or stdin if there is no FILE, e.g.
+  {\"local\": \"Bopsy\", \"port\": 8000}
+  prints \"started\" once the player can connect, then the winner's name
+  --record FILE       writes the game to FILE, for backgammon replay
+  --replace-cheater   replaces a cheating player, with Rando unless STRATEGY is given, instead of ending the game
+  --replacement-name  names the replacement, Malnati by default
+  --max-turns         stops the game after N turns between both players
+  --time-limit        stops the game once SECS seconds have passed, checked between turns
+  --void-at-limit     a stopped game has no winner, instead of going to the lower pip count
+  --rules             plays standard backgammon, plakoto or fevga
+  --start             starts from standard, nackgammon, back-game, priming-battle, hypergammon or stacked,
+                      or a board such as {\"black\": [..], \"white\": [..]}, instead of where the rules start";
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let mut config = None;
+    let mut record = None;
+    let mut handle_cheater = HandleCheater::EndGame;
+    let mut replacement = Replacement::default();
+    let mut limits = GameLimits::default();
+    let mut rules = AnyRules::default();
+    let mut start = None;
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--config" => config = Some(flag_value(arg, iter.next())?),
+            "--record" => record = Some(flag_value(arg, iter.next())?),
+            "--replace-cheater" => {
+                handle_cheater = HandleCheater::Replace;
+                if let Some(strategy) = iter.next_if(|next| !next.starts_with("--")) {
+                    replacement.strategy = strategy.parse().map_err(CliError::Usage)?;
+                }
+            }
+            "--replacement-name" => replacement.name = flag_value(arg, iter.next())?.to_string(),
+            "--max-turns" => match parse_flag(arg, iter.next())? {
+                0 => return Err(CliError::Usage("--max-turns must be at least 1".to_string())),
+                turns => limits.max_turns = Some(turns),
+            },
+            "--time-limit" => {
+                let secs: f64 = parse_flag(arg, iter.next())?;
+                if !(secs.is_finite() && secs > 0.0) {
+                    return Err(CliError::Usage("--time-limit must be a positive number of seconds".to_string()));
+                }
+                limits.time = Some(Duration::from_secs_f64(secs));
+            }
+            "--void-at-limit" => limits.result = LimitResult::Void,
+            "--rules" => rules = flag_value(arg, iter.next())?.parse().map_err(CliError::Usage)?,
+            "--start" => {
+                let value = flag_value(arg, iter.next())?;
+                let parsed = match serde_json::from_str(value) {
+                    Ok(board @ serde_json::Value::Object(_)) => StartPosition::from_json(&board),
+                    _ => value.parse(),
+                };
+                start = Some(parsed.map_err(CliError::Usage)?);
+            }
+            other => return Err(CliError::Usage(format!("unexpected argument '{}'", other))),
+        }
+    }
+    let json = read_json(config)?;
+    match json["local"].as_str() {
+        Some("Rando") | Some("Bopsy") => {}
+        _ => return Err(CliError::Failed("config needs \"local\": \"Rando\" or \"Bopsy\"".to_string())),
+    }
+    if !json["port"].is_u64() {
+        return Err(CliError::Failed("config needs a numeric \"port\"".to_string()));
+    }
+    let admin_config = get_admin_config(&json);
+
+    let listener = admin_config.get_listener();
+    println!("{}", json!("started"));
+    let (stream, _) = listener.accept().map_err(|e| CliError::Failed(format!("failed to accept a player: {}", e)))?;
+    let mut admin = admin_config.to_administrator(stream);
+    admin.set_replacement(replacement);
+    admin.set_limits(limits);
+    admin.set_rules(rules);
+    if let Some(start) = start {
+        admin.set_start(start);
+    }
+    if let Some(path) = record {
+        let recorder = GameRecorder::create(path, 1)
+            .map_err(|e| CliError::Failed(format!("failed to create {}: {}", path, e)))?;
+        admin.add_observer(Box::new(recorder));
+    }
+    admin.moderate_game(handle_cheater);
+    if let Some(cutoff) = admin.get_cutoff() {
+        eprintln!("stopped after {} turns, {}", admin.get_num_turns(), cutoff);
+    }
+    match admin.get_winning_player() {
+        Some(mut winner) => println!("{}", json!(winner.get_name().to_string())),
+        None => println!("{}", json!(null)),
+    }
+    Ok(())
+}
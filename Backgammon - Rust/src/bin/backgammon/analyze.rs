@@ -0,0 +1,109 @@
+use std::fs;
+
+use serde_json::Value;
+
+use backgammon_lib::{
+    analysis::analyze,
+    board::Board,
+    notation::format_play,
+    player::PlayerColor,
+    strategy::any_strat::AnyStrat,
+};
+
+use crate::{flag_value, parse_flag, CliError};
+
+pub const USAGE: &str = "usage: backgammon analyze <board> <dice> [--color C] [--strategy S] [--top N] [--json]
+  ranks every legal turn, best first
+  board               JSON board like {\"black\": [..], \"white\": [..]}, or a file holding one
+  dice                two dice like 3,1 or [3,1], doubles are played four times
+  --color C           the player to move, black (default) or white
+  --strategy S        scores the turns, Rando, Bopsy or Smarty (default), or Smarty:<weights file>
+  --top N             lists only the N best turns, 10 by default, 0 for all
+  --json              prints the turns as JSON";
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let mut positional: Vec<&String> = Vec::with_capacity(2);
+    let mut color = PlayerColor::Black;
+    let mut strategy = "Smarty".to_string();
+    let mut top: usize = 10;
+    let mut as_json = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--color" => color = parse_color(flag_value(arg, iter.next())?)?,
+            "--strategy" => strategy = flag_value(arg, iter.next())?.to_string(),
+            "--top" => top = parse_flag(arg, iter.next())?,
+            "--json" => as_json = true,
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() != 2 {
+        return Err(CliError::Usage(format!("expected a board and dice, given {} arguments", positional.len())));
+    }
+    let board = parse_board(positional[0])?;
+    let dice = parse_dice(positional[1])?;
+    let strategy: AnyStrat = strategy.parse().map_err(CliError::Usage)?;
+
+    let turns = analyze(&board, &color, &dice, &strategy);
+    let shown = if top == 0 { turns.len() } else { top.min(turns.len()) };
+    if as_json {
+        let json: Vec<Value> = turns[..shown].iter().map(|turn| turn.to_json()).collect();
+        println!("{}", Value::Array(json));
+        return Ok(());
+    }
+    let color_name = match color {
+        PlayerColor::Black => "black",
+        PlayerColor::White => "white",
+    };
+    println!("{} to play {}, {} legal turns", color_name, format_dice(&dice), turns.len());
+    for turn in &turns[..shown] {
+        let moves = if turn.moves.is_empty() { "(no move)".to_string() } else { format_play(&board, &turn.moves, &color) };
+        println!("{:>4}. {:<24} score {}", turn.rank, moves, turn.score);
+    }
+    Ok(())
+}
+
+fn parse_color(text: &str) -> Result<PlayerColor, CliError> {
+    match text.to_lowercase().as_str() {
+        "black" => Ok(PlayerColor::Black),
+        "white" => Ok(PlayerColor::White),
+        _ => Err(CliError::Usage(format!("'{}' is not a color, expected black or white", text))),
+    }
+}
+
+fn parse_board(text: &str) -> Result<Board, CliError> {
+    //! reads the board from text, or from the file named by text
+    let json = match serde_json::from_str::<Value>(text) {
+        Ok(json) => json,
+        Err(_) => {
+            let contents = fs::read_to_string(text)
+                .map_err(|e| CliError::Usage(format!("'{}' is neither a JSON board nor a readable file: {}", text, e)))?;
+            serde_json::from_str(&contents).map_err(|e| CliError::Failed(format!("{} is not JSON: {}", text, e)))?
+        }
+    };
+    serde_json::from_value(json).map_err(|e| CliError::Failed(format!("invalid board: {}", e)))
+}
+
+fn parse_dice(text: &str) -> Result<Vec<u8>, CliError> {
+    let err = || CliError::Usage(format!("'{}' is not a roll, expected two dice like 3,1", text));
+    let dice: Vec<u8> = text.trim_matches(|c| c == '[' || c == ']')
+        .split(|c| c == ',' || c == '-')
+        .map(|die| die.trim().parse().map_err(|_| err()))
+        .collect::<Result<_, _>>()?;
+    if dice.len() != 2 || dice.iter().any(|die| !(1..=6).contains(die)) {
+        return Err(err());
+    }
+    if dice[0] == dice[1] {
+        return Ok(vec!(dice[0]; 4));
+    }
+    Ok(dice)
+}
+
+pub fn format_dice(dice: &[u8]) -> String {
+    //! the roll as it was thrown, like 3-1 or 5-5
+    match dice {
+        [first, second, ..] => format!("{}-{}", first, second),
+        _ => format!("{:?}", dice),
+    }
+}
@@ -0,0 +1,55 @@
+use serde_json::json;
+
+use backgammon_lib::{
+    net_config::NetConfig,
+    network_player::NetworkPlayer,
+    player::Player,
+    strategy::any_strat::AnyStrat,
+    tcp_handler::TcpHandler,
+};
+
+use crate::{flag_value, CliError};
+
+pub const USAGE: &str = "usage: backgammon client <host> <port> <name> [--strategy S] [--secret SECRET] [--websocket]
+  joins the server as a player and plays until the server closes the connection
+  --strategy S        Rando, Bopsy or Smarty (default), or Smarty:<weights file>
+  --secret SECRET     answers the tournament's shared secret
+  --websocket         connects over WebSocket instead of TCP";
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let mut positional: Vec<&String> = Vec::with_capacity(3);
+    let mut strategy = "Smarty".to_string();
+    let mut secret = None;
+    let mut websocket = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--strategy" => strategy = flag_value(arg, iter.next())?.to_string(),
+            "--secret" => secret = Some(flag_value(arg, iter.next())?.to_string()),
+            "--websocket" => websocket = true,
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() != 3 {
+        return Err(CliError::Usage(format!("expected host, port and name, given {} arguments", positional.len())));
+    }
+    let port: u16 = positional[1].parse()
+        .map_err(|_| CliError::Usage(format!("'{}' is not a port", positional[1])))?;
+    let strategy: AnyStrat = strategy.parse().map_err(CliError::Usage)?;
+    let net = NetConfig::new(json!(positional[0]), json!(port));
+
+    let player = Player::new(positional[2].to_string(), strategy);
+    let connected = if websocket {
+        net.connect_websocket().map(|stream| NetworkPlayer::new(player, stream))
+    } else {
+        net.connect().map(|stream| NetworkPlayer::new(player, TcpHandler::new(stream)))
+    };
+    let mut client = connected.map_err(|e| CliError::Failed(format!("failed to connect: {}", e)))?;
+    if let Some(secret) = secret {
+        client = client.with_secret(secret);
+    }
+    client.handle_stream().map_err(|e| CliError::Failed(format!("bad message from server: {}", e)))?;
+    println!("server closed the connection");
+    Ok(())
+}
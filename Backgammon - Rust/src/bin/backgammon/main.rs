@@ -0,0 +1,92 @@
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+mod admin;
+mod analyze;
+mod client;
+mod replay;
+mod simulate;
+mod tournament;
+
+const USAGE: &str = "usage: backgammon <command> [arguments]
+commands:
+  tournament  host a tournament for remote players
+  admin       host one game between a local strategy and a remote player
+  client      join a server as a player
+  simulate    play two strategies against each other and report the results
+  analyze     rank every legal turn for a board and roll
+  replay      print a recorded game turn by turn
+run 'backgammon <command> --help' for the command's arguments";
+
+/// Why a command stopped early, which decides the exit code
+pub enum CliError {
+    /// the arguments were asked for with --help, exits 0
+    Help,
+    /// the arguments were wrong, exits 2
+    Usage(String),
+    /// the command couldn't finish, exits 1
+    Failed(String),
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    let (usage, result) = match command {
+        "tournament" => (tournament::USAGE, tournament::run(rest)),
+        "admin" => (admin::USAGE, admin::run(rest)),
+        "client" => (client::USAGE, client::run(rest)),
+        "simulate" => (simulate::USAGE, simulate::run(rest)),
+        "analyze" => (analyze::USAGE, analyze::run(rest)),
+        "replay" => (replay::USAGE, replay::run(rest)),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            return;
+        }
+        other => {
+            eprintln!("unknown command '{}'\n{}", other, USAGE);
+            process::exit(2);
+        }
+    };
+    match result {
+        Ok(()) => {}
+        Err(CliError::Help) => println!("{}", usage),
+        Err(CliError::Usage(msg)) => {
+            eprintln!("{}\n{}", msg, usage);
+            process::exit(2);
+        }
+        Err(CliError::Failed(msg)) => {
+            eprintln!("{}", msg);
+            process::exit(1);
+        }
+    }
+}
+
+pub fn flag_value<'a>(flag: &str, value: Option<&'a String>) -> Result<&'a String, CliError> {
+    value.ok_or_else(|| CliError::Usage(format!("{} expects a value", flag)))
+}
+
+pub fn parse_flag<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T, CliError> {
+    let value = flag_value(flag, value)?;
+    value.parse().map_err(|_| CliError::Usage(format!("'{}' is not a valid value for {}", value, flag)))
+}
+
+pub fn read_json(path: Option<&String>) -> Result<Value, CliError> {
+    //! reads a JSON value from the file at path, or stdin if there is no path
+    let mut text = String::new();
+    let read = match path {
+        Some(path) => fs::read_to_string(path).map(|contents| text = contents),
+        None => io::stdin().read_to_string(&mut text).map(|_| ()),
+    };
+    read.map_err(|e| CliError::Failed(format!("failed to read {}: {}", path.map_or("stdin", |p| p.as_str()), e)))?;
+    serde_json::from_str(&text).map_err(|e| CliError::Failed(format!("invalid JSON: {}", e)))
+}
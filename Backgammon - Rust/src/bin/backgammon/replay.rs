@@ -0,0 +1,93 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use backgammon_lib::{
+    human_player::render_board,
+    notation::format_turn,
+    player::PlayerColor,
+    record::{GameRecord, RecordStep},
+};
+
+use crate::{analyze::format_dice, flag_value, parse_flag, CliError};
+
+pub const USAGE: &str = "usage: backgammon replay <record> [--game ID] [--color C] [--moves-only]
+  prints a game recorded with --record, turn by turn
+  --game ID           replays only the game with this id, records can hold many games
+  --color C           draws the board from black's (default) or white's side
+  --moves-only        prints the moves without drawing the board after each turn";
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let mut path = None;
+    let mut game = None;
+    let mut pov = PlayerColor::Black;
+    let mut boards = true;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--game" => game = Some(parse_flag::<u64>(arg, iter.next())?),
+            "--color" => {
+                pov = match flag_value(arg, iter.next())?.to_lowercase().as_str() {
+                    "black" => PlayerColor::Black,
+                    "white" => PlayerColor::White,
+                    other => return Err(CliError::Usage(format!("'{}' is not a color, expected black or white", other))),
+                }
+            }
+            "--moves-only" => boards = false,
+            _ if path.is_none() => path = Some(arg),
+            other => return Err(CliError::Usage(format!("unexpected argument '{}'", other))),
+        }
+    }
+    let path = path.ok_or_else(|| CliError::Usage("expected a record".to_string()))?;
+    let file = File::open(path).map_err(|e| CliError::Failed(format!("failed to open {}: {}", path, e)))?;
+    let games = GameRecord::read_all(BufReader::new(file))
+        .map_err(|e| CliError::Failed(format!("{} is not a game record: {}", path, e)))?;
+    let games: Vec<&GameRecord> = games.iter().filter(|g| game.is_none_or(|id| g.game == id)).collect();
+    if games.is_empty() {
+        return Err(CliError::Failed(format!("{} has no matching games", path)));
+    }
+    for record in games {
+        print_game(record, &pov, boards);
+    }
+    Ok(())
+}
+
+fn print_game(record: &GameRecord, pov: &PlayerColor, boards: bool) {
+    println!("game {}: {} (black) vs {} (white)", record.game, record.black, record.white);
+    // replacements play on under their own names
+    let mut names = (record.black.clone(), record.white.clone());
+    for step in &record.steps {
+        match step {
+            RecordStep::Turn { color, dice, moves, board } => {
+                let moves = if moves.is_empty() { "(no move)".to_string() } else { format_turn(moves, color) };
+                println!("{} rolls {}: {}", player(&names, color), format_dice(dice), moves);
+                if boards {
+                    println!("{}", render_board(board, pov));
+                }
+            }
+            RecordStep::Rejected { color, moves } => {
+                println!("{} tried {}, which is illegal", player(&names, color), format_turn(moves, color));
+            }
+            RecordStep::Cheated { color } => println!("{} cheated", player(&names, color)),
+            RecordStep::Replaced { color, name } => {
+                println!("{} is replaced by {}", player(&names, color), name);
+                match color {
+                    PlayerColor::Black => names.0 = name.clone(),
+                    PlayerColor::White => names.1 = name.clone(),
+                }
+            }
+        }
+    }
+    match (&record.winner, record.finished) {
+        (Some(winner), true) => println!("{} wins\n", player(&names, winner)),
+        (None, true) => println!("no winner, both players cheated\n"),
+        (_, false) => println!("the record ends before the game does\n"),
+    }
+}
+
+fn player(names: &(String, String), color: &PlayerColor) -> String {
+    match color {
+        PlayerColor::Black => format!("{} (black)", names.0),
+        PlayerColor::White => format!("{} (white)", names.1),
+    }
+}
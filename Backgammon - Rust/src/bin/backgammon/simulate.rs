@@ -0,0 +1,35 @@
+use backgammon_lib::{
+    simulation::{simulate, SimConfig},
+    strategy::any_strat::AnyStrat,
+};
+
+use crate::{parse_flag, CliError};
+
+pub const USAGE: &str = "usage: backgammon simulate <strategy> <strategy> [--games N] [--threads N] [--seed N]
+  strategies: Rando, Bopsy, Smarty, or Smarty:<weights file>
+  plays N games between the two strategies, swapping colors every game,
+  and reports results from the first strategy's perspective";
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let mut strats: Vec<AnyStrat> = Vec::with_capacity(2);
+    let mut config = SimConfig::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--games" => config.games = parse_flag(arg, iter.next())?,
+            "--threads" => config.threads = parse_flag(arg, iter.next())?,
+            "--seed" => config.seed = parse_flag(arg, iter.next())?,
+            _ => strats.push(arg.parse().map_err(CliError::Usage)?),
+        }
+    }
+    if strats.len() != 2 {
+        return Err(CliError::Usage(format!("expected 2 strategies, given {}", strats.len())));
+    }
+    if config.games == 0 || config.threads == 0 {
+        return Err(CliError::Usage("--games and --threads must be at least 1".to_string()));
+    }
+    let stats = simulate(&strats[0], &strats[1], &config);
+    println!("{}", stats.report());
+    Ok(())
+}
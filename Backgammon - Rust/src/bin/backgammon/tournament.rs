@@ -0,0 +1,46 @@
+use std::fs;
+
+use serde_json::json;
+
+use backgammon_lib::tournament::config;
+
+use crate::{flag_value, read_json, CliError};
+
+pub const USAGE: &str = "usage: backgammon tournament [--config FILE]
+  hosts a tournament described by the config in FILE, or JSON on stdin if there is no FILE.
+  FILE is TOML if it ends in .toml and JSON otherwise, e.g.
+  {\"players\": 8, \"port\": 8000, \"type\": \"round robin\"}
+  optional keys: bind, transport, spectator-port, duplicate-names, secret, cheaters,
+  replacement, filler, games-per-pairing, seed, rules, start, timeouts, limits, output and log
+  prints \"started\" once players can join, then the winners, then why each cheater was caught if anyone cheated";
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    let mut path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--config" => path = Some(flag_value(arg, iter.next())?),
+            other => return Err(CliError::Usage(format!("unexpected argument '{}'", other))),
+        }
+    }
+    let loaded = match path {
+        Some(path) => config::load(path),
+        None => config::from_json(&read_json(None)?),
+    };
+    let config = loaded.map_err(|e| CliError::Failed(format!("invalid tournament config: {}", e)))?;
+    let mut tournament = config.to_tournament();
+    println!("{}", json!("started"));
+    tournament.moderate_tournament();
+    let report = tournament.report_winner();
+    let infractions = tournament.report_infractions();
+    println!("{}", report);
+    if infractions.as_array().is_some_and(|all| !all.is_empty()) {
+        println!("{}", json!({ "infractions": infractions }));
+    }
+    if let Some(results) = config.get_results() {
+        fs::write(results, format!("{}\n", json!({ "results": report, "infractions": infractions })))
+            .map_err(|e| CliError::Failed(format!("failed to write {}: {}", results.display(), e)))?;
+    }
+    Ok(())
+}
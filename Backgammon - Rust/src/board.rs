@@ -1,6 +1,7 @@
 use serde::{Serialize, Deserialize};
 
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 use serde::de::{self, Deserializer, Visitor, SeqAccess, MapAccess};
 use serde::ser::{Serializer, SerializeStruct};
@@ -8,21 +9,51 @@ use serde_json::Value;
 
 use crate::{
     player::PlayerColor,
-    r#mod::{move_checker, NUM_CHECKERS, piece_u8_to_val, piece_val_to_u8, try_bop, count_occur},
+    r#mod::{BAR, HOME, move_checker, MAX_CHECKERS, pad_positions, piece_u8_to_val, piece_val_to_u8, try_bop, count_occur},
     r#move::Move,
+    zobrist::{checkers_key, hash_board, hash_pins, pin_key, update_point, with_side},
 };
 
+const DEFAULT_BLACK: [u8; MAX_CHECKERS] = [6,6,6,6,6,8,8,8,13,13,13,13,13,24,24];
+const DEFAULT_WHITE: [u8; MAX_CHECKERS] = [1,1,12,12,12,12,12,17,17,17,19,19,19,19,19];
+
+/// A move made by Board::make_move, and whether it hit
+#[derive(Clone, PartialEq, Debug)]
+pub struct Undo {
+    pub mve: Move,
+    /// a lone opponent checker was sent to the bar
+    pub hit: bool,
+    /// both players' pins before the move, which rules without hitting may change
+    pinned: [u32; 2],
+}
+
+impl Undo {
+    pub fn before(board: &Board, mve: &Move) -> Undo {
+        //! records what's needed to take back mve, which is about to be made on board without hitting
+        Undo { mve: mve.clone(), hit: false, pinned: board.pinned }
+    }
+}
+
 const DEFAULT_BOARD: Board = Board {
-    black: [6,6,6,6,6,8,8,8,13,13,13,13,13,24,24],
-    white: [1,1,12,12,12,12,12,17,17,17,19,19,19,19,19],
+    black: DEFAULT_BLACK,
+    white: DEFAULT_WHITE,
+    hash: hash_board(&DEFAULT_BLACK, &DEFAULT_WHITE),
+    checkers: MAX_CHECKERS as u8,
+    pinned: [0; 2],
 };
 
 #[derive(Clone)]
 #[cfg_attr(debug_assertions, derive(Debug))]
 pub struct Board {
     //TODO: Make fields private?
-    pub black: [u8; NUM_CHECKERS],
-    pub white: [u8; NUM_CHECKERS],
+    pub black: [u8; MAX_CHECKERS],
+    pub white: [u8; MAX_CHECKERS],
+    /// zobrist hash of the position, checker count and pins, kept current by make_move, move_no_bop & set_pinned
+    hash: u64,
+    /// how many checkers each player started with, the rest of black and white is HOME
+    checkers: u8,
+    /// bit p is set when black's (then white's) lone checker on world point p is pinned, as in Plakoto
+    pinned: [u32; 2],
 }
 
 impl Board {
@@ -30,26 +61,110 @@ impl Board {
         DEFAULT_BOARD
     }
 
-    pub fn make_move(&mut self, player: &PlayerColor, mve: &Move) {
-        //! moves checker and bops enemy if possible
-        let (pieces, enemy_pieces) = match player {
-            PlayerColor::Black => (&mut self.black, &mut self.white),
-            PlayerColor::White => (&mut self.white, &mut self.black),
+    pub fn from_positions(black: [u8; MAX_CHECKERS], white: [u8; MAX_CHECKERS]) -> Board {
+        //! creates board from sorted positions in world coordinates
+        Board {
+            hash: hash_board(&black, &white),
+            black,
+            white,
+            checkers: MAX_CHECKERS as u8,
+            pinned: [0; 2],
+        }
+    }
+
+    pub fn with_checkers(black: &[u8], white: &[u8]) -> Board {
+        /*!
+        creates board for a game with fewer checkers, such as Hypergammon, from positions in any order.
+        Panics unless both players have the same number of checkers, from 1 to MAX_CHECKERS
+        */
+        assert!(black.len() == white.len() && (1..=MAX_CHECKERS).contains(&black.len()),
+                "both players need 1 to {} checkers, given {} and {}", MAX_CHECKERS, black.len(), white.len());
+        let board = Board::from_positions(pad_positions(black), pad_positions(white));
+        Board {
+            hash: board.hash ^ checkers_key(black.len() as u8),
+            checkers: black.len() as u8,
+            ..board
+        }
+    }
+
+    #[inline]
+    pub fn num_checkers(&self) -> usize {
+        //! checkers each player started with, only the first this many of black and white are in the game
+        self.checkers as usize
+    }
+
+    pub fn make_move(&mut self, player: &PlayerColor, mve: &Move) -> Undo {
+        //! moves checker and bops enemy if possible, returning what unmake_move needs to take it back
+        let opponent = match player {
+            PlayerColor::Black => PlayerColor::White,
+            PlayerColor::White => PlayerColor::Black,
         };
+        let mut undo = Undo::before(self, mve);
+        self.move_no_bop(player, mve);
 
-        move_checker(pieces, &mve.start, &mve.end);
-        try_bop(enemy_pieces, &mve.end);
+        let enemy_pieces = match player {
+            PlayerColor::Black => &mut self.white,
+            PlayerColor::White => &mut self.black,
+        };
+        // borne off checkers can't be bopped
+        let hit = mve.end != HOME && count_occur(enemy_pieces, &mve.end) == 1;
+        if hit {
+            let on_bar = count_occur(enemy_pieces, &BAR);
+            try_bop(enemy_pieces, &mve.end);
+            self.hash = update_point(self.hash, &opponent, mve.end, 1, 0);
+            self.hash = update_point(self.hash, &opponent, BAR, on_bar, on_bar + 1);
+        }
+        undo.hit = hit;
+        undo
+    }
+
+    pub fn unmake_move(&mut self, player: &PlayerColor, undo: &Undo) {
+        //! takes back the last make_move by player, or a rule's make_move, restoring the board exactly, hash and pins included
+        let opponent = match player {
+            PlayerColor::Black => PlayerColor::White,
+            PlayerColor::White => PlayerColor::Black,
+        };
+        self.move_no_bop(player, &Move { start: undo.mve.end, end: undo.mve.start });
+        if undo.hit {
+            self.move_no_bop(&opponent, &Move { start: BAR, end: undo.mve.end });
+        }
+        self.hash ^= hash_pins(&self.pinned) ^ hash_pins(&undo.pinned);
+        self.pinned = undo.pinned;
     }
 
     pub fn move_no_bop(&mut self, player: &PlayerColor, mve: &Move) {
-        //! moves checker without regard for other pieces
+        //! moves checker without regard for other pieces, panics if player has no checker on start
         let pieces = match player {
             PlayerColor::Black => &mut self.black,
             PlayerColor::White => &mut self.white,
         };
+        let at_start = count_occur(pieces, &mve.start);
+        assert!(at_start > 0, "no {:?} checker on {} to move", player, mve.start);
+        if mve.start != mve.end {
+            let at_end = count_occur(pieces, &mve.end);
+            self.hash = update_point(self.hash, player, mve.start, at_start, at_start - 1);
+            self.hash = update_point(self.hash, player, mve.end, at_end, at_end + 1);
+        }
         move_checker(pieces, &mve.start, &mve.end);
     }
 
+    #[inline]
+    pub fn zobrist_hash(&self) -> u64 {
+        //! returns hash of the position, independent of side to move
+        self.hash
+    }
+
+    #[inline]
+    pub fn position_key(&self, to_move: &PlayerColor) -> u64 {
+        //! returns hash of the position combined with side to move
+        with_side(self.hash, to_move)
+    }
+
+    pub fn rehash(&mut self) {
+        //! recomputes hash from scratch, needed after editing black or white directly
+        self.hash = hash_board(&self.black, &self.white) ^ checkers_key(self.checkers) ^ hash_pins(&self.pinned);
+    }
+
     pub fn count_occurrences(&self, player: &PlayerColor, pos: &u8) -> u8 {
         //! returns the number of times checkers at position pos
         let pieces = match player {
@@ -58,6 +173,60 @@ impl Board {
         };
         count_occur(pieces, pos)
     }
+
+    #[inline]
+    pub fn is_pinned(&self, player: &PlayerColor, point: u8) -> bool {
+        //! true if player's checker on point is held there by an opponent's checker
+        self.pinned[pin_side(player)] & (1 << point) != 0
+    }
+
+    pub fn set_pinned(&mut self, player: &PlayerColor, point: u8, pinned: bool) {
+        //! pins or frees player's checker on point, only rules without hitting pin checkers
+        debug_assert!(point != BAR && point != HOME, "only checkers on the board can be pinned, given {}", point);
+        if pinned != self.is_pinned(player, point) {
+            self.hash ^= pin_key(player, point);
+        }
+        if pinned {
+            self.pinned[pin_side(player)] |= 1 << point;
+        } else {
+            self.pinned[pin_side(player)] &= !(1 << point);
+        }
+    }
+
+    pub fn pip_count(&self, player: &PlayerColor) -> u32 {
+        //! total distance player's checkers have left to travel, the bar is 25 pips from home
+        let pips = |point: &u8| -> u32 {
+            match (player, *point) {
+                (_, BAR) => HOME as u32,
+                (_, HOME) => 0,
+                (PlayerColor::Black, point) => point as u32,
+                (PlayerColor::White, point) => (HOME - point) as u32,
+            }
+        };
+        match player {
+            PlayerColor::Black => self.black.iter().map(pips).sum(),
+            PlayerColor::White => self.white.iter().map(pips).sum(),
+        }
+    }
+}
+
+// the cached hash is left out, black and white are edited directly without rehashing
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.checkers == other.checkers && self.pinned == other.pinned
+            && self.black == other.black && self.white == other.white
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.black.hash(state);
+        self.white.hash(state);
+        self.checkers.hash(state);
+        self.pinned.hash(state);
+    }
 }
 
 impl Serialize for Board {
@@ -65,18 +234,21 @@ impl Serialize for Board {
         where
             S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Board", 2)?;
-        state.serialize_field("black", &pos_to_val(&self.black))?;
-        state.serialize_field("white", &pos_to_val(&self.white))?;
+        let pinned = self.pinned != [0; 2];
+        let mut state = serializer.serialize_struct("Board", if pinned { 3 } else { 2 })?;
+        let checkers = self.num_checkers();
+        state.serialize_field("black", &pos_to_val(&self.black[..checkers]))?;
+        state.serialize_field("white", &pos_to_val(&self.white[..checkers]))?;
+        if pinned {
+            // only sent in games that pin, so boards look the same as ever everywhere else
+            let points = |player: &PlayerColor| -> Vec<u8> { (1..HOME).filter(|p| self.is_pinned(player, *p)).collect() };
+            state.serialize_field("pinned", &Pins { black: points(&PlayerColor::Black), white: points(&PlayerColor::White) })?;
+        }
         return state.end();
 
-        fn pos_to_val(pieces: &[u8; NUM_CHECKERS]) -> Vec<Value> {
+        fn pos_to_val(pieces: &[u8]) -> Vec<Value> {
             //converts u8 positions to Value
-            let mut output: Vec<Value> = Vec::with_capacity(NUM_CHECKERS);
-            for i in 0..NUM_CHECKERS {
-                output.push(piece_u8_to_val(pieces[i]));
-            }
-            output
+            pieces.iter().map(|piece| piece_u8_to_val(*piece)).collect()
         }
     }
 }
@@ -86,7 +258,7 @@ impl<'de> Deserialize<'de> for Board {
         where
             D: Deserializer<'de>,
     {
-        enum Field { Black, White }
+        enum Field { Black, White, Pinned }
 
         impl<'de> Deserialize<'de> for Field {
             fn deserialize<D>(deserializer: D) -> Result<Field, D::Error>
@@ -99,7 +271,7 @@ impl<'de> Deserialize<'de> for Board {
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`black` or `white`")
+                        formatter.write_str("`black`, `white` or `pinned`")
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -109,6 +281,7 @@ impl<'de> Deserialize<'de> for Board {
                         match value {
                             "black" => Ok(Field::Black),
                             "white" => Ok(Field::White),
+                            "pinned" => Ok(Field::Pinned),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -131,11 +304,12 @@ impl<'de> Deserialize<'de> for Board {
                 where
                     V: SeqAccess<'de>,
             {
-                let black_val = seq.next_element()?
+                let black_val: Vec<Value> = seq.next_element()?
                     .ok_or_else(|| de::Error::invalid_length(0, &self))?;
-                let white_val = seq.next_element()?
+                let white_val: Vec<Value> = seq.next_element()?
                     .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-                return Ok(Board {black: pos_to_u8(&black_val), white: pos_to_u8(&white_val)});
+                let pins: Option<Pins> = seq.next_element()?;
+                from_values(&black_val, &white_val, pins)
             }
 
             fn visit_map<V>(self, mut map: V) -> Result<Board, V::Error>
@@ -144,6 +318,7 @@ impl<'de> Deserialize<'de> for Board {
             {
                 let mut black_val = None;
                 let mut white_val = None;
+                let mut pins = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Black => {
@@ -158,32 +333,61 @@ impl<'de> Deserialize<'de> for Board {
                             }
                             white_val = Some(map.next_value()?);
                         }
+                        Field::Pinned => {
+                            if pins.is_some() {
+                                return Err(de::Error::duplicate_field("pinned"));
+                            }
+                            pins = Some(map.next_value()?);
+                        }
                     }
                 }
-                let black_val = black_val.ok_or_else(|| de::Error::missing_field("black"))?;
-                let white_val = white_val.ok_or_else(|| de::Error::missing_field("white"))?;
-                return Ok(Board {black: pos_to_u8(&black_val), white: pos_to_u8(&white_val)});
+                let black_val: Vec<Value> = black_val.ok_or_else(|| de::Error::missing_field("black"))?;
+                let white_val: Vec<Value> = white_val.ok_or_else(|| de::Error::missing_field("white"))?;
+                from_values(&black_val, &white_val, pins)
             }
         }
 
-        const FIELDS: &'static [&'static str] = &["black", "white"];
+        const FIELDS: &[&str] = &["black", "white", "pinned"];
         deserializer.deserialize_struct("Board", FIELDS, BoardVisitor)
     }
 }
 
-fn pos_to_u8(pieces: &Vec<Value>) -> [u8; NUM_CHECKERS] {
-    //converts string positions to u8
-    debug_assert_eq!(
-        pieces.len(),
-        NUM_CHECKERS,
-        "pieces.len() == {} instead of NUM_CHECKERS",
-        pieces.len()
-    );
-    let mut arr: [u8; NUM_CHECKERS] = [0; NUM_CHECKERS];
-    for i in 0..NUM_CHECKERS {
-        arr[i] = piece_val_to_u8(&pieces[i]);
-    }
-    arr
+/// The points where each player's checkers are pinned
+#[derive(Serialize, Deserialize, Default)]
+struct Pins {
+    #[serde(default)]
+    black: Vec<u8>,
+    #[serde(default)]
+    white: Vec<u8>,
+}
+
+#[inline]
+fn pin_side(player: &PlayerColor) -> usize {
+    match player {
+        PlayerColor::Black => 0,
+        PlayerColor::White => 1,
+    }
+}
+
+fn from_values<E: de::Error>(black: &[Value], white: &[Value], pins: Option<Pins>) -> Result<Board, E> {
+    //! a board with as many checkers as each side lists, which must agree
+    if black.len() != white.len() || !(1..=MAX_CHECKERS).contains(&black.len()) {
+        return Err(E::custom(format!("expected 1 to {} checkers for both players, got {} black and {} white",
+                                     MAX_CHECKERS, black.len(), white.len())));
+    }
+    let black: Vec<u8> = black.iter().map(piece_val_to_u8).collect();
+    let white: Vec<u8> = white.iter().map(piece_val_to_u8).collect();
+    let mut board = Board::with_checkers(&black, &white);
+    let pins = pins.unwrap_or_default();
+    for (player, points) in [(PlayerColor::Black, pins.black), (PlayerColor::White, pins.white)] {
+        for point in points {
+            if point == BAR || point >= HOME || board.count_occurrences(&player, &point) != 1 {
+                return Err(E::custom(format!("a pinned checker must be alone on a point, not {}", point)));
+            }
+            board.set_pinned(&player, point, true);
+        }
+    }
+    Ok(board)
 }
 
 #[cfg(test)]
@@ -289,15 +493,15 @@ mod tests {
 
     #[test]
     fn special_bops() {
-        let mut board = Board {
-            black: [0,1,2,2,2,2,6,6,6,6,8,15,15,20,22],
-            white: [3,4,4,4,9,12,14,16,17,19,21,23,23,23,23],
-        };
+        let mut board = Board::from_positions(
+            [0,1,2,2,2,2,6,6,6,6,8,15,15,20,22],
+            [3,4,4,4,9,12,14,16,17,19,21,23,23,23,23],
+        );
 
-        let fin_board = Board {
-            black: [0,0,1,2,2,2,2,6,6,6,6,8,15,15,20],
-            white: [3,4,4,4,9,12,14,16,17,19,22,23,23,23,23],
-        };
+        let fin_board = Board::from_positions(
+            [0,0,1,2,2,2,2,6,6,6,6,8,15,15,20],
+            [3,4,4,4,9,12,14,16,17,19,22,23,23,23,23],
+        );
 
         let moves: Vec<Move> = vec!(
             Move { start: 21, end: 22},
@@ -309,6 +513,21 @@ mod tests {
 
         assert_eq!(board.black, fin_board.black);
         assert_eq!(board.white, fin_board.white);
+        assert!(board == fin_board);
+    }
+
+    #[test]
+    fn no_bop_home() {
+        let mut board = Board::from_positions(
+            [1,1,2,2,2,3,3,4,5,5,6,6,6,6,25],
+            [19,19,20,20,20,21,21,22,22,23,23,24,24,24,24],
+        );
+        // black has borne one checker off, white bearing off mustn't send it to the bar
+        let undo = board.make_move(&PlayerColor::White, &Move { start: 24, end: 25 });
+        assert!(!undo.hit);
+        assert_eq!(board.black, [1,1,2,2,2,3,3,4,5,5,6,6,6,6,25]);
+        assert_eq!(board.white, [19,19,20,20,20,21,21,22,22,23,23,24,24,24,25]);
+        assert_eq!(board.zobrist_hash(), hash_board(&board.black, &board.white));
     }
 
     #[test]
@@ -319,6 +538,18 @@ mod tests {
         assert_eq!(board.white, [1,1,12,12,12,12,12,17,17,17,19,19,19,19,19]);
     }
 
+    #[test]
+    fn pips() {
+        let board = Board::new();
+        assert_eq!(board.pip_count(&PlayerColor::Black), 167);
+        assert_eq!(board.pip_count(&PlayerColor::White), 167);
+        let board = Board::from_positions(
+            [BAR, 1, 2, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME],
+            [20, 24, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME]);
+        assert_eq!(board.pip_count(&PlayerColor::Black), 28);
+        assert_eq!(board.pip_count(&PlayerColor::White), 6);
+    }
+
     #[test]
     fn count() {
         let board = DEFAULT_BOARD;
@@ -335,4 +566,154 @@ mod tests {
         assert_eq!(board.count_occurrences(&PlayerColor::White, &23), 0);
         assert_eq!(board.count_occurrences(&PlayerColor::White,  &6), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn incremental_hash() {
+        let mut board = Board::new();
+        assert_eq!(board.zobrist_hash(), hash_board(&board.black, &board.white));
+
+        let moves: Vec<(PlayerColor, Move)> = vec!(
+            (PlayerColor::White, Move { start: 1, end: 7 }),
+            (PlayerColor::Black, Move { start: 8, end: 7 }),
+            (PlayerColor::White, Move { start: BAR, end: 5 }),
+            (PlayerColor::Black, Move { start: 6, end: 5 }),
+            (PlayerColor::Black, Move { start: 6, end: 6 }),
+            (PlayerColor::White, Move { start: 19, end: 25 }),
+        );
+        for (color, mve) in moves.iter() {
+            board.make_move(color, mve);
+            assert_eq!(board.zobrist_hash(), hash_board(&board.black, &board.white));
+        }
+
+        board.move_no_bop(&PlayerColor::Black, &Move { start: 24, end: 19 });
+        assert_eq!(board.zobrist_hash(), hash_board(&board.black, &board.white));
+    }
+
+    #[test]
+    fn transpositions_equal() {
+        let mut board_1 = Board::new();
+        board_1.make_move(&PlayerColor::Black, &Move { start: 13, end: 10 });
+        board_1.make_move(&PlayerColor::Black, &Move { start: 10, end: 8 });
+
+        let mut board_2 = Board::new();
+        board_2.make_move(&PlayerColor::Black, &Move { start: 13, end: 11 });
+        board_2.make_move(&PlayerColor::Black, &Move { start: 11, end: 8 });
+
+        assert!(board_1 == board_2);
+        assert_eq!(board_1.zobrist_hash(), board_2.zobrist_hash());
+        assert_ne!(board_1.zobrist_hash(), Board::new().zobrist_hash());
+        assert_ne!(board_1.position_key(&PlayerColor::Black), board_1.position_key(&PlayerColor::White));
+    }
+
+    #[test]
+    fn rehash_after_edit() {
+        let mut board = Board::new();
+        board.black[0] = 5;
+        assert_ne!(board.zobrist_hash(), hash_board(&board.black, &board.white));
+        board.rehash();
+        assert_eq!(board.zobrist_hash(), hash_board(&board.black, &board.white));
+    }
+
+    #[test]
+    fn equal_without_rehash() {
+        let mut board = Board::new();
+        board.black[0] = 5;
+        let mut rehashed = board.clone();
+        rehashed.rehash();
+        assert!(board == rehashed);
+        let hash = |board: &Board| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            board.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&board), hash(&rehashed));
+    }
+
+    #[test]
+    fn hash_pins_and_checkers() {
+        let mut board = Board::from_positions([1, 2, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+                                              [15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 24, 24, 24, 24, 24]);
+        let unpinned = board.zobrist_hash();
+        board.set_pinned(&PlayerColor::Black, 1, true);
+        assert_ne!(board.zobrist_hash(), unpinned);
+        board.set_pinned(&PlayerColor::Black, 1, true);
+        let pinned = board.zobrist_hash();
+        board.rehash();
+        assert_eq!(board.zobrist_hash(), pinned);
+        board.set_pinned(&PlayerColor::Black, 1, false);
+        assert_eq!(board.zobrist_hash(), unpinned);
+
+        // three checkers each, or a standard game with the other twelve borne off
+        let hyper = Board::with_checkers(&[22, 23, 24], &[1, 2, 3]);
+        let standard = Board::from_positions(hyper.black, hyper.white);
+        assert_ne!(hyper.zobrist_hash(), standard.zobrist_hash());
+        let mut rehashed = hyper.clone();
+        rehashed.rehash();
+        assert_eq!(rehashed.zobrist_hash(), hyper.zobrist_hash());
+    }
+
+    #[test]
+    fn few_checkers() {
+        let mut board = Board::with_checkers(&[24, 22, 23], &[1, 2, 3]);
+        assert_eq!(board.num_checkers(), 3);
+        assert_eq!(board.black[..4], [22, 23, 24, HOME]);
+        let json = serde_json::json!(board);
+        assert_eq!(json, serde_json::json!({ "black": [22, 23, 24], "white": [1, 2, 3] }));
+        assert_eq!(serde_json::from_value::<Board>(json).unwrap(), board);
+        assert!(serde_json::from_value::<Board>(serde_json::json!({ "black": [22], "white": [1, 2] })).is_err());
+        // unlike the padding, borne off checkers are still sent
+        board.make_move(&PlayerColor::White, &Move { start: 3, end: 22 });
+        assert_eq!(board.count_occurrences(&PlayerColor::Black, &BAR), 1);
+        board.make_move(&PlayerColor::Black, &Move { start: 23, end: HOME });
+        assert_eq!(serde_json::json!(board)["black"], serde_json::json!(["bar", 24, "home"]));
+        assert_ne!(board, Board::from_positions(board.black, board.white));
+    }
+
+    #[test]
+    fn pinned() {
+        let mut board = Board::with_checkers(&[5, 24], &[5, 6]);
+        assert_eq!(serde_json::json!(board).get("pinned"), None);
+        board.set_pinned(&PlayerColor::White, 5, true);
+        assert!(board.is_pinned(&PlayerColor::White, 5) && !board.is_pinned(&PlayerColor::Black, 5));
+        let json = serde_json::json!(board);
+        assert_eq!(json["pinned"], serde_json::json!({ "black": [], "white": [5] }));
+        assert_eq!(serde_json::from_value::<Board>(json).unwrap(), board);
+        assert_ne!(board, Board::with_checkers(&[5, 24], &[5, 6]));
+        // only a lone checker can be pinned
+        let stacked = serde_json::json!({ "black": [24, 24], "white": [1, 1], "pinned": { "white": [1] } });
+        assert!(serde_json::from_value::<Board>(stacked).is_err());
+    }
+
+    #[test]
+    fn unmake() {
+        let mut board = Board::new();
+        let mut played = Vec::new();
+        let turns = [
+            (PlayerColor::White, Move { start: 1, end: 7 }),
+            (PlayerColor::Black, Move { start: 13, end: 7 }),
+            (PlayerColor::White, Move { start: BAR, end: 3 }),
+            (PlayerColor::Black, Move { start: 6, end: 3 }),
+            (PlayerColor::Black, Move { start: 8, end: 7 }),
+        ];
+        for (player, mve) in turns.iter() {
+            played.push((player, board.clone(), board.make_move(player, mve)));
+        }
+        assert_eq!(played.iter().map(|(_, _, undo)| undo.hit).collect::<Vec<bool>>(), vec!(false, true, false, true, false));
+        for (player, before, undo) in played.iter().rev() {
+            board.unmake_move(player, undo);
+            assert!(board == *before);
+            assert_eq!(board.zobrist_hash(), hash_board(&before.black, &before.white));
+        }
+
+        let mut home = Board::from_positions([1; MAX_CHECKERS], [24; MAX_CHECKERS]);
+        let undo = home.make_move(&PlayerColor::White, &Move { start: 24, end: HOME });
+        home.unmake_move(&PlayerColor::White, &undo);
+        assert!(home == Board::from_positions([1; MAX_CHECKERS], [24; MAX_CHECKERS]));
+    }
+
+    #[test]
+    #[should_panic(expected = "no White checker on 2 to move")]
+    fn move_from_empty_point() {
+        Board::new().move_no_bop(&PlayerColor::White, &Move { start: 2, end: 4 });
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{
+    tcp_handler::TcpHandler,
+    ws_handler::WsHandler,
+};
+
+/**
+A connection carrying one JSON message at a time, independent of the transport.
+Players and spectators talk over either newline-delimited TCP or WebSocket text frames.
+*/
+pub trait MessageChannel: Send {
+    /// reads the next message, a message that isn't JSON is an error but leaves the connection open
+    fn recv(&mut self) -> Result<Value, RecvError>;
+    fn send(&mut self, val: &Value) -> io::Result<()>;
+    /// None waits forever, otherwise recv gives up with RecvError::Io after timeout
+    fn set_read_timeout(&mut self, timeout: Option<Duration>);
+    /// another handle to the same connection
+    fn duplicate(&self) -> Box<dyn MessageChannel>;
+}
+
+/// Why MessageChannel::recv didn't give a message
+#[derive(Debug)]
+pub enum RecvError {
+    /// the peer sent something that isn't JSON, kept as it was sent
+    Malformed(String),
+    /// the peer closed the connection
+    Closed,
+    /// the connection failed or the read timed out
+    Io(io::Error),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecvError::Malformed(text) => write!(f, "not JSON: {}", text),
+            RecvError::Closed => write!(f, "connection closed"),
+            RecvError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transport {
+    /// one JSON value per line
+    Tcp,
+    /// one JSON value per text frame, for browser clients
+    WebSocket,
+}
+
+impl Transport {
+    pub fn wrap(&self, stream: TcpStream) -> io::Result<Box<dyn MessageChannel>> {
+        //! turns a freshly accepted connection into a channel, WebSockets complete their handshake here
+        match self {
+            Transport::Tcp => Ok(Box::new(TcpHandler::new(stream))),
+            Transport::WebSocket => Ok(Box::new(WsHandler::accept(stream)?)),
+        }
+    }
+}
+
+/// A TcpListener whose connections speak the given transport
+pub struct ChannelListener {
+    listener: TcpListener,
+    transport: Transport,
+}
+
+impl From<TcpListener> for ChannelListener {
+    fn from(listener: TcpListener) -> ChannelListener {
+        ChannelListener::new(listener, Transport::Tcp)
+    }
+}
+
+impl ChannelListener {
+    pub fn new(listener: TcpListener, transport: Transport) -> ChannelListener {
+        ChannelListener { listener, transport }
+    }
+
+    pub fn accept(&self) -> io::Result<Box<dyn MessageChannel>> {
+        let (stream, _) = self.listener.accept()?;
+        self.transport.wrap(stream)
+    }
+
+    pub fn incoming(&self) -> impl Iterator<Item = io::Result<TcpStream>> + '_ {
+        //! the raw connections, wrap them with transport() once off the listening thread
+        self.listener.incoming()
+    }
+
+    #[inline]
+    pub fn transport(&self) -> Transport { self.transport }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn try_clone(&self) -> io::Result<ChannelListener> {
+        Ok(ChannelListener::new(self.listener.try_clone()?, self.transport))
+    }
+}
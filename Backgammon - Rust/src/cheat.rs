@@ -0,0 +1,114 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::{
+    player::PlayerColor,
+    r#move::Move,
+    turn_error::TurnError,
+};
+
+/// how many legal turns an illegal turn's reason lists
+pub const MAX_ALTERNATIVES: usize = 5;
+
+/// Why a player was judged to have cheated, with what's needed to settle a dispute
+#[derive(Clone, PartialEq, Debug)]
+pub enum CheatReason {
+    /// didn't answer request within the response timeout
+    Timeout { request: Value },
+    /// dropped the connection while asked request, and didn't reconnect in time
+    Disconnected { request: Value },
+    /// answered request with something that isn't the expected message
+    Malformed { request: Value, response: Value },
+    /// answered start-game or end-game with something other than "okay"
+    BadAck { request: Value, response: Value },
+    /// legal holds up to MAX_ALTERNATIVES turns that could have been played instead, closest first
+    IllegalTurn { dice: Vec<u8>, moves: Vec<Move>, error: TurnError, legal: Vec<Vec<Move>> },
+    /// the player doesn't keep track of why
+    Unexplained,
+}
+
+impl CheatReason {
+    pub fn to_json(&self) -> Value {
+        let turn = |moves: &[Move]| -> Vec<Value> { moves.iter().map(|mve| mve.to_json()).collect() };
+        match self {
+            CheatReason::Timeout { request } => json!({ "reason": "timeout", "request": request }),
+            CheatReason::Disconnected { request } => json!({ "reason": "disconnected", "request": request }),
+            CheatReason::Malformed { request, response } => json!({
+                "reason": "malformed", "request": request, "response": response,
+            }),
+            CheatReason::BadAck { request, response } => json!({
+                "reason": "bad-ack", "request": request, "response": response,
+            }),
+            CheatReason::IllegalTurn { dice, moves, error, legal } => json!({
+                "reason": "illegal-turn", "dice": dice, "turn": turn(moves), "error": error.to_string(),
+                "legal": legal.iter().map(|moves| turn(moves)).collect::<Vec<_>>(),
+            }),
+            CheatReason::Unexplained => json!({ "reason": "unexplained" }),
+        }
+    }
+}
+
+impl fmt::Display for CheatReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheatReason::Timeout { request } => write!(f, "didn't answer {} in time", describe(request)),
+            CheatReason::Disconnected { request } => write!(f, "disconnected when asked {}", describe(request)),
+            CheatReason::Malformed { request, response } => write!(f, "answered {} with {}", describe(request), response),
+            CheatReason::BadAck { request, response } => {
+                write!(f, "answered {} with {} instead of \"okay\"", describe(request), response)
+            }
+            CheatReason::IllegalTurn { dice, moves, error, .. } => {
+                write!(f, "played {} with {:?}: {}", json!(moves.iter().map(|mve| mve.to_json()).collect::<Vec<_>>()), dice, error)
+            }
+            CheatReason::Unexplained => write!(f, "cheated"),
+        }
+    }
+}
+
+fn describe(request: &Value) -> String {
+    //! names a request by its key, since take-turn requests hold a whole board
+    match request {
+        Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// A player caught cheating during a game
+#[derive(Clone, Debug)]
+pub struct Infraction {
+    pub name: String,
+    pub color: PlayerColor,
+    pub reason: CheatReason,
+}
+
+impl Infraction {
+    pub fn to_json(&self) -> Value {
+        json!({ "name": self.name, "color": self.color, "cheat": self.reason.to_json() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::turn_error::MoveError;
+
+    #[test]
+    fn describe_reasons() {
+        let request = json!({ "take-turn": [[], [1, 2]] });
+        assert_eq!(CheatReason::Timeout { request: request.clone() }.to_string(), "didn't answer take-turn in time");
+        let ack = CheatReason::BadAck { request: json!({ "end-game": [] }), response: json!("nope") };
+        assert_eq!(ack.to_string(), "answered end-game with \"nope\" instead of \"okay\"");
+        assert_eq!(ack.to_json(), json!({ "reason": "bad-ack", "request": { "end-game": [] }, "response": "nope" }));
+        let illegal = CheatReason::IllegalTurn {
+            dice: vec!(1, 2),
+            moves: vec!(Move { start: 1, end: 4 }),
+            error: TurnError::Move { index: 1, error: MoveError::NoSuchDie },
+            legal: vec!(vec!(Move { start: 1, end: 2 }, Move { start: 2, end: 4 })),
+        };
+        assert_eq!(illegal.to_json()["legal"], json!([[[1, 2], [2, 4]]]));
+        assert_eq!(illegal.to_json()["error"], "move 1 doesn't match an unused die");
+        assert_eq!(illegal.to_string(), "played [[1,4]] with [1, 2]: move 1 doesn't match an unused die");
+    }
+}
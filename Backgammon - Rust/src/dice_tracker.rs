@@ -17,7 +17,7 @@ regardless of which die is used.
 */
 
 impl DiceTracker {
-    pub fn new(dice: &Vec<u8>) -> DiceTracker {
+    pub fn new(dice: &[u8]) -> DiceTracker {
         //! creates tracker with dice.len() LSBs set
         debug_assert!(dice.len() == 2 || dice.len() == 4, "dice is not valid length");
         if dice.len() == 2 {
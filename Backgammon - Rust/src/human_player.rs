@@ -0,0 +1,321 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    analysis::analyze_with_rules,
+    board::Board,
+    local_remote::IPlayer,
+    notation::{format_move, format_play, parse_turn, world_point},
+    player::{Player, PlayerColor, PlayerName},
+    player_board::PlayerBoard,
+    r#mod::{BAR, HOME},
+    r#move::Move,
+    rules::{any_rules::AnyRules, base::Rules},
+    start_position::StartPosition,
+    strategy::{rando::Rando, smarty::Smarty},
+    turn_tree::TurnNode,
+};
+
+const NUM_HINTS: usize = 3;
+const STACK_HEIGHT: u8 = 5;
+
+const HELP: &str = "enter moves from your point of view, e.g. '24/18 13/11', 'bar/22/16', '8/4(2)' or '6/off'
+  undo    take back your last move
+  hint    show the best turns
+  board   show the board again
+  done    submit your turn
+  quit    forfeit the game";
+
+/// Player controlled from the terminal
+pub struct HumanPlayer {
+    player: Player<Rando>,
+    /// used to suggest turns
+    hints: Smarty,
+    quit: bool,
+}
+
+impl HumanPlayer {
+    pub fn new(name: String) -> HumanPlayer {
+        HumanPlayer {
+            player: Player::new(name, Rando),
+            hints: Smarty::new(),
+            quit: false,
+        }
+    }
+
+    pub fn read_turn<R: BufRead, W: Write>(&self, input: &mut R, output: &mut W, board: &Board, dice: &Vec<u8>) -> io::Result<Option<Vec<Move>>> {
+        /*!
+        prompts for moves until a complete legal turn is submitted.
+        Returns None if the player quits or input ends.
+        */
+        let color = self.player.color();
+        let rules = self.player.rules();
+        let pl_board = PlayerBoard::new(&color, board, &self.hints, rules);
+        let turns = pl_board.generate_valid_turns(dice);
+        let turn_len = turns.first().map_or(0, |t| t.get_max_depth() as usize);
+
+        let mut pending: Vec<Move> = Vec::with_capacity(turn_len);
+        let mut cur_board = board.clone();
+        writeln!(output, "{}", render_board(&cur_board, &color))?;
+        writeln!(output, "you rolled {:?}", dice)?;
+        if turns.is_empty() {
+            writeln!(output, "no legal moves, enter 'done' to pass")?;
+        }
+
+        let mut line = String::new();
+        loop {
+            write!(output, "[{}] > ", format_play(board, &pending, &color))?;
+            output.flush()?;
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+
+            match line.trim().to_lowercase().as_str() {
+                "help" | "?" => writeln!(output, "{}", HELP)?,
+                "board" => writeln!(output, "{}", render_board(&cur_board, &color))?,
+                "quit" => return Ok(None),
+                "undo" => {
+                    if pending.pop().is_none() {
+                        writeln!(output, "nothing to undo")?;
+                        continue;
+                    }
+                    cur_board = board.clone();
+                    for mve in pending.iter() {
+                        rules.make_move(&mut cur_board, &color, mve);
+                    }
+                    writeln!(output, "{}", render_board(&cur_board, &color))?;
+                }
+                "hint" => {
+                    let hints = analyze_with_rules(board, &color, dice, &self.hints, rules);
+                    let matching = hints.iter().filter(|h| h.moves.starts_with(&pending));
+                    for hint in matching.take(NUM_HINTS) {
+                        writeln!(output, "  {}. {} (score {})", hint.rank, format_play(board, &hint.moves, &color), hint.score)?;
+                    }
+                }
+                "done" | "submit" => {
+                    if pending.len() == turn_len {
+                        return Ok(Some(pending));
+                    }
+                    writeln!(output, "your turn isn't finished, {} more move(s) must be played", turn_len - pending.len())?;
+                }
+                text => {
+                    let moves = match parse_turn(text, &color) {
+                        Ok(moves) => moves,
+                        Err(e) => {
+                            writeln!(output, "{}, enter 'help' for instructions", e)?;
+                            continue;
+                        }
+                    };
+                    for mve in moves.iter() {
+                        if pending.len() == turn_len {
+                            writeln!(output, "your turn is already complete, {} was not played", format_move(mve, &color))?;
+                            break;
+                        }
+                        let options = branches_after(&turns, &pending);
+                        if !options.iter().any(|node| node.get_move() == mve) {
+                            writeln!(output, "{} is not legal here, legal moves are: {}",
+                                     format_move(mve, &color), format_options(options, &color))?;
+                            break;
+                        }
+                        rules.make_move(&mut cur_board, &color, mve);
+                        pending.push(mve.clone());
+                    }
+                    writeln!(output, "{}", render_board(&cur_board, &color))?;
+                    if pending.len() == turn_len {
+                        writeln!(output, "turn complete, enter 'done' to submit or 'undo' to change it")?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl IPlayer for HumanPlayer {
+    fn get_turn(&mut self, board: &Board, dice: &Vec<u8>) -> Vec<Move> {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        let mut output = io::stdout();
+        match self.read_turn(&mut input, &mut output, board, dice) {
+            Ok(Some(moves)) => moves,
+            _ => {
+                self.quit = true;
+                vec!()
+            }
+        }
+    }
+
+    fn get_name(&mut self) -> PlayerName { self.player.get_name().clone() }
+
+    fn validate_turn(&mut self, _board: &Board, _dice: &Vec<u8>, _moves: &Vec<Move>) -> bool {
+        // turns are checked against the legal turn tree as they are entered
+        !self.quit
+    }
+
+    fn start_game(&mut self, color: PlayerColor, opp_name: String) -> bool {
+        println!("starting game against {}, you are {:?} (X)", opp_name, color);
+        self.player.start_game(color, opp_name).is_ok()
+    }
+
+    fn start_game_from(&mut self, color: PlayerColor, opp_name: String, _start: &StartPosition, rules: AnyRules) -> bool {
+        if rules != AnyRules::default() {
+            println!("playing {}", rules);
+        }
+        self.player.set_rules(rules);
+        self.start_game(color, opp_name)
+    }
+
+    fn end_game(&mut self, board: &Board, won: bool) -> bool {
+        println!("{}", render_board(board, &self.player.color()));
+        println!("{}", if won { "you won!" } else { "you lost" });
+        self.player.end_game(won);
+        true
+    }
+
+    fn has_cheated(&self) -> bool { self.quit }
+
+    fn get_color(&self) -> PlayerColor {
+        self.player.color()
+    }
+
+    fn duplicate(&self) -> Box<dyn IPlayer> {
+        Box::new(HumanPlayer { player: self.player.clone(), hints: self.hints.clone(), quit: self.quit })
+    }
+}
+
+fn branches_after<'a>(turns: &'a [Box<TurnNode>], pending: &[Move]) -> &'a [Box<TurnNode>] {
+    //! returns the moves available after pending, which must be a legal partial turn
+    let mut nodes = turns;
+    for mve in pending.iter() {
+        let node = nodes.iter().find(|node| node.get_move() == mve).expect("pending moves must be legal");
+        nodes = node.get_branches();
+    }
+    nodes
+}
+
+fn format_options(options: &[Box<TurnNode>], color: &PlayerColor) -> String {
+    let mut formatted: Vec<String> = Vec::with_capacity(options.len());
+    for node in options.iter() {
+        let text = format_move(node.get_move(), color);
+        if !formatted.contains(&text) {
+            formatted.push(text);
+        }
+    }
+    formatted.join(", ")
+}
+
+pub fn render_board(board: &Board, color: &PlayerColor) -> String {
+    //! draws the board from color's point of view, X is color and O is the opponent
+    let opp_color = match color {
+        PlayerColor::Black => PlayerColor::White,
+        PlayerColor::White => PlayerColor::Black,
+    };
+    let cell = |point: u8, row: u8| -> String {
+        let world = world_point(point, color);
+        let mine = board.count_occurrences(color, &world);
+        let theirs = board.count_occurrences(&opp_color, &world);
+        let (count, symbol) = if mine > 0 { (mine, 'X') } else { (theirs, 'O') };
+        if count <= row {
+            "   ".to_string()
+        } else if row == STACK_HEIGHT - 1 && count > STACK_HEIGHT {
+            format!("{:>2} ", count)
+        } else {
+            format!(" {} ", symbol)
+        }
+    };
+    let numbers = |points: &[u8]| -> String {
+        points.iter().map(|p| format!("{:>3}", p)).collect()
+    };
+    let top_left: Vec<u8> = (13..19).collect();
+    let top_right: Vec<u8> = (19..25).collect();
+    let bottom_left: Vec<u8> = (7..13).rev().collect();
+    let bottom_right: Vec<u8> = (1..7).rev().collect();
+
+    let mut out = String::new();
+    out.push_str(&format!(" {} {}\n", numbers(&top_left), numbers(&top_right)));
+    out.push_str("+------------------+------------------+\n");
+    for row in 0..STACK_HEIGHT {
+        let left: String = top_left.iter().map(|p| cell(*p, row)).collect();
+        let right: String = top_right.iter().map(|p| cell(*p, row)).collect();
+        out.push_str(&format!("|{}|{}|\n", left, right));
+    }
+    out.push_str("|                  |                  |\n");
+    for row in (0..STACK_HEIGHT).rev() {
+        let left: String = bottom_left.iter().map(|p| cell(*p, row)).collect();
+        let right: String = bottom_right.iter().map(|p| cell(*p, row)).collect();
+        out.push_str(&format!("|{}|{}|\n", left, right));
+    }
+    out.push_str("+------------------+------------------+\n");
+    out.push_str(&format!(" {} {}\n", numbers(&bottom_left), numbers(&bottom_right)));
+    out.push_str(&format!("bar: X {} O {}   off: X {} O {}",
+                          board.count_occurrences(color, &BAR), board.count_occurrences(&opp_color, &BAR),
+                          board.count_occurrences(color, &HOME), board.count_occurrences(&opp_color, &HOME)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn run_input(color: PlayerColor, board: &Board, dice: &Vec<u8>, input: &str) -> (Option<Vec<Move>>, String) {
+        let mut human = HumanPlayer::new("human".to_string());
+        human.start_game(color, "opp".to_string());
+        let mut output: Vec<u8> = Vec::new();
+        let result = human.read_turn(&mut Cursor::new(input), &mut output, board, dice).unwrap();
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn legal_turn() {
+        let board = Board::new();
+        let (moves, _) = run_input(PlayerColor::Black, &board, &vec!(6, 2), "24/18 13/11\ndone\n");
+        assert_eq!(moves.unwrap(), vec!(Move { start: 24, end: 18 }, Move { start: 13, end: 11 }));
+
+        let (moves, _) = run_input(PlayerColor::White, &board, &vec!(6, 2), "24/18\n13/11\ndone\n");
+        assert_eq!(moves.unwrap(), vec!(Move { start: 1, end: 7 }, Move { start: 12, end: 14 }));
+    }
+
+    #[test]
+    fn illegal_then_undo() {
+        let board = Board::new();
+        let input = "24/17\n24/18\ndone\nundo\n13/7\n13/11\ndone\n";
+        let (moves, output) = run_input(PlayerColor::Black, &board, &vec!(6, 2), input);
+        assert!(output.contains("24/17 is not legal here"));
+        assert!(output.contains("1 more move(s) must be played"));
+        assert_eq!(moves.unwrap(), vec!(Move { start: 13, end: 7 }, Move { start: 13, end: 11 }));
+    }
+
+    #[test]
+    fn errors_and_hints() {
+        let board = Board::new();
+        let (moves, output) = run_input(PlayerColor::Black, &board, &vec!(3, 1), "8-5\nhint\nquit\n");
+        assert!(moves.is_none());
+        assert!(output.contains("'8-5' is not a move"));
+        assert!(output.contains("  1. "));
+
+        let (moves, _) = run_input(PlayerColor::Black, &board, &vec!(3, 1), "8/5");
+        assert!(moves.is_none());
+    }
+
+    #[test]
+    fn pass() {
+        let board = Board::from_positions(
+            [0, 0, 3, 6, 9, 9, 12, 12, 13, 13, 13, 23, 23, 24, 24],
+            [5, 10, 11, 14, 14, 16, 16, 17, 18, 19, 20, 20, 21, 21, 22]);
+        let (moves, output) = run_input(PlayerColor::Black, &board, &vec!(4, 5), "done\n");
+        assert!(output.contains("no legal moves"));
+        assert_eq!(moves.unwrap(), vec!());
+    }
+
+    #[test]
+    fn render() {
+        let board = Board::new();
+        let black_view = render_board(&board, &PlayerColor::Black);
+        let white_view = render_board(&board, &PlayerColor::White);
+        // each player sees the same starting position
+        assert_eq!(black_view, white_view);
+        assert!(black_view.starts_with("  13 14 15 16 17 18  19 20 21 22 23 24\n"));
+        assert!(black_view.contains("| X           O    | O              X |\n"));
+        assert!(black_view.ends_with("bar: X 0 O 0   off: X 0 O 0"));
+    }
+}
@@ -7,19 +7,47 @@ mod r#mod;
 pub mod net_config;
 pub mod network_player;
 pub mod tcp_handler;
+pub mod ws_handler;
+pub mod channel;
 pub mod administrator;
 pub mod local_remote;
 mod turn_tree;
 mod dice_tracker;
+pub mod zobrist;
+pub mod transposition;
+pub mod simulation;
+pub mod analysis;
+pub mod notation;
+pub mod human_player;
+pub mod spectator;
+pub mod observer;
+pub mod session;
+pub mod protocol;
+pub mod record;
+pub mod log;
+pub mod cheat;
+pub mod turn_error;
+pub mod start_position;
 
 pub mod strategy {
     pub mod rando;
     pub mod bopsy;
     pub mod smarty;
+    pub mod any_strat;
+    pub mod tuning;
+}
+
+pub mod rules {
+    pub mod base;
+    pub mod standard;
+    pub mod plakoto;
+    pub mod fevga;
+    pub mod any_rules;
 }
 
 pub mod tournament {
     pub mod tournament;
+    pub mod config;
     mod round_robin;
     mod single_elim;
 }
@@ -1,4 +1,6 @@
 use std::net::TcpStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use serde::{Serialize, Serializer};
 use serde::ser::SerializeStruct;
@@ -6,22 +8,43 @@ use serde_json::{json, Value};
 
 use crate::{
     board::Board,
+    channel::{MessageChannel, RecvError},
+    cheat::{CheatReason, MAX_ALTERNATIVES},
     network_player::TcpTurn,
     parser::get_moves,
     player::{Player, PlayerColor, PlayerName},
     player_board::PlayerStrat,
+    protocol::{Capability, Protocol},
     r#move::Move,
+    rules::{any_rules::AnyRules, base::Rules},
+    session::SessionRegistry,
+    start_position::StartPosition,
     strategy::rando::Rando,
     tcp_handler::TcpHandler,
 };
 
-pub trait IPlayer {
+pub trait IPlayer: Send {
     fn get_turn(&mut self, board: &Board, dice: &Vec<u8>) -> Vec<Move>;
     fn get_name(&mut self) -> PlayerName;
     fn validate_turn(&mut self, board: &Board, dice: &Vec<u8>, moves: &Vec<Move>) -> bool;
     fn start_game(&mut self, color: PlayerColor, opp_name: String) -> bool;
+    fn start_game_from(&mut self, color: PlayerColor, opp_name: String, _start: &StartPosition, _rules: AnyRules) -> bool {
+        /*!
+        starts a game that may not begin from the standard setup, every turn still comes with the board.
+        Players that can only play backgammon ignore the rules
+        */
+        self.start_game(color, opp_name)
+    }
+    fn resume_game(&mut self, color: PlayerColor, opp_name: String, _board: &Board) -> bool {
+        //! joins a game already underway at board, such as in place of a cheater
+        self.start_game(color, opp_name)
+    }
     fn end_game(&mut self, board: &Board, won: bool) -> bool;
     fn has_cheated(&self) -> bool;
+    fn cheat_reason(&self) -> Option<CheatReason> {
+        //! why the player cheated, for players that don't keep track it is Unexplained
+        if self.has_cheated() { Some(CheatReason::Unexplained) } else { None }
+    }
     fn get_color(&self) -> PlayerColor;
     fn duplicate(&self) -> Box<dyn IPlayer>;
 }
@@ -30,7 +53,7 @@ pub struct LocalPlayer<S: PlayerStrat> {
     player: Player<S>,
 }
 
-impl<S: PlayerStrat> IPlayer for LocalPlayer<S> {
+impl<S: PlayerStrat + Send> IPlayer for LocalPlayer<S> {
     fn get_turn(&mut self, board: &Board, dice: &Vec<u8>) -> Vec<Move> {
         self.player.get_turn(&board, &dice)
     }
@@ -47,6 +70,11 @@ impl<S: PlayerStrat> IPlayer for LocalPlayer<S> {
         } else { false }
     }
 
+    fn start_game_from(&mut self, color: PlayerColor, opp_name: String, _start: &StartPosition, rules: AnyRules) -> bool {
+        self.player.set_rules(rules);
+        self.start_game(color, opp_name)
+    }
+
     fn end_game(&mut self, _board: &Board, won: bool) -> bool {
         self.player.end_game(won);
         true
@@ -69,23 +97,47 @@ impl<S: PlayerStrat> LocalPlayer<S> {
             player: Player::new(name, strategy)
         }
     }
+
+    pub fn with_rules(mut self, rules: AnyRules) -> LocalPlayer<S> {
+        self.player.set_rules(rules);
+        self
+    }
 }
 
+/// source of RemotePlayer connection ids
+static NEXT_CONNECTION: AtomicU64 = AtomicU64::new(1);
+
 pub struct RemotePlayer {
     player: Player<Rando>,
-    stream: TcpHandler,
-    cheated: bool,
+    /// unique to each connection, shared by duplicates
+    id: u64,
+    stream: Box<dyn MessageChannel>,
+    /// the first way the player cheated
+    cheated: Option<CheatReason>,
     given_name: bool,
+    /// None if the player can't reconnect
+    sessions: Option<SessionRegistry>,
+    token: Option<String>,
+    /// agreed with the client before asking its name
+    protocol: Option<Protocol>,
+    /// a player slower than this to answer is treated as a cheater
+    response_timeout: Option<Duration>,
 }
 
 impl IPlayer for RemotePlayer {
     fn get_turn(&mut self, board: &Board, dice: &Vec<u8>) -> Vec<Move> {
-        self.stream.write(&TakeTurn::get_message(&board, &dice));
-        let response = self.stream.read_line();
-        if let Ok(x) = serde_json::from_value::<TcpTurn>(response) {
+        let request = TakeTurn::get_message(board, dice);
+        let response = match self.request(&request) {
+            Ok(response) => response,
+            Err(reason) => {
+                self.cheat(reason);
+                return vec!();
+            }
+        };
+        if let Ok(x) = serde_json::from_value::<TcpTurn>(response.clone()) {
             return get_moves(&x.turn);
         }
-        self.cheated = true;
+        self.cheat(CheatReason::Malformed { request, response });
         vec!()
     }
 
@@ -93,36 +145,69 @@ impl IPlayer for RemotePlayer {
         if self.given_name {
             return self.player.get_name().clone();
         }
-        self.stream.write(&json!("name"));
-        let response = self.stream.read_line();
-        if let Ok(x) = serde_json::from_value::<PlayerName>(response) {
-            self.player.assign_name(x.clone());
-            return x;
+        self.negotiate();
+        let request = json!("name");
+        match self.request(&request) {
+            Ok(response) => {
+                if let Ok(x) = serde_json::from_value::<PlayerName>(response.clone()) {
+                    self.player.assign_name(x.clone());
+                    self.given_name = true;
+                    if self.token.is_none() {
+                        self.open_session();
+                    }
+                    return x;
+                }
+                self.cheat(CheatReason::Malformed { request, response });
+            }
+            Err(reason) => self.cheat(reason),
         }
-        self.cheated = true;
         return self.player.get_name().clone();
     }
 
     fn validate_turn(&mut self, board: &Board, dice: &Vec<u8>, moves: &Vec<Move>) -> bool {
-        if self.cheated == false {
-            self.cheated = !self.player.validate_turn(&board, &dice, &moves);
+        if self.cheated.is_some() {
+            return false;
+        }
+        if let Err(illegal) = self.player.check_turn(board, dice, moves) {
+            // the closest alternative leads
+            let mut legal = vec!(illegal.alternative.clone());
+            legal.extend(self.player.legal_turns(board, dice).into_iter()
+                .filter(|turn| *turn != illegal.alternative)
+                .take(MAX_ALTERNATIVES - 1));
+            self.cheat(CheatReason::IllegalTurn { dice: dice.clone(), moves: moves.clone(), error: illegal.error, legal });
         }
-        !self.cheated
+        self.cheated.is_none()
     }
 
     fn start_game(&mut self, color: PlayerColor, opp_name: String) -> bool {
         self.player.start_game(color.clone(), opp_name.clone()).ok();
-        self.stream.write(&StartGame::get_message(color, opp_name));
-        self.get_okay()
+        let request = StartGame::get_message(color, opp_name);
+        self.get_okay(request)
+    }
+
+    fn start_game_from(&mut self, color: PlayerColor, opp_name: String, start: &StartPosition, rules: AnyRules) -> bool {
+        //! announces the start and rules to players who understand them, legacy players only see the start in their first take-turn
+        self.player.set_rules(rules);
+        if !self.protocol().supports(Capability::Start) {
+            return self.start_game(color, opp_name);
+        }
+        self.player.start_game(color.clone(), opp_name.clone()).ok();
+        let mut request = StartGame::get_message(color, opp_name);
+        let mut announced = start.to_json();
+        announced["rules"] = json!(rules.name());
+        request["start-game"].as_array_mut().unwrap().push(announced);
+        self.get_okay(request)
     }
 
     fn end_game(&mut self, board: &Board, won: bool) -> bool {
         self.player.end_game(won);
-        self.stream.write(&EndGame::get_message(board, won));
-        self.get_okay()
+        let request = EndGame::get_message(board, won);
+        self.get_okay(request)
     }
 
-    fn has_cheated(&self) -> bool { self.cheated }
+    fn has_cheated(&self) -> bool { self.cheated.is_some() }
+
+    fn cheat_reason(&self) -> Option<CheatReason> { self.cheated.clone() }
 
     fn get_color(&self) -> PlayerColor {
         self.player.color()
@@ -131,31 +216,172 @@ impl IPlayer for RemotePlayer {
     fn duplicate(&self) -> Box<dyn IPlayer> {
         Box::new(RemotePlayer {
             player: self.player.clone(),
-            stream: self.stream.clone(),
+            id: self.id,
+            stream: self.stream.duplicate(),
             cheated: self.cheated.clone(),
             given_name: self.given_name.clone(),
+            sessions: self.sessions.clone(),
+            token: self.token.clone(),
+            protocol: self.protocol.clone(),
+            response_timeout: self.response_timeout,
         })
     }
 }
 
 impl RemotePlayer {
     pub fn new(socket: TcpStream) -> RemotePlayer {
+        RemotePlayer::from_channel(Box::new(TcpHandler::new(socket)))
+    }
+
+    pub fn from_channel(stream: Box<dyn MessageChannel>) -> RemotePlayer {
+        //! creates a player over any transport, such as a WebSocket
         RemotePlayer {
             player: Player::new("remote".to_string(), Rando),
-            stream: TcpHandler::new(socket),
-            cheated: false,
+            id: NEXT_CONNECTION.fetch_add(1, Ordering::Relaxed),
+            stream,
+            cheated: None,
             given_name: false,
+            sessions: None,
+            token: None,
+            protocol: None,
+            response_timeout: None,
         }
     }
 
-    fn get_okay(&mut self) -> bool {
-        let response = self.stream.read_line();
-        if response.is_string() {
-            self.cheated = response.as_str().unwrap() != "okay" || self.cheated;
-            return !self.cheated;
+    pub fn with_sessions(stream: Box<dyn MessageChannel>, sessions: SessionRegistry) -> RemotePlayer {
+        //! creates a player who is offered a session token, which lets them reconnect if they drop
+        RemotePlayer {
+            sessions: Some(sessions),
+            ..RemotePlayer::from_channel(stream)
         }
-        self.cheated = true;
-        false
+    }
+
+    #[inline]
+    pub fn connection_id(&self) -> u64 { self.id }
+
+    pub fn set_response_timeout(&mut self, timeout: Option<Duration>) {
+        //! None waits for answers forever
+        self.response_timeout = timeout;
+        self.stream.set_read_timeout(timeout);
+    }
+
+    pub fn rename(&mut self, name: String) {
+        //! replaces the name the player gave, such as when it is already taken
+        self.player.assign_name(PlayerName::new(name));
+        self.given_name = true;
+    }
+
+    pub fn protocol(&mut self) -> &Protocol {
+        self.negotiate();
+        self.protocol.as_ref().unwrap()
+    }
+
+    pub fn check_secret(&mut self, secret: &str) -> bool {
+        //! asks the player for the tournament's shared secret, legacy clients can't give one
+        if !self.protocol().supports(Capability::Secret) {
+            return false;
+        }
+        let response = self.request(&json!("secret")).unwrap_or(Value::Null);
+        response.as_str() == Some(secret)
+    }
+
+    pub fn reject(mut self, reason: &str) {
+        //! tells the player why they can't join, then disconnects them
+        if self.protocol().supports(Capability::Reject) {
+            self.stream.send(&json!({ "rejected": reason })).ok();
+        }
+        if let (Some(sessions), Some(token)) = (&self.sessions, &self.token) {
+            sessions.end(token);
+        }
+    }
+
+    fn negotiate(&mut self) {
+        /*!
+        sends hello once, before anything else. Clients that don't answer with
+        their own hello are legacy clients, and are never sent extended messages.
+        */
+        if self.protocol.is_some() {
+            return;
+        }
+        let ours = Protocol::current();
+        self.stream.send(&ours.hello()).ok();
+        let theirs = self.stream.recv().ok()
+            .and_then(|hello| Protocol::from_hello(&hello))
+            .unwrap_or_else(Protocol::legacy);
+        self.protocol = Some(ours.negotiate(&theirs));
+    }
+
+    fn open_session(&mut self) {
+        //! sends the player a session token, clients that don't reply "okay" can't reconnect
+        if !self.protocol().supports(Capability::Session) {
+            self.sessions = None;
+            return;
+        }
+        if let Some(sessions) = self.sessions.take() {
+            let token = sessions.issue();
+            self.stream.send(&json!({ "session": token })).ok();
+            if self.stream.recv().is_ok_and(|reply| reply == "okay") {
+                self.token = Some(token);
+                self.sessions = Some(sessions);
+            } else {
+                sessions.end(&token);
+            }
+        }
+    }
+
+    fn request(&mut self, msg: &Value) -> Result<Value, CheatReason> {
+        //! sends msg and reads the reply, sending msg again if the player drops and reconnects
+        loop {
+            let sent = Instant::now();
+            self.stream.send(msg).ok();
+            match self.stream.recv() {
+                Ok(response) => return Ok(response),
+                // a player still connected can't be waited on to reconnect
+                Err(RecvError::Malformed(line)) => {
+                    return Err(CheatReason::Malformed { request: msg.clone(), response: Value::String(line) });
+                }
+                Err(_) => {}
+            }
+            // a player who ran out of time is still connected, so can't reconnect
+            if self.response_timeout.is_some_and(|timeout| sent.elapsed() >= timeout) {
+                return Err(CheatReason::Timeout { request: msg.clone() });
+            }
+            if !self.reconnect() {
+                return Err(CheatReason::Disconnected { request: msg.clone() });
+            }
+        }
+    }
+
+    fn cheat(&mut self, reason: CheatReason) {
+        //! keeps the first reason, later ones are usually a consequence of it
+        if self.cheated.is_none() {
+            self.cheated = Some(reason);
+        }
+    }
+
+    fn reconnect(&mut self) -> bool {
+        let stream = match (&self.sessions, &self.token) {
+            (Some(sessions), Some(token)) => sessions.await_reconnect(token),
+            _ => None,
+        };
+        match stream {
+            Some(mut stream) => {
+                stream.set_read_timeout(self.response_timeout);
+                self.stream = stream;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn get_okay(&mut self, request: Value) -> bool {
+        //! sends request, which the player must answer with "okay"
+        match self.request(&request) {
+            Ok(response) if response == json!("okay") => {}
+            Ok(response) => self.cheat(CheatReason::BadAck { request, response }),
+            Err(reason) => self.cheat(reason),
+        }
+        self.cheated.is_none()
     }
 }
 
@@ -226,4 +452,107 @@ impl Serialize for EndGame {
         state.serialize_field("end-game", &self.end_game)?;
         state.end()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// answers each message with the next reply, then hangs up, keeping what it was sent
+    #[derive(Clone)]
+    struct Scripted(Arc<Mutex<VecDeque<Result<Value, String>>>>, Arc<Mutex<Vec<Value>>>);
+
+    impl Scripted {
+        fn new(replies: Vec<Value>) -> Scripted {
+            Scripted(Arc::new(Mutex::new(replies.into_iter().map(Ok).collect())), Arc::default())
+        }
+
+        fn player(replies: Vec<Value>) -> RemotePlayer {
+            RemotePlayer::from_channel(Box::new(Scripted::new(replies)))
+        }
+
+        fn then_raw(self, line: &str) -> Scripted {
+            //! follows the replies with a line that isn't JSON
+            self.0.lock().unwrap().push_back(Err(line.to_string()));
+            self
+        }
+    }
+
+    impl MessageChannel for Scripted {
+        fn recv(&mut self) -> Result<Value, RecvError> {
+            match self.0.lock().unwrap().pop_front() {
+                Some(reply) => reply.map_err(RecvError::Malformed),
+                None => Err(RecvError::Closed),
+            }
+        }
+        fn send(&mut self, val: &Value) -> io::Result<()> { self.1.lock().unwrap().push(val.clone()); Ok(()) }
+        fn set_read_timeout(&mut self, _timeout: Option<Duration>) {}
+        fn duplicate(&self) -> Box<dyn MessageChannel> { Box::new(self.clone()) }
+    }
+
+    #[test]
+    fn cheat_reasons() {
+        // "legacy" answers hello, so the player is never sent a session
+        let mut bad_ack = Scripted::player(vec!(json!("legacy"), json!({ "name": "Sam" }), json!("nope")));
+        assert_eq!(bad_ack.get_name().to_string(), "Sam");
+        assert!(!bad_ack.start_game(PlayerColor::Black, "Lou".to_string()));
+        bad_ack.get_turn(&Board::new(), &vec!(1, 2));
+        // the first reason sticks
+        match bad_ack.cheat_reason() {
+            Some(CheatReason::BadAck { request, response }) => {
+                assert!(request.get("start-game").is_some());
+                assert_eq!(response, json!("nope"));
+            }
+            other => panic!("expected a bad ack, got {:?}", other),
+        }
+
+        let mut illegal = Scripted::player(vec!(json!("legacy"), json!({ "name": "Sam" }), json!("okay")));
+        illegal.get_name();
+        assert!(illegal.start_game(PlayerColor::Black, "Lou".to_string()));
+        assert!(!illegal.validate_turn(&Board::new(), &vec!(1, 2), &vec!(Move { start: 24, end: 16 })));
+        match illegal.cheat_reason() {
+            Some(CheatReason::IllegalTurn { error, legal, .. }) => {
+                assert_eq!(error.to_string(), "move 1 doesn't match an unused die");
+                assert!(!legal.is_empty() && legal.len() <= MAX_ALTERNATIVES);
+            }
+            other => panic!("expected an illegal turn, got {:?}", other),
+        }
+
+        let mut gone = Scripted::player(vec!());
+        assert!(gone.get_turn(&Board::new(), &vec!(1, 2)).is_empty());
+        assert!(matches!(gone.cheat_reason(), Some(CheatReason::Disconnected { .. })));
+
+        let script = Scripted::new(vec!(json!("legacy"), json!({ "name": "Sam" }), json!("okay"))).then_raw("{turn: oops");
+        let mut garbled = RemotePlayer::from_channel(Box::new(script));
+        garbled.get_name();
+        assert!(garbled.start_game(PlayerColor::Black, "Lou".to_string()));
+        assert!(garbled.get_turn(&Board::new(), &vec!(1, 2)).is_empty());
+        match garbled.cheat_reason() {
+            Some(CheatReason::Malformed { request, response }) => {
+                assert!(request.get("take-turn").is_some());
+                assert_eq!(response, json!("{turn: oops"));
+            }
+            other => panic!("expected a malformed reply, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn announces_start() {
+        let hello = json!({ "hello": { "version": 2, "capabilities": ["start-position"] } });
+        let sent = |hello: Value| {
+            let script = Scripted::new(vec!(hello, json!({ "name": "Sam" }), json!("okay")));
+            let mut player = RemotePlayer::from_channel(Box::new(script.clone()));
+            player.get_name();
+            assert!(player.start_game_from(PlayerColor::White, "Lou".to_string(), &StartPosition::Nackgammon, AnyRules::default()));
+            let last = script.1.lock().unwrap().last().unwrap().clone();
+            last["start-game"].as_array().unwrap().clone()
+        };
+        let start = sent(hello);
+        assert_eq!(start[2]["board"], StartPosition::Nackgammon.to_json()["board"]);
+        assert_eq!(start[2]["rules"], "standard");
+        // legacy players are sent the usual start-game
+        assert_eq!(sent(json!("legacy")), vec!(json!("white"), json!("Lou")));
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LogLevel {
+    Off,
+    /// players joining and leaving, and match results
+    Info,
+    /// every game's result too
+    Debug,
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LogLevel, String> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            _ => Err(format!("unknown log level '{}', expected off, info or debug", s)),
+        }
+    }
+}
+
+/**
+Writes tournament progress as lines like "[info] Lou joined".
+Cloning the logger shares its output, so games on other threads can log too.
+*/
+#[derive(Clone)]
+pub struct Logger {
+    level: LogLevel,
+    out: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl Default for Logger {
+    fn default() -> Logger {
+        Logger::off()
+    }
+}
+
+impl std::fmt::Debug for Logger {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Logger").field("level", &self.level).finish()
+    }
+}
+
+impl Logger {
+    pub fn new(level: LogLevel, out: Box<dyn Write + Send>) -> Logger {
+        Logger { level, out: Arc::new(Mutex::new(out)) }
+    }
+
+    pub fn off() -> Logger {
+        Logger::new(LogLevel::Off, Box::new(io::sink()))
+    }
+
+    pub fn stderr(level: LogLevel) -> Logger {
+        Logger::new(level, Box::new(io::stderr()))
+    }
+
+    pub fn file<P: AsRef<Path>>(level: LogLevel, path: P) -> io::Result<Logger> {
+        //! appends to the file at path, creating it if needed
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Logger::new(level, Box::new(file)))
+    }
+
+    #[inline]
+    pub fn level(&self) -> LogLevel { self.level }
+
+    pub fn info<M: Display>(&self, msg: M) {
+        self.log(LogLevel::Info, "info", msg);
+    }
+
+    pub fn debug<M: Display>(&self, msg: M) {
+        self.log(LogLevel::Debug, "debug", msg);
+    }
+
+    fn log<M: Display>(&self, level: LogLevel, tag: &str, msg: M) {
+        if self.level >= level {
+            let mut out = self.out.lock().unwrap();
+            writeln!(out, "[{}] {}", tag, msg).ok();
+            out.flush().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.lock().unwrap().write(buf) }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn levels() {
+        let out = Shared::default();
+        let logger = Logger::new("info".parse().unwrap(), Box::new(out.clone()));
+        logger.info("Lou joined");
+        logger.clone().debug("game 1 over");
+        assert_eq!(String::from_utf8(out.0.lock().unwrap().clone()).unwrap(), "[info] Lou joined\n");
+        assert!("loud".parse::<LogLevel>().is_err());
+    }
+}
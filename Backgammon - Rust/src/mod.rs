@@ -2,7 +2,9 @@ use serde_json::Value;
 
 pub const BAR: u8 = 0;
 pub const HOME: u8 = 25;
-pub const NUM_CHECKERS: usize = 15;
+/// checkers each player has in a standard game, and the most any game can have.
+/// Games with fewer checkers fill the rest of each position array with HOME, as if they were borne off
+pub const MAX_CHECKERS: usize = 15;
 
 #[inline]
 pub fn piece_val_to_u8(val: &Value) -> u8 {
@@ -33,14 +35,23 @@ pub fn piece_u8_to_val(val: u8) -> Value {
     }
 }
 
+pub fn pad_positions(pieces: &[u8]) -> [u8; MAX_CHECKERS] {
+    //! sorts up to MAX_CHECKERS positions, filling the unused checkers with HOME
+    debug_assert!(pieces.len() <= MAX_CHECKERS, "at most {} checkers, given {}", MAX_CHECKERS, pieces.len());
+    let mut positions = [HOME; MAX_CHECKERS];
+    positions[..pieces.len()].copy_from_slice(pieces);
+    positions.sort_unstable();
+    positions
+}
+
 //TODO: Consider making a transparent PlayerPositions struct for all of the below functions
 
 #[inline]
-pub fn try_bop(positions: &mut [u8; NUM_CHECKERS], bop_val: &u8) {
+pub fn try_bop(positions: &mut [u8; MAX_CHECKERS], bop_val: &u8) {
     unsafe {
-        let first_ind = first_binary_search(positions, bop_val, 0, NUM_CHECKERS);
+        let first_ind = first_binary_search(positions, bop_val, 0, MAX_CHECKERS);
         if let Ok(x) = first_ind {
-            if x == NUM_CHECKERS - 1 || &positions[x + 1] != bop_val {
+            if x == MAX_CHECKERS - 1 || &positions[x + 1] != bop_val {
                 let end_ind = last_binary_search(positions, &BAR, 0, x);
                 move_checker_with_index(positions, &BAR, (first_ind, end_ind));
             }
@@ -49,28 +60,28 @@ pub fn try_bop(positions: &mut [u8; NUM_CHECKERS], bop_val: &u8) {
 }
 
 #[inline]
-pub fn count_occur(positions: &[u8; NUM_CHECKERS], cnt_val: &u8) -> u8 {
+pub fn count_occur(positions: &[u8; MAX_CHECKERS], cnt_val: &u8) -> u8 {
     let mut tally: u8 = 0;
     let first_ind;
     unsafe {
-        first_ind = first_binary_search(positions, cnt_val, 0, NUM_CHECKERS);
+        first_ind = first_binary_search(positions, cnt_val, 0, MAX_CHECKERS);
     }
     if let Ok(x) = first_ind {
-        for i in x..NUM_CHECKERS {
-            if &positions[i] == cnt_val { tally += 1; } else { break; }
+        for position in positions[x..].iter() {
+            if position == cnt_val { tally += 1; } else { break; }
         }
     }
     tally
 }
 
-pub fn move_checker(positions: &mut [u8; NUM_CHECKERS], start_val: &u8, end_val: &u8) {
+pub fn move_checker(positions: &mut [u8; MAX_CHECKERS], start_val: &u8, end_val: &u8) {
     //! moves checker while maintaining sorted list
     let indices = double_binary_search(positions, start_val, end_val);
     move_checker_with_index(positions, end_val, indices);
 }
 
 #[inline]
-fn move_checker_with_index(positions: &mut [u8; NUM_CHECKERS], end_val: &u8, indices: (Result<usize, usize>, Result<usize, usize>)) {
+fn move_checker_with_index(positions: &mut [u8; MAX_CHECKERS], end_val: &u8, indices: (Result<usize, usize>, Result<usize, usize>)) {
     //! moves checker while maintaining sorted list
     let (r1, r2) = indices;
     let start_ind = r1.ok().expect("position not found");
@@ -96,20 +107,20 @@ fn move_checker_with_index(positions: &mut [u8; NUM_CHECKERS], end_val: &u8, ind
 }
 
 #[inline]
-fn double_binary_search(positions: &[u8; NUM_CHECKERS], targ1: &u8, targ2: &u8) -> (Result<usize, usize>, Result<usize, usize>) {
-    debug_assert_eq!(positions.len(), NUM_CHECKERS, "double_binary_search requires Vec of len 15 for safety");
+fn double_binary_search(positions: &[u8; MAX_CHECKERS], targ1: &u8, targ2: &u8) -> (Result<usize, usize>, Result<usize, usize>) {
+    debug_assert_eq!(positions.len(), MAX_CHECKERS, "double_binary_search requires Vec of len 15 for safety");
     let r1;
     let r2;
 
     if targ1 < targ2 {
         unsafe {
-            r1 = last_binary_search(positions, targ1, 0, NUM_CHECKERS);
-            r2 = first_binary_search(positions, targ2, 0, NUM_CHECKERS);
+            r1 = last_binary_search(positions, targ1, 0, MAX_CHECKERS);
+            r2 = first_binary_search(positions, targ2, 0, MAX_CHECKERS);
         }
     } else {
         unsafe {
-            r1 = first_binary_search(positions, targ1, 0, NUM_CHECKERS);
-            r2 = last_binary_search(positions, targ2, 0, NUM_CHECKERS);
+            r1 = first_binary_search(positions, targ1, 0, MAX_CHECKERS);
+            r2 = last_binary_search(positions, targ2, 0, MAX_CHECKERS);
         }
     }
 
@@ -117,7 +128,7 @@ fn double_binary_search(positions: &[u8; NUM_CHECKERS], targ1: &u8, targ2: &u8)
 }
 
 #[inline]
-unsafe fn first_binary_search(positions: &[u8; NUM_CHECKERS], targ: &u8, low: usize, high: usize) -> Result<usize, usize> {
+unsafe fn first_binary_search(positions: &[u8; MAX_CHECKERS], targ: &u8, low: usize, high: usize) -> Result<usize, usize> {
     debug_assert!(high >= low, "high < low! not allowed!");
     // adapted from rust src: https://doc.rust-lang.org/src/core/slice/mod.rs.html#2112-2114
     let mut left = low;
@@ -142,7 +153,7 @@ unsafe fn first_binary_search(positions: &[u8; NUM_CHECKERS], targ: &u8, low: us
 }
 
 #[inline]
-unsafe fn last_binary_search(positions: &[u8; NUM_CHECKERS], targ: &u8, low: usize, high: usize) -> Result<usize, usize> {
+unsafe fn last_binary_search(positions: &[u8; MAX_CHECKERS], targ: &u8, low: usize, high: usize) -> Result<usize, usize> {
     debug_assert!(high >= low, "high < low! not allowed!");
     // adapted from rust src: https://doc.rust-lang.org/src/core/slice/mod.rs.html#2112-2114
     let mut left = low;
@@ -188,6 +199,14 @@ mod tests {
         assert_eq!(piece_u8_to_val(17), json!(17));
     }
 
+    #[test]
+    fn padding() {
+        let positions = pad_positions(&[24, BAR, 3]);
+        assert_eq!(positions[..4], [BAR, 3, 24, HOME]);
+        assert_eq!(count_occur(&positions, &HOME), 12);
+        assert_eq!(pad_positions(&TEST_POSITIONS), TEST_POSITIONS);
+    }
+
     #[test]
     #[should_panic]
     fn panic_u8_to_val() {
@@ -200,9 +219,9 @@ mod tests {
         piece_val_to_u8(&json!("x"));
     }
 
-    const TEST_POSITIONS: [u8; NUM_CHECKERS] = [3, 3, 5, 5, 7, 8, 9, 11, 11, 12, 13, 13, 13, 13, 15];
+    const TEST_POSITIONS: [u8; MAX_CHECKERS] = [3, 3, 5, 5, 7, 8, 9, 11, 11, 12, 13, 13, 13, 13, 15];
 
-    fn naive_move_checkers(positions: &[u8; NUM_CHECKERS], start_val: &u8, end_val: &u8) -> [u8; NUM_CHECKERS] {
+    fn naive_move_checkers(positions: &[u8; MAX_CHECKERS], start_val: &u8, end_val: &u8) -> [u8; MAX_CHECKERS] {
         let index = positions.binary_search(start_val).ok().unwrap();
         let mut new_pos = positions.clone();
         new_pos[index] = *end_val;
@@ -245,7 +264,7 @@ mod tests {
         let mut rng = rand::thread_rng();
 
         for _ in 0..100_000 {
-            let start_ind = rng.gen_range(0..NUM_CHECKERS);
+            let start_ind = rng.gen_range(0..MAX_CHECKERS);
             let start_val = positions[start_ind];
             let end_val = rng.gen_range(BAR..HOME + 1);
             move_checker(&mut positions, &start_val, &end_val);
@@ -3,6 +3,11 @@ use std::net::{TcpStream, TcpListener};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::{
+    channel::{ChannelListener, Transport},
+    ws_handler::WsHandler,
+};
+
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[serde(deny_unknown_fields)]
@@ -26,11 +31,27 @@ impl NetConfig {
         TcpStream::connect(address)
     }
 
+    pub fn connect_websocket(&self) -> Result<WsHandler, std::io::Error> {
+        let url = format!("ws://{}:{}/", self.host.as_str().unwrap(), self.port.as_u64().unwrap());
+        WsHandler::connect(self.connect()?, &url)
+    }
+
     pub fn connect_listener(port: Value) -> Result<TcpListener, std::io::Error>{
-        let mut address: String = String::from("localhost:");
+        NetConfig::bind_listener("localhost", port)
+    }
+
+    pub fn bind_listener(host: &str, port: Value) -> Result<TcpListener, std::io::Error> {
+        //! listens on host, such as "0.0.0.0" to accept players from other machines
+        let mut address: String = host.to_string();
+        address.push(':');
         address.push_str(&port.as_u64().unwrap().to_string());
         TcpListener::bind(address)
     }
+
+    pub fn connect_websocket_listener(port: Value) -> Result<ChannelListener, std::io::Error> {
+        //! like connect_listener, but connections speak JSON over WebSocket text frames
+        Ok(ChannelListener::new(NetConfig::connect_listener(port)?, Transport::WebSocket))
+    }
 }
 
 #[cfg(test)]
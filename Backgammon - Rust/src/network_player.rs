@@ -2,34 +2,66 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use crate::{
+    channel::MessageChannel,
     player::{Player, PlayerColor},
-    tcp_handler::TcpHandler,
+    protocol::Protocol,
+    rules::any_rules::AnyRules,
 };
 use crate::board::Board;
 use crate::player_board::PlayerStrat;
 
 pub struct NetworkPlayer<S: PlayerStrat> {
     player: Player<S>,
-    stream: TcpHandler,
+    stream: Box<dyn MessageChannel>,
+    /// token given by the server, used to resume the game after reconnecting
+    session: Option<String>,
+    /// shared secret some tournaments require to join
+    secret: Option<String>,
+    /// agreed with the server's hello, legacy until then
+    protocol: Protocol,
 }
 
 impl<S: PlayerStrat> NetworkPlayer<S> {
-    pub fn new(player: Player<S>, stream: TcpHandler) -> NetworkPlayer<S> {
-        NetworkPlayer { player, stream }
+    pub fn new<C: MessageChannel + 'static>(player: Player<S>, stream: C) -> NetworkPlayer<S> {
+        //! stream is a TcpHandler or a WsHandler, depending on how the server listens
+        NetworkPlayer { player, stream: Box::new(stream), session: None, secret: None, protocol: Protocol::legacy() }
+    }
+
+    pub fn with_secret(mut self, secret: String) -> NetworkPlayer<S> {
+        self.secret = Some(secret);
+        self
+    }
+
+    #[inline]
+    pub fn session(&self) -> Option<&String> { self.session.as_ref() }
+
+    #[inline]
+    pub fn protocol(&self) -> &Protocol { &self.protocol }
+
+    pub fn reconnect<C: MessageChannel + 'static>(&mut self, stream: C) -> bool {
+        /*!
+        resumes the session over a new connection, returns false if there is no
+        session or the server refused it. handle_stream can then be called again.
+        */
+        let token = match &self.session {
+            Some(token) => token.clone(),
+            None => return false,
+        };
+        self.stream = Box::new(stream);
+        self.stream.send(&json!({ "reconnect": token })).ok();
+        self.stream.recv().is_ok_and(|reply| reply == "okay")
     }
 
     pub fn handle_stream(&mut self) -> Result<(), serde_json::Error> {
         //! handle all input from stream
-        loop {
-            let new_val = self.stream.read_line();
-            if new_val.is_null() { break; }
+        while let Ok(new_val) = self.stream.recv() {
             let out_val;
             if new_val.is_string() {
                 out_val = self.process_json_str(new_val).unwrap();
             } else {
                 out_val = self.process_json_obj(new_val).unwrap();
             }
-            self.stream.write(&out_val);
+            self.stream.send(&out_val).ok();
         }
 
         Ok(())
@@ -38,6 +70,8 @@ impl<S: PlayerStrat> NetworkPlayer<S> {
     fn process_json_str(&mut self, json: serde_json::Value) -> Result<Value, serde_json::Error> {
         if json.as_str().unwrap() == "name" {
             Ok(json!(self.player.get_name()))
+        } else if json.as_str().unwrap() == "secret" {
+            Ok(json!(self.secret.clone().unwrap_or_default()))
         } else {
             Ok(json!("error reading json input"))
         }
@@ -47,10 +81,30 @@ impl<S: PlayerStrat> NetworkPlayer<S> {
         // here we determine what kind of input came from server
         // and which function to process it with
         // start-game, take-turn, or end-game
+        if let Some(server) = Protocol::from_hello(&json) {
+            let ours = Protocol::current();
+            self.protocol = ours.negotiate(&server);
+            return Ok(ours.hello());
+        }
         let map = json.as_object().unwrap();
+        if let Some(token) = map.get("session") {
+            self.session = token.as_str().map(|t| t.to_string());
+            return Ok(json!("okay"));
+        }
+        if map.contains_key("rejected") {
+            // the server disconnects after rejecting us
+            return Ok(json!("okay"));
+        }
         if map.contains_key("start-game") {
+            // a third element says where the checkers start, but every take-turn carries the board anyway,
+            // and which rules the game is played by
             let color = serde_json::from_value::<PlayerColor>(map["start-game"][0].clone())?;
             let opp_name = serde_json::from_value::<String>(map["start-game"][1].clone())?;
+            let rules = match map["start-game"][2]["rules"].as_str() {
+                Some(name) => name.parse().map_err(serde::de::Error::custom)?,
+                None => AnyRules::default(),
+            };
+            self.player.set_rules(rules);
             return Ok(self.start_game(color, opp_name));
         } else if map.contains_key("take-turn") {
             let board = serde_json::from_value::<Board>(map["take-turn"][0].clone())?;
@@ -0,0 +1,235 @@
+//! Standard backgammon notation, written from the moving player's point of view.
+//! Each player moves from their 24 point towards their 1 point, then "off".
+//! Black's point of view matches world coordinates, White's is flipped.
+//! A hit is marked "*", a checker moving again is written "24/18/13" and a move repeated "8/4(2)".
+
+use crate::{
+    board::Board,
+    player::PlayerColor,
+    player_board::flip_move,
+    r#mod::{BAR, HOME},
+    r#move::Move,
+};
+
+pub fn to_world(mve: &Move, color: &PlayerColor) -> Move {
+    //! converts a move from the player's point of view to world coordinates
+    match color {
+        PlayerColor::Black => mve.clone(),
+        PlayerColor::White => flip_move(mve),
+    }
+}
+
+pub fn from_world(mve: &Move, color: &PlayerColor) -> Move {
+    //! converts a move in world coordinates to the player's point of view
+    to_world(mve, color)
+}
+
+#[inline]
+pub fn world_point(point: u8, color: &PlayerColor) -> u8 {
+    //! converts a point (1-24) between world coordinates and the player's point of view
+    match color {
+        PlayerColor::White if point != BAR && point != HOME => HOME - point,
+        _ => point,
+    }
+}
+
+pub fn parse_move(text: &str, color: &PlayerColor) -> Result<Move, String> {
+    //! parses a single move such as "24/18", "bar/22", "13/11*" or "6/off" into world coordinates
+    match parse_play(text, color)?.as_slice() {
+        [mve] => Ok(mve.clone()),
+        _ => Err(format!("'{}' is more than one move", text.trim())),
+    }
+}
+
+pub fn parse_turn(text: &str, color: &PlayerColor) -> Result<Vec<Move>, String> {
+    //! parses whitespace separated moves, such as "24/18 13/11*", "bar/22/16" or "8/4(2)"
+    let mut moves = Vec::with_capacity(4);
+    for play in text.split_whitespace() {
+        moves.extend(parse_play(play, color)?);
+    }
+    Ok(moves)
+}
+
+fn parse_play(text: &str, color: &PlayerColor) -> Result<Vec<Move>, String> {
+    //! parses one checker's moves in a row, repeated as many times as a suffix like "(2)" says
+    let lower = text.trim().to_lowercase();
+    let (path, times) = match lower.strip_suffix(')').and_then(|rest| rest.rsplit_once('(')) {
+        Some((path, times)) => match times.parse::<usize>() {
+            Ok(times) if (1..=4).contains(&times) => (path, times),
+            _ => return Err(format!("'({})' is not a repeat, expected (2), (3) or (4)", times)),
+        },
+        None => (lower.as_str(), 1),
+    };
+    let points: Vec<&str> = path.split('/').collect();
+    if points.len() < 2 {
+        return Err(format!("'{}' is not a move, expected a form like 24/18", text.trim()));
+    }
+
+    let mut start = match points[0] {
+        "bar" => BAR,
+        point => parse_point(point)?,
+    };
+    let mut chain = Vec::with_capacity(points.len() - 1);
+    for (i, point) in points.iter().enumerate().skip(1) {
+        // the board decides whether a move hits, so the marker is only for readers
+        let end = match point.strip_suffix('*').unwrap_or(point) {
+            "off" if i == points.len() - 1 => HOME,
+            point => parse_point(point)?,
+        };
+        chain.push(to_world(&Move { start, end }, color));
+        start = end;
+    }
+    let mut moves = Vec::with_capacity(chain.len() * times);
+    for _ in 0..times {
+        moves.extend(chain.iter().cloned());
+    }
+    Ok(moves)
+}
+
+pub fn format_move(mve: &Move, color: &PlayerColor) -> String {
+    //! formats a move in world coordinates from the player's point of view
+    let local = from_world(mve, color);
+    let start = match local.start {
+        BAR => "bar".to_string(),
+        x => x.to_string(),
+    };
+    let end = match local.end {
+        HOME => "off".to_string(),
+        x => x.to_string(),
+    };
+    format!("{}/{}", start, end)
+}
+
+pub fn format_turn(moves: &[Move], color: &PlayerColor) -> String {
+    let formatted: Vec<String> = moves.iter().map(|mve| format_move(mve, color)).collect();
+    formatted.join(" ")
+}
+
+pub fn format_play(board: &Board, moves: &[Move], color: &PlayerColor) -> String {
+    /*!
+    formats a turn played from board as players write it, marking hits and joining each checker's
+    moves in a row, then counting repeated plays, e.g. "bar/22* 13/9/5(2)"
+    */
+    let mut board = board.clone();
+    let mut chains: Vec<(Move, String)> = Vec::with_capacity(moves.len());
+    for mve in moves {
+        let hit = board.make_move(color, mve).hit;
+        let local = from_world(mve, color);
+        let end = match local.end {
+            HOME => "off".to_string(),
+            x => x.to_string(),
+        } + if hit { "*" } else { "" };
+        match chains.last_mut() {
+            Some((last, text)) if last.end == local.start => {
+                last.end = local.end;
+                text.push('/');
+                text.push_str(&end);
+            }
+            _ => {
+                let start = match local.start {
+                    BAR => "bar".to_string(),
+                    x => x.to_string(),
+                };
+                chains.push((local, format!("{}/{}", start, end)));
+            }
+        }
+    }
+
+    let mut plays: Vec<(String, usize)> = Vec::with_capacity(chains.len());
+    for (_, text) in chains {
+        match plays.iter_mut().find(|(play, _)| *play == text) {
+            Some((_, times)) => *times += 1,
+            None => plays.push((text, 1)),
+        }
+    }
+    let formatted: Vec<String> = plays.into_iter()
+        .map(|(play, times)| if times == 1 { play } else { format!("{}({})", play, times) })
+        .collect();
+    formatted.join(" ")
+}
+
+fn parse_point(text: &str) -> Result<u8, String> {
+    match text.parse::<u8>() {
+        Ok(x) if x != BAR && x < HOME => Ok(x),
+        _ => Err(format!("'{}' is not a point, expected 1-24, bar or off", text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_black() {
+        assert_eq!(parse_move("24/18", &PlayerColor::Black).unwrap(), Move { start: 24, end: 18 });
+        assert_eq!(parse_move("bar/22", &PlayerColor::Black).unwrap(), Move { start: BAR, end: 22 });
+        assert_eq!(parse_move("6/OFF", &PlayerColor::Black).unwrap(), Move { start: 6, end: HOME });
+    }
+
+    #[test]
+    fn parse_white() {
+        assert_eq!(parse_move("24/18", &PlayerColor::White).unwrap(), Move { start: 1, end: 7 });
+        assert_eq!(parse_move("bar/22", &PlayerColor::White).unwrap(), Move { start: BAR, end: 3 });
+        assert_eq!(parse_move("6/off", &PlayerColor::White).unwrap(), Move { start: 19, end: HOME });
+        assert_eq!(
+            parse_turn(" 24/18  13/11 ", &PlayerColor::White).unwrap(),
+            vec!(Move { start: 1, end: 7 }, Move { start: 12, end: 14 })
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_move("24-18", &PlayerColor::Black).is_err());
+        assert!(parse_move("25/18", &PlayerColor::Black).is_err());
+        assert!(parse_move("off/18", &PlayerColor::Black).is_err());
+        assert!(parse_move("24/bar", &PlayerColor::Black).is_err());
+        assert!(parse_move("24/18/12", &PlayerColor::Black).is_err());
+        assert!(parse_turn("24/18 x", &PlayerColor::Black).is_err());
+        assert!(parse_turn("24/off/18", &PlayerColor::Black).is_err());
+        assert!(parse_turn("24*/18", &PlayerColor::Black).is_err());
+        assert!(parse_turn("8/4(5)", &PlayerColor::Black).unwrap_err().contains("(5)"));
+        assert!(parse_turn("8/4(2", &PlayerColor::Black).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        for color in [PlayerColor::Black, PlayerColor::White].iter() {
+            for text in ["24/18", "bar/20", "3/off", "13/11"].iter() {
+                let mve = parse_move(text, color).unwrap();
+                assert_eq!(&format_move(&mve, color), text);
+            }
+        }
+        let moves = vec!(Move { start: 1, end: 7 }, Move { start: 12, end: 14 });
+        assert_eq!(format_turn(&moves, &PlayerColor::White), "24/18 13/11");
+    }
+
+    #[test]
+    fn compound_and_repeats() {
+        let black = &PlayerColor::Black;
+        assert_eq!(parse_turn("bar/22* 13/11*", black).unwrap(), vec!(Move { start: BAR, end: 22 }, Move { start: 13, end: 11 }));
+        assert_eq!(parse_turn("24/18*/13", black).unwrap(), vec!(Move { start: 24, end: 18 }, Move { start: 18, end: 13 }));
+        assert_eq!(parse_turn("8/4(2) 6/off", black).unwrap(),
+                   vec!(Move { start: 8, end: 4 }, Move { start: 8, end: 4 }, Move { start: 6, end: HOME }));
+        assert_eq!(parse_turn("13/9/5(2)", &PlayerColor::White).unwrap().len(), 4);
+        assert_eq!(parse_move("13/11*", &PlayerColor::White).unwrap(), Move { start: 12, end: 14 });
+    }
+
+    #[test]
+    fn play() {
+        let board = Board::from_positions(
+            [6, 6, 6, 6, 6, 8, 8, 8, 13, 13, 13, 13, 13, 24, 24],
+            [1, 1, 5, 12, 12, 12, 12, 17, 17, 17, 19, 19, 19, 19, 19]);
+        let black = &PlayerColor::Black;
+        let hits = parse_turn("13/10 10/5*", black).unwrap();
+        assert_eq!(format_play(&board, &hits, black), "13/10/5*");
+        let doubles = parse_turn("13/9 9/5 13/9 9/5", black).unwrap();
+        // only the first checker to land hits
+        assert_eq!(format_play(&board, &doubles, black), "13/9/5* 13/9/5");
+        let white = &PlayerColor::White;
+        let moves = parse_turn("24/18 13/11", white).unwrap();
+        assert_eq!(format_play(&board, &moves, white), format_turn(&moves, white));
+        for text in ["8/4(2) 6/2(2)", "13/7 13/9", "24/20/16(2)"] {
+            assert_eq!(format_play(&Board::new(), &parse_turn(text, black).unwrap(), black), text);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    board::Board,
+    cheat::Infraction,
+    player::PlayerColor,
+    r#move::Move,
+};
+
+/**
+Receives callbacks as an Administrator moderates a game.
+Every callback does nothing by default, so observers only implement what they need.
+*/
+pub trait AdminObserver: Send {
+    fn game_started(&mut self, _black: &str, _white: &str) {}
+
+    fn dice_rolled(&mut self, _color: &PlayerColor, _dice: &[u8]) {}
+
+    /// board is the board after the moves were made
+    fn turn_submitted(&mut self, _color: &PlayerColor, _moves: &[Move], _board: &Board) {}
+
+    /// the player submitted an illegal turn, or cheated while taking it
+    fn turn_rejected(&mut self, _color: &PlayerColor, _moves: &[Move]) {}
+
+    /// the player was caught cheating, in or out of a turn, and is followed by the infraction
+    fn player_cheated(&mut self, _color: &PlayerColor) {}
+
+    fn player_replaced(&mut self, _color: &PlayerColor, _replacement: &str) {}
+
+    /// the player was caught cheating, in or out of a turn, before anyone takes their seat
+    fn infraction(&mut self, _infraction: &Infraction) {}
+
+    /// winner is None when both players cheated, or the game hit a limit and is void
+    fn game_over(&mut self, _winner: Option<&PlayerColor>, _board: &Board) {}
+}
+
+/// Forwards every callback to each observer in order
+impl AdminObserver for Vec<Box<dyn AdminObserver>> {
+    fn game_started(&mut self, black: &str, white: &str) {
+        for observer in self.iter_mut() { observer.game_started(black, white); }
+    }
+
+    fn dice_rolled(&mut self, color: &PlayerColor, dice: &[u8]) {
+        for observer in self.iter_mut() { observer.dice_rolled(color, dice); }
+    }
+
+    fn turn_submitted(&mut self, color: &PlayerColor, moves: &[Move], board: &Board) {
+        for observer in self.iter_mut() { observer.turn_submitted(color, moves, board); }
+    }
+
+    fn turn_rejected(&mut self, color: &PlayerColor, moves: &[Move]) {
+        for observer in self.iter_mut() { observer.turn_rejected(color, moves); }
+    }
+
+    fn player_cheated(&mut self, color: &PlayerColor) {
+        for observer in self.iter_mut() { observer.player_cheated(color); }
+    }
+
+    fn player_replaced(&mut self, color: &PlayerColor, replacement: &str) {
+        for observer in self.iter_mut() { observer.player_replaced(color, replacement); }
+    }
+
+    fn infraction(&mut self, infraction: &Infraction) {
+        for observer in self.iter_mut() { observer.infraction(infraction); }
+    }
+
+    fn game_over(&mut self, winner: Option<&PlayerColor>, board: &Board) {
+        for observer in self.iter_mut() { observer.game_over(winner, board); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct Counter {
+        rolls: Arc<Mutex<usize>>,
+    }
+
+    impl AdminObserver for Counter {
+        fn dice_rolled(&mut self, _color: &PlayerColor, _dice: &[u8]) {
+            *self.rolls.lock().unwrap() += 1;
+        }
+    }
+
+    #[test]
+    fn fan_out() {
+        let rolls = Arc::new(Mutex::new(0));
+        let mut observers: Vec<Box<dyn AdminObserver>> = vec!(
+            Box::new(Counter { rolls: rolls.clone() }),
+            Box::new(Counter { rolls: rolls.clone() }),
+        );
+        observers.dice_rolled(&PlayerColor::White, &[3, 4]);
+        observers.game_over(None, &Board::new());
+        assert_eq!(*rolls.lock().unwrap(), 2);
+    }
+}
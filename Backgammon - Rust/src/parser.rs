@@ -7,7 +7,7 @@ use crate::{
     board::Board,
     net_config::NetConfig,
     r#move::Move,
-    tournament::tournament::{TournConfig, TType},
+    tournament::{config, tournament::TournConfig},
     r#mod::piece_val_to_u8,
 };
 
@@ -36,11 +36,8 @@ pub fn get_admin_config(json_obj: &Value) -> AdminConfig {
 }
 
 pub fn get_tournament_config(json_obj: &Value) -> TournConfig {
-    let map = json_obj.as_object().unwrap();
-    let players = map["players"].clone().as_u64().unwrap();
-    let port = map["port"].clone();
-    let ev_type = serde_json::from_value::<TType>(map["type"].clone()).unwrap();
-    TournConfig::new(players, port, ev_type)
+    //! see tournament::config for the keys, panics if the config is invalid
+    config::from_json(json_obj).unwrap_or_else(|e| panic!("Invalid tournament config: {}", e))
 }
 
 pub fn get_moves(move_arr: &Vec<Value>) -> Vec<Move> {
@@ -8,6 +8,8 @@ use crate::{
     board::Board,
     player_board::{PlayerBoard, PlayerStrat},
     r#move::Move,
+    rules::any_rules::AnyRules,
+    turn_error::IllegalTurn,
 };
 
 #[derive(Debug)]
@@ -31,6 +33,7 @@ pub struct Player<S: PlayerStrat> {
     color: PlayerColor,
     is_playing: bool,
     strategy: S,
+    rules: AnyRules,
 }
 
 impl<S: PlayerStrat> Player<S> {
@@ -40,6 +43,7 @@ impl<S: PlayerStrat> Player<S> {
             color: PlayerColor::Black,
             is_playing: false,
             strategy,
+            rules: AnyRules::default(),
         }
     }
 
@@ -51,6 +55,14 @@ impl<S: PlayerStrat> Player<S> {
         self.color.clone()
     }
 
+    #[inline]
+    pub fn rules(&self) -> AnyRules { self.rules }
+
+    pub fn set_rules(&mut self, rules: AnyRules) {
+        //! the rules turns are chosen and validated by, backgammon unless set
+        self.rules = rules;
+    }
+
     pub fn start_game(&mut self, color: PlayerColor, _opponent_name: String) -> Result<(), PlayerError> {
         if self.is_playing {
             // panic!("player is already in a game");
@@ -63,16 +75,28 @@ impl<S: PlayerStrat> Player<S> {
     }
 
     pub fn get_turn(&self, board: &Board, dice: &Vec<u8>) -> Vec<Move> {
-        let pl_board = PlayerBoard::new(&self.color, board, &self.strategy);
+        let pl_board = PlayerBoard::new(&self.color, board, &self.strategy, self.rules);
         pl_board.pick_turn(&dice)
     }
 
     pub fn validate_turn(&self, board: &Board, dice: &Vec<u8>, moves: &Vec<Move>) -> bool {
         debug_assert!(self.is_playing, "must start game to know what color to validate");
-        let pl_board = PlayerBoard::new(&self.color, board, &self.strategy);
+        let pl_board = PlayerBoard::new(&self.color, board, &self.strategy, self.rules);
         pl_board.validate_turn(&dice, &moves)
     }
 
+    pub fn check_turn(&self, board: &Board, dice: &[u8], moves: &[Move]) -> Result<(), IllegalTurn> {
+        //! validates moves like validate_turn, explaining why they aren't a legal turn
+        debug_assert!(self.is_playing, "must start game to know what color to validate");
+        let pl_board = PlayerBoard::new(&self.color, board, &self.strategy, self.rules);
+        pl_board.check_turn(dice, moves)
+    }
+
+    pub fn legal_turns(&self, board: &Board, dice: &[u8]) -> Vec<Vec<Move>> {
+        let pl_board = PlayerBoard::new(&self.color, board, &self.strategy, self.rules);
+        pl_board.legal_turns(dice)
+    }
+
     pub fn end_game(&mut self, _won: bool) {
         if !self.is_playing {
             // panic? return error through result?
@@ -91,7 +115,7 @@ impl<S: PlayerStrat> Player<S> {
     }
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
 pub enum PlayerColor {
     Black,
     White,
@@ -120,6 +144,10 @@ pub struct PlayerName {
 }
 
 impl PlayerName {
+    pub fn new(name: String) -> PlayerName {
+        PlayerName { name }
+    }
+
     pub fn to_string(&self) -> String {
         self.name.clone()
     }
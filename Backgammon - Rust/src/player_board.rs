@@ -1,16 +1,28 @@
+use std::cell::RefCell;
 use std::fmt;
+use std::ops::Range;
+
+use rand::Rng;
 
 use crate::{
     board::Board,
     dice_tracker::DiceTracker,
     player::PlayerColor,
-    r#mod::{BAR, HOME, move_checker, NUM_CHECKERS},
+    r#mod::{BAR, HOME, count_occur, move_checker, MAX_CHECKERS},
     r#move::Move,
-    turn_tree::TurnNode,
+    rules::{any_rules::AnyRules, base::{Landing, Rules}},
+    turn_error::{closest_turn, IllegalTurn, MoveError, TurnError},
+    turn_tree::{TurnArena, TurnNode, Turns},
 };
 
 pub const HOME_EDGE: u8 = 19;
 
+thread_local! {
+    /// turns generated on a thread are built here, so the arena's capacity is only allocated once.
+    /// Turns generated while it's in use, by a strategy choosing a turn, are built in an arena of their own
+    static ARENA: RefCell<TurnArena> = RefCell::new(TurnArena::default());
+}
+
 /**
 Stores checker positions in a local coordinate system
 where the player's checkers always move from 0->25.
@@ -19,49 +31,78 @@ Simplifies game logic by making rules identical for both players
 pub struct PlayerBoard<'a, S: PlayerStrat> {
     opponent_frq: Vec<u8>,
     strategy: &'a S,
-    player_pos: [u8; NUM_CHECKERS],
+    player_pos: [u8; MAX_CHECKERS],
     is_flipped: bool,
+    rules: AnyRules,
+    /// bit p is set when the player's checker on local point p is pinned
+    pinned: u32,
+    /// bit p is set when a checker may land on local point p
+    landable: u32,
 }
 
 impl<S: PlayerStrat> PlayerBoard<'_, S> {
-    pub fn new<'a>(color: &PlayerColor, board: &Board, strat: &'a S) -> PlayerBoard<'a, S> {
-        let mut stack_pos: [u8; NUM_CHECKERS];
-        match &color {
+    pub fn new<'a>(color: &PlayerColor, board: &Board, strat: &'a S, rules: AnyRules) -> PlayerBoard<'a, S> {
+        //! the opponent's checkers are placed where rules say they stand for this player
+        let mut stack_pos: [u8; MAX_CHECKERS];
+        let (opp_color, opp_frq) = match &color {
             PlayerColor::Black => {
-                stack_pos = board.black.clone();
+                stack_pos = board.black;
                 invert_pos(&mut stack_pos);
-                let opp_frq = pos_to_inv_freqs(&board.white);
-
-                PlayerBoard {
-                    player_pos: stack_pos,
-                    opponent_frq: opp_frq,
-                    is_flipped: true,
-                    strategy: strat,
-                }
+                (PlayerColor::White, pos_to_freqs(&board.white))
             }
             PlayerColor::White => {
-                stack_pos = board.white.clone();
-                PlayerBoard {
-                    player_pos: stack_pos,
-                    opponent_frq: pos_to_freqs(&board.black),
-                    is_flipped: false,
-                    strategy: strat,
-                }
+                stack_pos = board.white;
+                (PlayerColor::Black, pos_to_inv_freqs(&board.black))
+            }
+        };
+        let is_flipped = *color == PlayerColor::Black;
+        // local points of either player, in their own coordinates
+        let local = |flipped: bool, point: u8| if flipped { HOME - point } else { point };
+
+        let mut opponent_frq = vec![0; HOME as usize + 1];
+        let (mut pinned, mut opp_pinned, mut landable) = (0u32, 0u32, 0u32);
+        for point in BAR..=HOME {
+            opponent_frq[rules.opponent_point(point) as usize] = opp_frq[point as usize];
+        }
+        for point in 1..HOME {
+            if board.is_pinned(color, point) {
+                pinned |= 1 << local(is_flipped, point);
             }
+            if board.is_pinned(&opp_color, point) {
+                opp_pinned |= 1 << rules.opponent_point(local(!is_flipped, point));
+            }
+        }
+        for point in 1..HOME {
+            let bit = 1 << point;
+            if rules.landing(opponent_frq[point as usize], opp_pinned & bit != 0, pinned & bit != 0) != Landing::Blocked {
+                landable |= bit;
+            }
+        }
+
+        PlayerBoard {
+            player_pos: stack_pos,
+            opponent_frq,
+            is_flipped,
+            strategy: strat,
+            rules,
+            pinned,
+            landable,
         }
     }
 
-    pub fn generate_valid_turns(&self, dice: &Vec<u8>) -> Vec<Box<TurnNode>> {
-        let mut turns = self.generate_potential_turns(&dice);
+    pub fn generate_valid_turns(&self, dice: &[u8]) -> Vec<Box<TurnNode>> {
+        let mut turns = self.generate_potential_turns(dice);
         if self.is_flipped {
             for node in turns.iter_mut() { node.flip_tree(); }
         }
         turns
     }
 
-    pub fn pick_turn(&self, dice: &Vec<u8>) -> Vec<Move> {
-        let mut turns = self.generate_potential_turns(&dice);
-        let mut moves = self.strategy.choose_turn(&self.player_pos, &self.opponent_frq, &mut turns);
+    pub fn pick_turn(&self, dice: &[u8]) -> Vec<Move> {
+        let mut moves = self.with_turns(dice, |arena, roots| {
+            let mut turns = arena.turns(roots).track_position(&self.player_pos);
+            self.strategy.choose_walk(&self.player_pos, &self.opponent_frq, &mut turns)
+        });
         if self.is_flipped {
             for mve in moves.iter_mut() {
                 *mve = flip_move(mve);
@@ -70,99 +111,162 @@ impl<S: PlayerStrat> PlayerBoard<'_, S> {
         moves
     }
 
-    pub fn validate_turn(&self, dice: &Vec<u8>, moves: &Vec<Move>) -> bool {
+    fn to_world(&self, mve: &Move) -> Move {
+        //! converts a move between local and world coordinates, which is the same conversion both ways
+        if self.is_flipped { flip_move(mve) } else { mve.clone() }
+    }
+
+    pub fn score_turns(&self, dice: &[u8]) -> Vec<(Vec<Move>, usize)> {
+        //! scores every legal turn with the strategy, returns turns in world coordinates
+        self.with_turns(dice, |arena, roots| {
+            let mut scored = Vec::new();
+            let mut walk = arena.turns(roots).track_position(&self.player_pos);
+            let mut scores = vec!(self.strategy.start_turn(&self.player_pos, &self.opponent_frq));
+            let score_move = |before: &TurnScore, pos: &[u8; MAX_CHECKERS], mve: &Move, new_pos: &[u8; MAX_CHECKERS]| {
+                self.strategy.score_move(before, &self.opponent_frq, pos, mve, new_pos)
+            };
+            while let Some((moves, end_pos)) = walk.next_turn_scored(&mut scores, score_move) {
+                let score = self.strategy.score_end(scores.last().unwrap(), &self.player_pos, &self.opponent_frq, end_pos);
+                scored.push((moves.iter().map(|mve| self.to_world(mve)).collect(), score));
+            }
+            scored
+        })
+    }
+
+    pub fn legal_turns(&self, dice: &[u8]) -> Vec<Vec<Move>> {
+        //! every legal turn in world coordinates, empty if the player can't move
+        self.with_turns(dice, |arena, roots| {
+            arena.turns(roots).map(|turn| turn.iter().map(|mve| self.to_world(mve)).collect()).collect()
+        })
+    }
+
+    pub fn validate_turn(&self, dice: &[u8], moves: &[Move]) -> bool {
         //!returns true if moves constitute a valid turn
-        let turn_options = self.generate_valid_turns(dice);
-        if turn_options.is_empty() {
-            return moves.is_empty();
-        } else if turn_options[0].get_max_depth() != moves.len() as u8 { return false; }
-        return validate_move_recur(&turn_options, &moves, 0);
+        let local: Vec<Move> = moves.iter().map(|mve| self.to_world(mve)).collect();
+        self.with_turns(dice, |arena, roots| arena.contains(roots, &local))
     }
 
-    fn generate_potential_turns(&self, dice: &Vec<u8>) -> Vec<Box<TurnNode>> {
-        let mut potential_turn_set: Vec<Box<TurnNode>> = self.get_potential_moves(&self.player_pos, &DiceTracker::new(&dice), &dice);
+    pub fn check_turn(&self, dice: &[u8], moves: &[Move]) -> Result<(), IllegalTurn> {
+        //! like validate_turn, but explains an illegal turn and suggests a legal one instead
+        if self.validate_turn(dice, moves) {
+            return Ok(());
+        }
+        let legal = self.legal_turns(dice);
+        let error = self.diagnose(dice, moves, &legal);
+        Err(IllegalTurn { error, alternative: closest_turn(moves, legal) })
+    }
 
-        if potential_turn_set.is_empty() {
-            return vec!();
+    fn diagnose(&self, dice: &[u8], moves: &[Move], legal: &[Vec<Move>]) -> TurnError {
+        //! replays moves in local coordinates until one breaks a rule, then checks the turn as a whole
+        let mut pos = self.player_pos;
+        let mut unused = dice.to_vec();
+        let mut used = Vec::with_capacity(moves.len());
+        for (i, mve) in moves.iter().enumerate() {
+            if mve.start > HOME || mve.end > HOME {
+                return TurnError::Move { index: i + 1, error: MoveError::OffBoard };
+            }
+            let local = if self.is_flipped { flip_move(mve) } else { mve.clone() };
+            let die = match self.check_move(&pos, &unused, &local) {
+                Ok(die) => die,
+                Err(error) => return TurnError::Move { index: i + 1, error },
+            };
+            unused.remove(unused.iter().position(|d| *d == die).unwrap());
+            used.push(die);
+            move_checker(&mut pos, &local.start, &local.end);
         }
 
+        let possible = legal.first().map_or(0, |turn| turn.len());
+        if moves.len() < possible {
+            TurnError::UnusedDice { used: moves.len(), possible }
+        } else if possible == 1 && used.len() == 1 && dice.len() == 2 && dice[0] != dice[1] && used[0] < dice[0].max(dice[1]) {
+            TurnError::MustUseLarger
+        } else {
+            TurnError::NotLegal
+        }
+    }
+
+    fn generate_potential_turns(&self, dice: &[u8]) -> Vec<Box<TurnNode>> {
+        self.with_turns(dice, |arena, roots| roots.iter().map(|root| arena.to_tree(*root)).collect())
+    }
+
+    fn with_turns<T, F: FnOnce(&TurnArena, &[usize]) -> T>(&self, dice: &[u8], f: F) -> T {
+        //! builds every turn into the thread's arena, then calls f with the roots of the turns that make best use of dice
+        ARENA.with(|cell| {
+            // taken out of the cell while f runs, so f may generate turns of its own
+            let mut arena = cell.take();
+            arena.clear();
+            let roots = self.build_turns(&mut arena, dice);
+            let result = f(&arena, &roots);
+            cell.replace(arena);
+            result
+        })
+    }
+
+    fn build_turns(&self, arena: &mut TurnArena, dice: &[u8]) -> Vec<usize> {
+        let roots = self.get_potential_moves(&self.player_pos, &DiceTracker::new(dice), dice, arena);
+
         let mut max_depth = 0;
-        for turn in potential_turn_set.iter_mut() {
-            self.gen_turn_tree(&self.player_pos, turn, &dice);
-            let depth = turn.compute_depth();
+        for root in roots.clone() {
+            let depth = self.gen_turn_tree(&self.player_pos, root, dice, arena);
             if depth > max_depth {
                 max_depth = depth;
             }
         }
 
-        potential_turn_set.retain(|turn| turn.get_max_depth() == max_depth);
-
-        for node in potential_turn_set.iter_mut() {
-            // remove turns that don't make best use of dice
-            node.prune(&max_depth);
+        let mut potential_turn_set: Vec<usize> = roots.filter(|root| arena.get_max_depth(*root) == max_depth).collect();
+        if max_depth == 1 {
+            keep_larger_die(arena, dice, &mut potential_turn_set);
         }
         potential_turn_set
     }
 
-    fn gen_turn_tree(&self, pos: &[u8; NUM_CHECKERS], node: &mut TurnNode, dice: &Vec<u8>) {
-        if node.get_dice_tracker().is_empty() {
-            return;
+    fn gen_turn_tree(&self, pos: &[u8; MAX_CHECKERS], node: usize, dice: &[u8], arena: &mut TurnArena) -> u8 {
+        //! builds the tree after node into arena, returning its depth
+        if arena.get_dice_tracker(node).is_empty() {
+            return 1;
         }
-        let mut new_pos = pos.clone();
-        let new_mve = node.get_move();
+        let mut new_pos = *pos;
+        let new_mve = arena.get_move(node);
         move_checker(&mut new_pos,
                      &new_mve.start,
                      &new_mve.end);
 
-        let mut next_potential_move = self.get_potential_moves(&new_pos, node.get_dice_tracker(), &dice);
-        if next_potential_move.is_empty() {
-            return;
-        }
-
-        for turn in next_potential_move.iter_mut() {
-            self.gen_turn_tree(&new_pos, turn, &dice);
+        let tracker = arena.get_dice_tracker(node).clone();
+        let next_potential_move = self.get_potential_moves(&new_pos, &tracker, dice, arena);
+        for turn in next_potential_move.clone() {
+            self.gen_turn_tree(&new_pos, turn, dice, arena);
         }
-
-        node.set_branches(next_potential_move);
+        arena.set_branches(node, next_potential_move)
     }
 
-    fn get_potential_moves(&self, pos: &[u8; NUM_CHECKERS], tracker: &DiceTracker, dice: &Vec<u8>) -> Vec<Box<TurnNode>> {
-        //! returns turn trees in local coordinates
-        match in_home_phase(&pos) {
-            true => self.legal_home_moves(pos, tracker, dice),
-            false => match has_pieces_on_bar(&pos) {
-                false => self.legal_moves(pos, tracker, dice),
-                true => self.legal_bar_moves(tracker, dice),
+    fn get_potential_moves(&self, pos: &[u8; MAX_CHECKERS], tracker: &DiceTracker, dice: &[u8], arena: &mut TurnArena) -> Range<usize> {
+        //! adds nodes in local coordinates to arena, returning where they are
+        let first = arena.len();
+        match in_home_phase(pos) {
+            true => self.legal_home_moves(pos, tracker, dice, arena),
+            false => match has_pieces_on_bar(pos) {
+                false => self.legal_moves(pos, tracker, dice, arena),
+                true => self.legal_bar_moves(tracker, dice, arena),
             }
         }
+        first..arena.len()
     }
 
-    fn legal_bar_moves(&self, tracker: &DiceTracker, dice: &Vec<u8>) -> Vec<Box<TurnNode>> {
-        // returns local coordinates
+    fn legal_bar_moves(&self, tracker: &DiceTracker, dice: &[u8], moves_at_bar: &mut TurnArena) {
+        // local coordinates
         let num_loops = tracker.num_unique();
-        let mut moves_at_bar: Vec<Box<TurnNode>> = Vec::with_capacity(num_loops);
         for i in 0..num_loops {
             let die_ind = tracker.get_die_ind(&i);
-            if !(self.opponent_frq[dice[die_ind] as usize] > 1) {
-                moves_at_bar.push(
-                    Box::new(TurnNode::new(Move { start: BAR, end: dice[die_ind] }, tracker.use_die(&die_ind)))
-                );
+            if self.can_land(dice[die_ind]) {
+                moves_at_bar.push(Move { start: BAR, end: dice[die_ind] }, tracker.use_die(&die_ind));
             }
         }
-        moves_at_bar
     }
 
-    fn legal_home_moves(&self, pos: &[u8; NUM_CHECKERS], tracker: &DiceTracker, dice: &Vec<u8>) -> Vec<Box<TurnNode>> {
-        // returns local coordinates
-        if tracker.is_empty() {
-            return vec!();
-        }
-        // list of piece positions, and the potential moves
-        // available to a piece at that position
-        let mut moves: Vec<Box<TurnNode>> = Vec::with_capacity(pos.len());
-
-        if &pos[0] == &HOME {
-            return moves;
+    fn legal_home_moves(&self, pos: &[u8; MAX_CHECKERS], tracker: &DiceTracker, dice: &[u8], moves: &mut TurnArena) {
+        // local coordinates
+        if tracker.is_empty() || pos[0] == HOME {
+            return;
         }
 
         let mut largest_die = dice.iter().max().unwrap();
@@ -172,10 +276,8 @@ impl<S: PlayerStrat> PlayerBoard<'_, S> {
             largest_die = &dice[largest_i];
         }
 
-        if &(HOME - largest_die) < &pos[0] {
-            moves.push(
-                Box::new(TurnNode::new(Move { start: pos[0], end: HOME }, tracker.use_die(&largest_i)))
-            );
+        if HOME - largest_die < pos[0] && self.may_move(pos, pos[0]) {
+            moves.push(Move { start: pos[0], end: HOME }, tracker.use_die(&largest_i));
         }
 
         let num_loops: usize = tracker.num_unique();
@@ -186,17 +288,16 @@ impl<S: PlayerStrat> PlayerBoard<'_, S> {
             for piece in pos {
                 if piece == &prev_piece {
                     continue;
+                } else if piece != &HOME && !self.may_move(pos, *piece) {
+                    prev_piece = *piece;
+                    continue;
                 }
                 if &(HOME - dice[die_ind]) == piece {
-                    moves.push(
-                        Box::new(TurnNode::new(Move { start: *piece, end: HOME }, tracker.use_die(&die_ind)))
-                    );
+                    moves.push(Move { start: *piece, end: HOME }, tracker.use_die(&die_ind));
                 } else if &(HOME - dice[die_ind]) > piece {
                     let end = piece + dice[die_ind];
-                    if self.opponent_frq[end as usize] <= 1 {
-                        moves.push(
-                            Box::new(TurnNode::new(Move { start: *piece, end }, tracker.use_die(&die_ind)))
-                        );
+                    if self.can_land(end) {
+                        moves.push(Move { start: *piece, end }, tracker.use_die(&die_ind));
                     }
                 } else if piece == &HOME {
                     break;
@@ -204,16 +305,10 @@ impl<S: PlayerStrat> PlayerBoard<'_, S> {
                 prev_piece = *piece;
             }
         }
-
-        return moves;
     }
 
-    fn legal_moves(&self, pos: &[u8; NUM_CHECKERS], tracker: &DiceTracker, dice: &Vec<u8>) -> Vec<Box<TurnNode>> {
-        // returns local coordinates
-
-        // list of piece positions, and the potential moves
-        // avaialable to a piece at that position
-        let mut moves: Vec<Box<TurnNode>> = Vec::with_capacity(pos.len());
+    fn legal_moves(&self, pos: &[u8; MAX_CHECKERS], tracker: &DiceTracker, dice: &[u8], moves: &mut TurnArena) {
+        // local coordinates
 
         let num_loops: usize = tracker.num_unique();
         for i in 0..num_loops {
@@ -226,16 +321,60 @@ impl<S: PlayerStrat> PlayerBoard<'_, S> {
                     continue;
                 }
                 let end = piece + dice[die_ind];
-                if end < HOME && self.opponent_frq[end as usize] <= 1 {
-                    moves.push(
-                        Box::new(TurnNode::new(Move { start: *piece, end: end }, tracker.use_die(&die_ind)))
-                    );
+                if end < HOME && self.can_land(end) && self.may_move(pos, *piece) {
+                    moves.push(Move { start: *piece, end }, tracker.use_die(&die_ind));
                 }
                 prev_piece = *piece;
             }
         }
+    }
+
+    #[inline]
+    fn can_land(&self, point: u8) -> bool {
+        self.landable & (1 << point) != 0
+    }
+
+    #[inline]
+    fn may_move(&self, pos: &[u8; MAX_CHECKERS], start: u8) -> bool {
+        //! false if the checker on start is pinned or the rules hold it back
+        self.pinned & (1 << start) == 0 && self.rules.may_move(pos, start)
+    }
+
+    fn check_move(&self, pos: &[u8; MAX_CHECKERS], unused: &[u8], mve: &Move) -> Result<u8, MoveError> {
+        //! checks a move in local coordinates, returning the die it uses
+        if mve.start == HOME || count_occur(pos, &mve.start) == 0 {
+            return Err(MoveError::NoChecker);
+        } else if pos[0] == BAR && mve.start != BAR {
+            return Err(MoveError::MustEnter);
+        } else if mve.end <= mve.start {
+            return Err(MoveError::Backwards);
+        } else if self.pinned & (1 << mve.start) != 0 {
+            return Err(MoveError::Pinned);
+        } else if !self.rules.may_move(pos, mve.start) {
+            return Err(MoveError::Forbidden);
+        }
+
+        let distance = mve.end - mve.start;
+        if mve.end != HOME {
+            if !unused.contains(&distance) {
+                return Err(MoveError::NoSuchDie);
+            } else if !self.can_land(mve.end) {
+                return Err(MoveError::Blocked);
+            }
+            return Ok(distance);
+        }
 
-        return moves;
+        if pos[0] < HOME_EDGE {
+            return Err(MoveError::BearOffEarly);
+        } else if unused.contains(&distance) {
+            return Ok(distance);
+        }
+        // like the move generator, an inexact bear off uses the largest die
+        match unused.iter().max() {
+            Some(die) if *die > distance && pos[0] < mve.start => Err(MoveError::NotFurthest),
+            Some(die) if *die > distance => Ok(*die),
+            _ => Err(MoveError::NoSuchDie),
+        }
     }
 }
 
@@ -266,37 +405,25 @@ pub fn flip_move(mve: &Move) -> Move {
     Move { start, end }
 }
 
-fn invert_pos(pieces: &mut [u8; NUM_CHECKERS]) {
+fn invert_pos(pieces: &mut [u8; MAX_CHECKERS]) {
     //! returns local coordinates
     let mut last_bar = 0;
-    let mut first_home = NUM_CHECKERS;
-    for i in 0..NUM_CHECKERS {
-        if pieces[i] == HOME {
+    let mut first_home = MAX_CHECKERS;
+    for (i, piece) in pieces.iter_mut().enumerate() {
+        if *piece == HOME {
             first_home = i;
             break;
-        } else if pieces[i] == BAR {
+        } else if *piece == BAR {
             last_bar = i + 1;
         } else {
-            pieces[i] = HOME - pieces[i];
+            *piece = HOME - *piece;
         }
     }
     pieces[last_bar..first_home].reverse();
 }
 
-fn validate_move_recur(turn_options: &Vec<Box<TurnNode>>, moves: &Vec<Move>, index: usize) -> bool {
-    if moves.len() == index { return true; }
-    let mve = &moves[index];
-    for node in turn_options.iter() {
-        let node_move = node.get_move();
-        if node_move.start == mve.start && node_move.end == mve.end {
-            return validate_move_recur(&node.get_branches(), moves, index + 1);
-        }
-    }
-    return false;
-}
-
 #[inline]
-fn pos_to_freqs(pieces: &[u8; NUM_CHECKERS]) -> Vec<u8> {
+fn pos_to_freqs(pieces: &[u8; MAX_CHECKERS]) -> Vec<u8> {
     //! tallies number of checkers at each position
     let mut frequencies = vec![0; HOME as usize + 1];
     for piece in pieces {
@@ -306,7 +433,7 @@ fn pos_to_freqs(pieces: &[u8; NUM_CHECKERS]) -> Vec<u8> {
 }
 
 #[inline]
-fn pos_to_inv_freqs(pieces: &[u8; NUM_CHECKERS]) -> Vec<u8> {
+fn pos_to_inv_freqs(pieces: &[u8; MAX_CHECKERS]) -> Vec<u8> {
     //! tallies number of checkers at each inverted position
     let mut frequencies = vec![0; HOME as usize + 1];
     for piece in pieces {
@@ -320,31 +447,119 @@ fn pos_to_inv_freqs(pieces: &[u8; NUM_CHECKERS]) -> Vec<u8> {
     frequencies
 }
 
+fn keep_larger_die(arena: &TurnArena, dice: &[u8], roots: &mut Vec<usize>) {
+    //! when either die can be played but not both, only turns playing the larger are kept if there are any
+    if dice.len() != 2 || dice[0] == dice[1] {
+        return;
+    }
+    let larger = if dice[0] > dice[1] { 0 } else { 1 };
+    if roots.iter().any(|root| !arena.get_dice_tracker(*root).is_valid(larger)) {
+        roots.retain(|root| !arena.get_dice_tracker(*root).is_valid(larger));
+    }
+}
+
 #[inline(always)]
-fn in_home_phase(pos: &[u8; NUM_CHECKERS]) -> bool {
+fn in_home_phase(pos: &[u8; MAX_CHECKERS]) -> bool {
     unsafe { *pos.get_unchecked(0) >= HOME_EDGE }
 }
 
 #[inline(always)]
-fn has_pieces_on_bar(pos: &[u8; NUM_CHECKERS]) -> bool {
+fn has_pieces_on_bar(pos: &[u8; MAX_CHECKERS]) -> bool {
     unsafe { *pos.get_unchecked(0) == BAR }
 }
 
+/// The running score of a turn's first moves, handed from each move to the next, so turns that start alike share it
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct TurnScore {
+    pub score: usize,
+    /// bit p is set once a checker on point p has been bopped, a point can only be bopped once a turn
+    pub bopped: u32,
+    /// neither player can bop the other any more, so the rest of the turn is a race
+    pub racing: bool,
+}
+
 pub trait PlayerStrat: Clone + 'static {
     // 'static guarantees structs that impl PlayerStrat won't contain references
+    /// The running score before the first move of a turn from player_pos
+    fn start_turn(&self, _player_pos: &[u8; MAX_CHECKERS], _opp_frq: &[u8]) -> TurnScore {
+        TurnScore::default()
+    }
+
+    /// Adds mve, which takes the player's checkers from pos to new_pos, to the running score of the moves before it
+    fn score_move(&self, before: &TurnScore, _opp_frq: &[u8], _pos: &[u8; MAX_CHECKERS], _mve: &Move, _new_pos: &[u8; MAX_CHECKERS]) -> TurnScore {
+        *before
+    }
+
+    /// Scores a complete turn from player_pos, which leaves the player's checkers at end_pos, from the running score of its moves, higher is better
+    fn score_end(&self, moves: &TurnScore, _player_pos: &[u8; MAX_CHECKERS], _opp_frq: &[u8], _end_pos: &[u8; MAX_CHECKERS]) -> usize {
+        moves.score
+    }
+
+    /// Scores one complete turn from player_pos, a move at a time like choose_walk
+    fn score_turn(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8], moves: &[Move]) -> usize {
+        let mut pos = *player_pos;
+        let mut score = self.start_turn(player_pos, opp_frq);
+        for mve in moves {
+            let before = pos;
+            move_checker(&mut pos, &mve.start, &mve.end);
+            score = self.score_move(&score, opp_frq, &before, mve, &pos);
+        }
+        self.score_end(&score, player_pos, opp_frq, &pos)
+    }
+
     /// Scores given trees, and returns vector of chosen moves
-    /// NOTE: trees should only be modified through node.set_score()
-    fn choose_turn(&self, _player_pos: &[u8; NUM_CHECKERS], _opp_frq: &Vec<u8>, _turns: &mut Vec<Box<TurnNode>>) -> Vec<Move>;
+    /// NOTE: PlayerBoard::pick_turn no longer builds trees and calls choose_walk instead,
+    /// strategies that override choose_turn must override choose_walk to keep choosing their turns
+    #[allow(clippy::ptr_arg)]
+    fn choose_turn(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &Vec<u8>, turns: &mut Vec<Box<TurnNode>>) -> Vec<Move> {
+        best_turn(self, player_pos, opp_frq, &mut Turns::with_position(turns, player_pos))
+    }
+
+    /// Returns the best scoring turn, ties are broken at random, turns must track positions from player_pos
+    fn choose_walk(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8], turns: &mut Turns) -> Vec<Move> {
+        best_turn(self, player_pos, opp_frq, turns)
+    }
+}
+
+pub fn best_turn<S: PlayerStrat>(strat: &S, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8], turns: &mut Turns) -> Vec<Move> {
+    //! scores every turn with strat, keeping the best, turns must track positions. Moves turns share are scored once
+    let mut rng = rand::thread_rng();
+    let mut best: Vec<Move> = Vec::with_capacity(4);
+    let mut best_score = 0;
+    let mut ties: u32 = 0;
+    let mut scores = vec!(strat.start_turn(player_pos, opp_frq));
+    let score_move = |before: &TurnScore, pos: &[u8; MAX_CHECKERS], mve: &Move, new_pos: &[u8; MAX_CHECKERS]| {
+        strat.score_move(before, opp_frq, pos, mve, new_pos)
+    };
+    while let Some((moves, end_pos)) = turns.next_turn_scored(&mut scores, score_move) {
+        let score = strat.score_end(scores.last().unwrap(), player_pos, opp_frq, end_pos);
+        if ties == 0 || score > best_score {
+            best_score = score;
+            ties = 1;
+        } else if score == best_score {
+            // every tie is equally likely to be kept
+            ties += 1;
+            if rng.gen_range(0..ties) != 0 {
+                continue;
+            }
+        } else {
+            continue;
+        }
+        best.clear();
+        best.extend_from_slice(moves);
+    }
+    best
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::r#mod::{BAR, HOME};
+    use crate::start_position::StartPosition;
     use crate::strategy::rando::Rando;
 
-    const TEST_POSITIONS: [u8; NUM_CHECKERS] = [3, 3, 5, 5, 7, 8, 9, 11, 11, 12, 13, 13, 13, 13, 15];
-    const HOME_POSITIONS: [u8; NUM_CHECKERS] = [19, 20, 20, 20, 21, 22, 22, 23, 23, 23, 23, 24, 24, 25, 25];
+    const TEST_POSITIONS: [u8; MAX_CHECKERS] = [3, 3, 5, 5, 7, 8, 9, 11, 11, 12, 13, 13, 13, 13, 15];
+    const HOME_POSITIONS: [u8; MAX_CHECKERS] = [19, 20, 20, 20, 21, 22, 22, 23, 23, 23, 23, 24, 24, 25, 25];
 
     #[test]
     fn in_home() {
@@ -383,23 +598,61 @@ mod tests {
     #[test]
     fn inv_pos() {
         let mut tst_pos = TEST_POSITIONS;
-        let inv_tst: [u8; NUM_CHECKERS] = [10, 12, 12, 12, 12, 13, 14, 14, 16, 17, 18, 20, 20, 22, 22];
+        let inv_tst: [u8; MAX_CHECKERS] = [10, 12, 12, 12, 12, 13, 14, 14, 16, 17, 18, 20, 20, 22, 22];
         invert_pos(&mut tst_pos);
         assert_eq!(tst_pos, inv_tst);
 
         let mut hme_pos = HOME_POSITIONS;
-        let inv_hm: [u8; NUM_CHECKERS] = [1, 1, 2, 2, 2, 2, 3, 3, 4, 5, 5, 5, 6, 25, 25];
+        let inv_hm: [u8; MAX_CHECKERS] = [1, 1, 2, 2, 2, 2, 3, 3, 4, 5, 5, 5, 6, 25, 25];
         invert_pos(&mut hme_pos);
         assert_eq!(hme_pos, inv_hm);
     }
 
+    #[test]
+    fn explain_illegal() {
+        let explain = |color: PlayerColor, board: &Board, dice: Vec<u8>, moves: Vec<(u8, u8)>| {
+            let moves: Vec<Move> = moves.into_iter().map(|(start, end)| Move { start, end }).collect();
+            PlayerBoard::new(&color, board, &Rando, AnyRules::default()).check_turn(&dice, &moves).map_err(|illegal| illegal.error.to_string())
+        };
+        let start = Board::new();
+        assert_eq!(explain(PlayerColor::White, &start, vec!(3, 1), vec!((12, 15), (15, 16))), Ok(()));
+        assert_eq!(explain(PlayerColor::White, &start, vec!(3, 1), vec!((12, 15), (14, 15))).unwrap_err(),
+                   "move 2 starts from a point with no checker");
+        assert_eq!(explain(PlayerColor::White, &start, vec!(5, 2), vec!((1, 6), (6, 8))).unwrap_err(), "move 1 lands on a blocked point");
+        assert_eq!(explain(PlayerColor::Black, &start, vec!(5, 2), vec!((24, 19), (19, 17))).unwrap_err(), "move 1 lands on a blocked point");
+        assert_eq!(explain(PlayerColor::White, &start, vec!(2, 1), vec!((12, 10), (10, 9))).unwrap_err(), "move 1 moves backwards");
+        assert_eq!(explain(PlayerColor::White, &start, vec!(3, 1), vec!((12, 16))).unwrap_err(), "move 1 doesn't match an unused die");
+        assert_eq!(explain(PlayerColor::White, &start, vec!(6, 1), vec!((19, HOME), (1, 2))).unwrap_err(),
+                   "move 1 bears off before all checkers are home");
+        assert_eq!(explain(PlayerColor::White, &start, vec!(3, 1), vec!((12, 15))).unwrap_err(), "does not use both dice when possible");
+
+        let on_bar = Board::from_positions(
+            [6, 6, 6, 6, 6, 8, 8, 8, 13, 13, 13, 13, 13, 24, 24],
+            [BAR, 1, 12, 12, 12, 12, 12, 17, 17, 17, 19, 19, 19, 19, 19]);
+        assert_eq!(explain(PlayerColor::White, &on_bar, vec!(3, 1), vec!((12, 15), (BAR, 1))).unwrap_err(),
+                   "move 1 must enter from the bar first");
+
+        // either die can be played, but not both
+        let one_die = Board::from_positions(
+            [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 17, 17],
+            [10, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME]);
+        assert_eq!(explain(PlayerColor::White, &one_die, vec!(2, 5), vec!((10, 12))).unwrap_err(),
+                   "must use the larger die when only one can be used");
+
+        let illegal = PlayerBoard::new(&PlayerColor::White, &start, &Rando, AnyRules::default())
+            .check_turn(&vec!(3, 1), &vec!(Move { start: 12, end: 15 }))
+            .unwrap_err();
+        assert_eq!(illegal.alternative.len(), 2);
+        assert_eq!(illegal.alternative[0], Move { start: 12, end: 15 });
+    }
+
     #[test]
     fn val_bar_no_move() {
-        let board = Board {
-            black: [BAR, BAR, 3, 6, 9, 9, 12, 12, 13, 13, 13, 23, 23, 24, 24],
-            white: [5, 10, 11, 14, 14, 16, 16, 17, 18, 19, 20, 20, 21, 21, 22] };
+        let board = Board::from_positions(
+            [BAR, BAR, 3, 6, 9, 9, 12, 12, 13, 13, 13, 23, 23, 24, 24],
+            [5, 10, 11, 14, 14, 16, 16, 17, 18, 19, 20, 20, 21, 21, 22]);
         let dice: Vec<u8> = vec!(4, 5);
-        let pl_board = PlayerBoard::new(&PlayerColor::Black, &board, &Rando);
+        let pl_board = PlayerBoard::new(&PlayerColor::Black, &board, &Rando, AnyRules::default());
 
         for t in pl_board.generate_valid_turns(&dice).iter() {
             println!("{:?}", t.get_move());
@@ -420,11 +673,11 @@ mod tests {
 
     #[test]
     fn val_bar_one_move() {
-        let board = Board {
-            black: [BAR, BAR, 3, 6, 16, 9, 12, 12, 13, 13, 13, 23, 23, 24, 24],
-            white: [5, 10, 11, 14, 14, 16, 16, 17, 18, 19, 20, 21, 21, 21, 22] };
+        let board = Board::from_positions(
+            [BAR, BAR, 3, 6, 16, 9, 12, 12, 13, 13, 13, 23, 23, 24, 24],
+            [5, 10, 11, 14, 14, 16, 16, 17, 18, 19, 20, 21, 21, 21, 22]);
         let dice: Vec<u8> = vec!(4, 5);
-        let pl_board = PlayerBoard::new(&PlayerColor::Black, &board, &Rando);
+        let pl_board = PlayerBoard::new(&PlayerColor::Black, &board, &Rando, AnyRules::default());
 
         println!("{:?}", pl_board.player_pos);
 
@@ -441,4 +694,93 @@ mod tests {
         assert!(!pl_board.validate_turn(&dice, &fake_moves_4));
         assert!(!pl_board.validate_turn(&dice, &fake_moves_5));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn pinned_checkers() {
+        let mut board = Board::with_checkers(&[3, 20], &[3, 10]);
+        board.set_pinned(&PlayerColor::White, 3, true);
+        let plakoto: AnyRules = "plakoto".parse().unwrap();
+        let pl_board = PlayerBoard::new(&PlayerColor::White, &board, &Rando, plakoto);
+        let turns = pl_board.legal_turns(&vec!(2, 1));
+        assert!(!turns.is_empty() && turns.iter().all(|turn| turn.iter().all(|mve| mve.start != 3)));
+        let illegal = pl_board.check_turn(&vec!(2, 1), &vec!(Move { start: 3, end: 5 }, Move { start: 10, end: 11 })).unwrap_err();
+        assert_eq!(illegal.error.to_string(), "move 1 moves a pinned checker");
+        // a point black holds with one checker is open, the checker there is pinned rather than hit
+        assert!(pl_board.validate_turn(&vec!(6, 4), &vec!(Move { start: 10, end: 16 }, Move { start: 16, end: 20 })));
+
+        let fevga: AnyRules = "fevga".parse().unwrap();
+        let pl_board = PlayerBoard::new(&PlayerColor::White, &StartPosition::Stacked.board(), &Rando, fevga);
+        // only one checker may leave the start, so it plays both dice
+        let turns = pl_board.legal_turns(&vec!(3, 2));
+        assert_eq!(turns.len(), 2);
+        assert!(turns.iter().all(|turn| turn.len() == 2 && turn[1].start == turn[0].end));
+    }
+
+    #[test]
+    fn larger_die() {
+        let turns = |black: [u8; MAX_CHECKERS], dice: Vec<u8>| {
+            let board = Board::from_positions(black, [10, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME, HOME]);
+            PlayerBoard::new(&PlayerColor::White, &board, &Rando, AnyRules::default()).legal_turns(&dice)
+        };
+        let one_die = [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 17, 17];
+        // either die can be played, but not both, so the larger must be
+        assert_eq!(turns(one_die, vec!(2, 5)), vec!(vec!(Move { start: 10, end: 15 })));
+        assert_eq!(turns(one_die, vec!(5, 2)), vec!(vec!(Move { start: 10, end: 15 })));
+        // the smaller is played when the larger can't be
+        let larger_blocked = [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 15, 15, 17, 17];
+        assert_eq!(turns(larger_blocked, vec!(2, 5)), vec!(vec!(Move { start: 10, end: 12 })));
+        // either die can be played first when both can be
+        assert_eq!(turns([1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1], vec!(2, 5)).len(), 2);
+    }
+
+    #[derive(Clone)]
+    struct LookAround;
+
+    impl PlayerStrat for LookAround {
+        fn choose_walk(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8], turns: &mut Turns) -> Vec<Move> {
+            // generates other turns while turns walks the arena
+            let board = PlayerBoard::new(&PlayerColor::Black, &Board::new(), &Rando, AnyRules::default());
+            assert!(board.validate_turn(&vec!(6, 5), &vec!(Move { start: 24, end: 18 }, Move { start: 18, end: 13 })));
+            assert_eq!(board.pick_turn(&vec!(6, 5)).len(), 2);
+            best_turn(self, player_pos, opp_frq, turns)
+        }
+    }
+
+    #[test]
+    fn nested_turns() {
+        let board = Board::new();
+        let pl_board = PlayerBoard::new(&PlayerColor::White, &board, &LookAround, AnyRules::default());
+        let legal = pl_board.legal_turns(&vec!(3, 1));
+        let picked = pl_board.pick_turn(&vec!(3, 1));
+        assert!(legal.contains(&picked));
+        assert!(pl_board.validate_turn(&vec!(3, 1), &picked));
+    }
+
+    #[test]
+    fn picks_best_score() {
+        use crate::strategy::{bopsy::Bopsy, smarty::Smarty};
+        let board = StartPosition::BackGame.board();
+        for dice in [vec!(6, 5), vec!(3, 1), vec!(2, 2, 2, 2)].iter() {
+            for color in [PlayerColor::White, PlayerColor::Black].iter() {
+                let smarty = Smarty::new();
+                let pl_board = PlayerBoard::new(color, &board, &smarty, AnyRules::default());
+                let scored = pl_board.score_turns(dice);
+                let best = scored.iter().map(|(_, score)| *score).max().unwrap();
+                let picked = pl_board.pick_turn(dice);
+                assert!(scored.iter().any(|(turn, score)| *turn == picked && *score == best));
+
+                // the tree signature picks as well as the walk
+                let mut trees = pl_board.generate_potential_turns(dice);
+                let chosen: Vec<Move> = smarty.choose_turn(&pl_board.player_pos, &pl_board.opponent_frq, &mut trees)
+                    .iter().map(|mve| pl_board.to_world(mve)).collect();
+                assert!(scored.iter().any(|(turn, score)| *turn == chosen && *score == best));
+
+                let pl_board = PlayerBoard::new(color, &board, &Bopsy, AnyRules::default());
+                let scored = pl_board.score_turns(dice);
+                let best = scored.iter().map(|(_, score)| *score).max().unwrap();
+                let picked = pl_board.pick_turn(dice);
+                assert!(scored.iter().any(|(turn, score)| *turn == picked && *score == best));
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Versioning for the JSON protocol between RemotePlayer and NetworkPlayer.
+//! The server opens with {"hello": {"version": n, "capabilities": [..]}} and the client
+//! answers in kind. Clients that answer anything else speak the legacy protocol,
+//! and are only sent "name", start-game, take-turn and end-game.
+
+use serde_json::{json, Value};
+
+pub const PROTOCOL_VERSION: u64 = 2;
+/// version of clients that don't answer hello
+pub const LEGACY_VERSION: u64 = 1;
+
+/// Extended messages, each only sent to clients that support them
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Capability {
+    /// {"session": token} after "name", and {"reconnect": token} on new connections
+    Session,
+    /// "secret", answered with the tournament's shared secret
+    Secret,
+    /// {"rejected": reason} before a refused player is disconnected
+    Reject,
+    /// a third start-game element, {"name": name, "board": board} for where the checkers start
+    Start,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 4] = [Capability::Session, Capability::Secret, Capability::Reject, Capability::Start];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Session => "session",
+            Capability::Secret => "secret",
+            Capability::Reject => "reject",
+            Capability::Start => "start-position",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Capability> {
+        Capability::ALL.iter().find(|cap| cap.as_str() == name).copied()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Protocol {
+    pub version: u64,
+    capabilities: Vec<Capability>,
+}
+
+impl Protocol {
+    pub fn new(version: u64, capabilities: &[Capability]) -> Protocol {
+        Protocol { version, capabilities: capabilities.to_vec() }
+    }
+
+    pub fn legacy() -> Protocol {
+        Protocol::new(LEGACY_VERSION, &[])
+    }
+
+    pub fn current() -> Protocol {
+        //! everything this build supports
+        Protocol::new(PROTOCOL_VERSION, &Capability::ALL)
+    }
+
+    #[inline]
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    pub fn hello(&self) -> Value {
+        let capabilities: Vec<&str> = self.capabilities.iter().map(|cap| cap.as_str()).collect();
+        json!({ "hello": { "version": self.version, "capabilities": capabilities } })
+    }
+
+    pub fn from_hello(val: &Value) -> Option<Protocol> {
+        //! reads a hello message, ignoring capabilities this build doesn't know
+        let hello = val.get("hello")?;
+        let version = hello.get("version")?.as_u64()?;
+        let capabilities = hello.get("capabilities")?.as_array()?.iter()
+            .filter_map(|cap| cap.as_str().and_then(Capability::parse))
+            .collect();
+        Some(Protocol { version, capabilities })
+    }
+
+    pub fn negotiate(&self, other: &Protocol) -> Protocol {
+        //! the protocol both sides support
+        let capabilities = self.capabilities.iter().filter(|cap| other.supports(**cap)).copied().collect();
+        Protocol { version: self.version.min(other.version), capabilities }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hello_round_trip() {
+        let current = Protocol::current();
+        assert_eq!(Protocol::from_hello(&current.hello()), Some(current));
+        assert_eq!(Protocol::from_hello(&json!("error reading json input")), None);
+        assert_eq!(Protocol::from_hello(&json!({"hello": {"version": "2"}})), None);
+    }
+
+    #[test]
+    fn negotiation() {
+        let client = Protocol::from_hello(&json!({
+            "hello": { "version": 3, "capabilities": ["session", "cube", "reject"] }
+        })).unwrap();
+        let agreed = Protocol::current().negotiate(&client);
+        assert_eq!(agreed.version, PROTOCOL_VERSION);
+        assert!(agreed.supports(Capability::Session));
+        assert!(agreed.supports(Capability::Reject));
+        assert!(!agreed.supports(Capability::Secret));
+
+        let legacy = Protocol::current().negotiate(&Protocol::legacy());
+        assert_eq!(legacy, Protocol::legacy());
+    }
+}
@@ -0,0 +1,222 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::{
+    board::Board,
+    observer::AdminObserver,
+    parser::get_moves,
+    player::PlayerColor,
+    r#move::Move,
+    spectator::GameEvent,
+};
+
+/**
+Observer that writes a game to a record, one spectator event per line,
+so records can be replayed or fed to anything that understands the spectator stream.
+*/
+pub struct GameRecorder<W: Write + Send> {
+    writer: W,
+    game: u64,
+}
+
+impl GameRecorder<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P, game: u64) -> io::Result<GameRecorder<BufWriter<File>>> {
+        Ok(GameRecorder::new(BufWriter::new(File::create(path)?), game))
+    }
+}
+
+impl<W: Write + Send> GameRecorder<W> {
+    pub fn new(writer: W, game: u64) -> GameRecorder<W> {
+        GameRecorder { writer, game }
+    }
+
+    fn record(&mut self, event: &GameEvent) {
+        // a failed write shouldn't stop the game being recorded
+        writeln!(self.writer, "{}", event.to_json(self.game)).ok();
+    }
+}
+
+impl<W: Write + Send> AdminObserver for GameRecorder<W> {
+    fn game_started(&mut self, black: &str, white: &str) {
+        self.record(&GameEvent::GameStarted { black, white });
+    }
+
+    fn dice_rolled(&mut self, color: &PlayerColor, dice: &[u8]) {
+        self.record(&GameEvent::Roll { color: color.clone(), dice });
+    }
+
+    fn turn_submitted(&mut self, color: &PlayerColor, moves: &[Move], board: &Board) {
+        self.record(&GameEvent::Turn { color: color.clone(), moves, board });
+    }
+
+    fn turn_rejected(&mut self, color: &PlayerColor, moves: &[Move]) {
+        self.record(&GameEvent::TurnRejected { color: color.clone(), moves });
+    }
+
+    fn player_cheated(&mut self, color: &PlayerColor) {
+        self.record(&GameEvent::Cheated { color: color.clone() });
+    }
+
+    fn player_replaced(&mut self, color: &PlayerColor, replacement: &str) {
+        self.record(&GameEvent::Replaced { color: color.clone(), name: replacement });
+    }
+
+    fn game_over(&mut self, winner: Option<&PlayerColor>, board: &Board) {
+        self.record(&GameEvent::GameEnded { winner: winner.cloned(), board });
+        self.writer.flush().ok();
+    }
+}
+
+/// Something that happened in a recorded game, in the order it happened
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub enum RecordStep {
+    /// board is the board after the moves were made
+    Turn { color: PlayerColor, dice: Vec<u8>, moves: Vec<Move>, board: Board },
+    Rejected { color: PlayerColor, moves: Vec<Move> },
+    Cheated { color: PlayerColor },
+    Replaced { color: PlayerColor, name: String },
+}
+
+/// A game read back from a record
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub struct GameRecord {
+    pub game: u64,
+    pub black: String,
+    pub white: String,
+    pub steps: Vec<RecordStep>,
+    /// None until the game ends, or if both players cheated
+    pub winner: Option<PlayerColor>,
+    pub finished: bool,
+}
+
+impl GameRecord {
+    pub fn read_all<R: BufRead>(reader: R) -> Result<Vec<GameRecord>, String> {
+        //! reads every game in a record, games that were cut short are kept unfinished
+        let mut games: Vec<GameRecord> = Vec::new();
+        let mut dice: Vec<u8> = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: Value = serde_json::from_str(&line)
+                .map_err(|e| format!("line {} is not JSON: {}", i + 1, e))?;
+            let err = |what: &str| format!("line {} has a bad {}", i + 1, what);
+            let game = event["game"].as_u64().ok_or_else(|| err("game id"))?;
+            let kind = event["event"].as_str().ok_or_else(|| err("event"))?;
+
+            if kind == "game-started" {
+                games.push(GameRecord {
+                    game,
+                    black: event["black"].as_str().unwrap_or_default().to_string(),
+                    white: event["white"].as_str().unwrap_or_default().to_string(),
+                    steps: Vec::new(),
+                    winner: None,
+                    finished: false,
+                });
+                continue;
+            }
+            let record = games.iter_mut().rev().find(|g| g.game == game)
+                .ok_or_else(|| format!("line {} is from game {}, which never started", i + 1, game))?;
+            let color = || serde_json::from_value::<PlayerColor>(event["color"].clone()).map_err(|_| err("color"));
+            let moves = || event["turn"].as_array().map(get_moves).ok_or_else(|| err("turn"));
+            match kind {
+                "roll" => {
+                    dice = serde_json::from_value(event["dice"].clone()).map_err(|_| err("roll"))?;
+                }
+                "turn" => {
+                    let board = serde_json::from_value(event["board"].clone()).map_err(|_| err("board"))?;
+                    record.steps.push(RecordStep::Turn { color: color()?, dice: dice.clone(), moves: moves()?, board });
+                }
+                "turn-rejected" => record.steps.push(RecordStep::Rejected { color: color()?, moves: moves()? }),
+                "cheated" => record.steps.push(RecordStep::Cheated { color: color()? }),
+                "replaced" => {
+                    let name = event["name"].as_str().unwrap_or_default().to_string();
+                    record.steps.push(RecordStep::Replaced { color: color()?, name });
+                }
+                "game-ended" => {
+                    record.winner = serde_json::from_value(event["winner"].clone()).ok();
+                    record.finished = true;
+                }
+                other => return Err(format!("line {} has unknown event '{}'", i + 1, other)),
+            }
+        }
+        Ok(games)
+    }
+
+    pub fn name(&self, color: &PlayerColor) -> &str {
+        match color {
+            PlayerColor::Black => &self.black,
+            PlayerColor::White => &self.white,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+    use crate::{
+        administrator::{Administrator, HandleCheater, Winner},
+        local_remote::LocalPlayer,
+        strategy::{bopsy::Bopsy, rando::Rando},
+    };
+
+    /// lets the test read what the recorder wrote after the administrator drops it
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.lock().unwrap().write(buf) }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn record_and_read() {
+        let out = Shared::default();
+        let mut admin = Administrator::seeded(
+            Box::new(LocalPlayer::new("rando".to_string(), Rando)),
+            Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+            PlayerColor::Black, 11);
+        admin.add_observer(Box::new(GameRecorder::new(out.clone(), 4)));
+        admin.moderate_game(HandleCheater::EndGame);
+
+        let bytes = out.0.lock().unwrap().clone();
+        let games = GameRecord::read_all(Cursor::new(bytes)).unwrap();
+        assert_eq!(games.len(), 1);
+        let game = &games[0];
+        assert_eq!((game.game, game.black.as_str(), game.white.as_str()), (4, "rando", "bopsy"));
+        assert!(game.finished);
+        assert_eq!(game.steps.len(), admin.get_num_turns());
+        let winner = match admin.get_winner() {
+            Winner::PlayerOne => Some(PlayerColor::Black),
+            Winner::PlayerTwo => Some(PlayerColor::White),
+            Winner::None => None,
+        };
+        assert_eq!(game.winner, winner);
+        match game.steps.last() {
+            Some(RecordStep::Turn { board, dice, .. }) => {
+                assert!(dice.len() == 2 || dice.len() == 4);
+                assert_eq!(board, admin.get_board());
+            }
+            other => panic!("expected the last step to be a turn, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bad_records() {
+        let unstarted = r#"{"game": 2, "event": "cheated", "color": "black"}"#;
+        assert!(GameRecord::read_all(Cursor::new(unstarted)).unwrap_err().contains("never started"));
+        assert!(GameRecord::read_all(Cursor::new("nope")).unwrap_err().starts_with("line 1"));
+
+        let cut_short = r#"{"game": 1, "event": "game-started", "black": "a", "white": "b"}
+{"game": 1, "event": "cheated", "color": "white"}"#;
+        let games = GameRecord::read_all(Cursor::new(cut_short)).unwrap();
+        assert!(!games[0].finished);
+        assert_eq!(games[0].name(&PlayerColor::White), "b");
+    }
+}
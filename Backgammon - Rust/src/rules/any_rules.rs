@@ -0,0 +1,94 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    board::{Board, Undo},
+    player::PlayerColor,
+    r#mod::MAX_CHECKERS,
+    r#move::Move,
+    rules::{
+        base::{Landing, Rules},
+        fevga::Fevga,
+        plakoto::Plakoto,
+        standard::Standard,
+    },
+    start_position::StartPosition,
+};
+
+/// Rules chosen at runtime, e.g. from a command line argument or config file
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnyRules {
+    Standard(Standard),
+    Plakoto(Plakoto),
+    Fevga(Fevga),
+}
+
+impl AnyRules {
+    pub const ALL: [AnyRules; 3] = [AnyRules::Standard(Standard), AnyRules::Plakoto(Plakoto), AnyRules::Fevga(Fevga)];
+
+    fn rules(&self) -> &dyn Rules {
+        match self {
+            AnyRules::Standard(rules) => rules,
+            AnyRules::Plakoto(rules) => rules,
+            AnyRules::Fevga(rules) => rules,
+        }
+    }
+}
+
+impl Default for AnyRules {
+    fn default() -> AnyRules { AnyRules::Standard(Standard) }
+}
+
+impl Rules for AnyRules {
+    fn name(&self) -> &'static str { self.rules().name() }
+
+    fn start(&self) -> StartPosition { self.rules().start() }
+
+    fn opponent_point(&self, point: u8) -> u8 { self.rules().opponent_point(point) }
+
+    fn landing(&self, opponent: u8, opponent_pinned: bool, pinned: bool) -> Landing {
+        self.rules().landing(opponent, opponent_pinned, pinned)
+    }
+
+    fn may_move(&self, pos: &[u8; MAX_CHECKERS], start: u8) -> bool { self.rules().may_move(pos, start) }
+
+    fn make_move(&self, board: &mut Board, player: &PlayerColor, mve: &Move) -> Undo {
+        self.rules().make_move(board, player, mve)
+    }
+
+    fn winner(&self, board: &Board) -> Option<PlayerColor> { self.rules().winner(board) }
+}
+
+impl FromStr for AnyRules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<AnyRules, String> {
+        let name = s.to_lowercase();
+        match name.as_str() {
+            "backgammon" => Ok(AnyRules::default()),
+            _ => AnyRules::ALL.iter().find(|rules| rules.name() == name).copied()
+                .ok_or_else(|| format!("unknown rules '{}', expected standard, plakoto or fevga", s)),
+        }
+    }
+}
+
+impl fmt::Display for AnyRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for rules in AnyRules::ALL.iter() {
+            assert_eq!(rules.name().parse::<AnyRules>().unwrap(), *rules);
+        }
+        assert_eq!("Backgammon".parse::<AnyRules>().unwrap(), AnyRules::default());
+        assert_eq!("PLAKOTO".parse::<AnyRules>().unwrap().start(), StartPosition::Stacked);
+        assert!("tavla".parse::<AnyRules>().is_err());
+    }
+}
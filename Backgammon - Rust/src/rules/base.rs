@@ -0,0 +1,67 @@
+use crate::{
+    board::{Board, Undo},
+    player::PlayerColor,
+    r#mod::{BAR, HOME, MAX_CHECKERS},
+    r#move::Move,
+    start_position::StartPosition,
+};
+
+/// What a checker does to the opponent's checkers on the point it lands on
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Landing {
+    Open,
+    /// the opponent's lone checker is sent to the bar
+    Hit,
+    /// the opponent's lone checker can't move until the pinning checkers leave
+    Pin,
+    Blocked,
+}
+
+/**
+The rules of a tables game, such as backgammon.
+Points are in the player's local coordinates, where checkers always move from BAR to HOME,
+so the same rules serve both players
+*/
+pub trait Rules {
+    fn name(&self) -> &'static str;
+
+    /// where the checkers are when a game starts, unless another start is chosen
+    fn start(&self) -> StartPosition;
+
+    /// the point, in the player's coordinates, of the opponent's checker on point in the opponent's coordinates
+    fn opponent_point(&self, point: u8) -> u8;
+
+    /**
+    what landing on a point does, given how many of the opponent's checkers are there,
+    whether the opponent's checker is pinned there, and whether the player's own checker is
+    */
+    fn landing(&self, opponent: u8, opponent_pinned: bool, pinned: bool) -> Landing;
+
+    /// false if the checker on start can't move yet, on top of the usual rules
+    fn may_move(&self, _pos: &[u8; MAX_CHECKERS], _start: u8) -> bool { true }
+
+    /// plays a legal move in world coordinates, doing whatever landing does, Board::unmake_move takes it back
+    fn make_move(&self, board: &mut Board, player: &PlayerColor, mve: &Move) -> Undo;
+
+    /// the player who has won, if the game is over
+    fn winner(&self, board: &Board) -> Option<PlayerColor> {
+        borne_off(board)
+    }
+}
+
+pub fn borne_off(board: &Board) -> Option<PlayerColor> {
+    //! the player who has borne off every checker, if either has
+    if board.black[0] == HOME {
+        Some(PlayerColor::Black)
+    } else if board.white[0] == HOME {
+        Some(PlayerColor::White)
+    } else {
+        None
+    }
+}
+
+#[inline]
+pub fn mirror_point(point: u8) -> u8 {
+    //! the point as seen by a player moving the opposite way, the bar and home are unchanged
+    if point == BAR || point == HOME { point } else { HOME - point }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    board::{Board, Undo},
+    player::PlayerColor,
+    r#mod::{BAR, HOME, MAX_CHECKERS},
+    r#move::Move,
+    rules::base::{Landing, Rules},
+    start_position::StartPosition,
+};
+
+/// The opponent's first checkers start halfway around the board
+const OPPONENT_START: u8 = 13;
+
+/**
+Fevga, where both players race the same way around the board from opposite corners,
+and a single checker holds a point against the opponent.
+No second checker may leave the start until the first has passed the opponent's start.
+Boards keep each player's checkers in their own coordinates as for backgammon,
+so black's world point p is white's point ((p + 11) % 24) + 1, not point p.
+The rule against six point primes isn't enforced
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Fevga;
+
+impl Rules for Fevga {
+    fn name(&self) -> &'static str { "fevga" }
+
+    fn start(&self) -> StartPosition { StartPosition::Stacked }
+
+    fn opponent_point(&self, point: u8) -> u8 {
+        if point == BAR || point == HOME { point } else { ((point + 11) % 24) + 1 }
+    }
+
+    fn landing(&self, opponent: u8, _opponent_pinned: bool, _pinned: bool) -> Landing {
+        if opponent == 0 { Landing::Open } else { Landing::Blocked }
+    }
+
+    fn may_move(&self, pos: &[u8; MAX_CHECKERS], start: u8) -> bool {
+        // games with fewer checkers fill the unused entries with HOME, so only checkers in play count
+        let mut in_play = pos.iter().filter(|p| BAR < **p && **p < HOME);
+        start != 1 || in_play.clone().all(|p| *p == 1) || in_play.any(|p| *p >= OPPONENT_START)
+    }
+
+    fn make_move(&self, board: &mut Board, player: &PlayerColor, mve: &Move) -> Undo {
+        let undo = Undo::before(board, mve);
+        board.move_no_bop(player, mve);
+        undo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry() {
+        assert_eq!(Fevga.opponent_point(1), OPPONENT_START);
+        assert_eq!(Fevga.opponent_point(OPPONENT_START), 1);
+        assert_eq!(Fevga.opponent_point(24), 12);
+        assert_eq!(Fevga.opponent_point(HOME), HOME);
+        assert_eq!(Fevga.landing(1, false, false), Landing::Blocked);
+    }
+
+    #[test]
+    fn one_leaves_first() {
+        let mut pos = [1; MAX_CHECKERS];
+        assert!(Fevga.may_move(&pos, 1));
+        pos[14] = 12;
+        assert!(!Fevga.may_move(&pos, 1));
+        assert!(Fevga.may_move(&pos, 12));
+        pos[14] = 13;
+        assert!(Fevga.may_move(&pos, 1));
+    }
+
+    #[test]
+    fn one_leaves_first_short_game() {
+        let mut pos = [HOME; MAX_CHECKERS];
+        pos[..3].copy_from_slice(&[1, 1, 1]);
+        assert!(Fevga.may_move(&pos, 1));
+        pos[2] = 12;
+        assert!(!Fevga.may_move(&pos, 1));
+        assert!(Fevga.may_move(&pos, 12));
+        pos[2] = 13;
+        assert!(Fevga.may_move(&pos, 1));
+    }
+}
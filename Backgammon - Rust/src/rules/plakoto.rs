@@ -0,0 +1,123 @@
+use crate::{
+    board::{Board, Undo},
+    player::PlayerColor,
+    r#mod::HOME,
+    r#move::Move,
+    rules::base::{borne_off, mirror_point, Landing, Rules},
+    start_position::StartPosition,
+};
+
+/// Black's first checkers start on world point 24, white's on world point 1
+const BLACK_START: u8 = HOME - 1;
+const WHITE_START: u8 = 1;
+
+/**
+Plakoto, played like backgammon from stacked starts, except a lone checker is pinned
+rather than hit, and can't move until every checker pinning it leaves.
+A player whose last checker on their starting point is pinned loses
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Plakoto;
+
+impl Rules for Plakoto {
+    fn name(&self) -> &'static str { "plakoto" }
+
+    fn start(&self) -> StartPosition { StartPosition::Stacked }
+
+    fn opponent_point(&self, point: u8) -> u8 { mirror_point(point) }
+
+    fn landing(&self, opponent: u8, opponent_pinned: bool, pinned: bool) -> Landing {
+        match opponent {
+            _ if pinned => Landing::Blocked,
+            0 => Landing::Open,
+            // already pinned by the player's own checkers
+            1 if opponent_pinned => Landing::Open,
+            1 => Landing::Pin,
+            _ => Landing::Blocked,
+        }
+    }
+
+    fn make_move(&self, board: &mut Board, player: &PlayerColor, mve: &Move) -> Undo {
+        let opponent = match player {
+            PlayerColor::Black => PlayerColor::White,
+            PlayerColor::White => PlayerColor::Black,
+        };
+        let undo = Undo::before(board, mve);
+        board.move_no_bop(player, mve);
+        if mve.start != HOME && board.count_occurrences(player, &mve.start) == 0 && board.is_pinned(&opponent, mve.start) {
+            board.set_pinned(&opponent, mve.start, false);
+        }
+        if mve.end != HOME && board.count_occurrences(&opponent, &mve.end) == 1 {
+            board.set_pinned(&opponent, mve.end, true);
+        }
+        undo
+    }
+
+    fn winner(&self, board: &Board) -> Option<PlayerColor> {
+        borne_off(board).or_else(|| {
+            if board.is_pinned(&PlayerColor::Black, BLACK_START) {
+                Some(PlayerColor::White)
+            } else if board.is_pinned(&PlayerColor::White, WHITE_START) {
+                Some(PlayerColor::Black)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins() {
+        let mut board = Board::with_checkers(&[10, 24, 24], &[1, 1, 5]);
+        Plakoto.make_move(&mut board, &PlayerColor::Black, &Move { start: 10, end: 5 });
+        assert!(board.is_pinned(&PlayerColor::White, 5));
+        assert_eq!(board.count_occurrences(&PlayerColor::White, &5), 1);
+        assert_eq!(Plakoto.landing(1, true, false), Landing::Open);
+        assert_eq!(Plakoto.landing(1, false, true), Landing::Blocked);
+        assert_eq!(Plakoto.landing(1, false, false), Landing::Pin);
+
+        // stacking on the pin keeps it until the last pinning checker leaves
+        Plakoto.make_move(&mut board, &PlayerColor::Black, &Move { start: 24, end: 5 });
+        Plakoto.make_move(&mut board, &PlayerColor::Black, &Move { start: 5, end: 2 });
+        assert!(board.is_pinned(&PlayerColor::White, 5));
+        Plakoto.make_move(&mut board, &PlayerColor::Black, &Move { start: 5, end: 3 });
+        assert!(!board.is_pinned(&PlayerColor::White, 5));
+        assert_eq!(Plakoto.winner(&board), None);
+    }
+
+    #[test]
+    fn unmake_restores_pins() {
+        let mut board = Board::with_checkers(&[10, 24, 24], &[1, 1, 5]);
+        let moves = [
+            (PlayerColor::Black, Move { start: 10, end: 5 }),
+            (PlayerColor::Black, Move { start: 24, end: 5 }),
+            (PlayerColor::Black, Move { start: 5, end: 2 }),
+            (PlayerColor::Black, Move { start: 5, end: 3 }),
+        ];
+        let mut played = Vec::new();
+        for (player, mve) in moves.iter() {
+            played.push((player, board.clone(), Plakoto.make_move(&mut board, player, mve)));
+        }
+        assert!(!board.is_pinned(&PlayerColor::White, 5));
+        for (player, before, undo) in played.iter().rev() {
+            board.unmake_move(player, undo);
+            assert!(board == *before);
+            assert_eq!(board.zobrist_hash(), before.zobrist_hash());
+        }
+        assert!(!board.is_pinned(&PlayerColor::White, 5));
+    }
+
+    #[test]
+    fn mother_pinned() {
+        let mut board = Board::with_checkers(&[3, 20, 24], &[1, 18, 19]);
+        Plakoto.make_move(&mut board, &PlayerColor::Black, &Move { start: 3, end: 1 });
+        assert_eq!(Plakoto.winner(&board), Some(PlayerColor::Black));
+        let mut board = Board::with_checkers(&[3, 20, 24], &[1, 18, 19]);
+        Plakoto.make_move(&mut board, &PlayerColor::White, &Move { start: 19, end: 24 });
+        assert_eq!(Plakoto.winner(&board), Some(PlayerColor::White));
+    }
+}
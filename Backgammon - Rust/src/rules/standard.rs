@@ -0,0 +1,49 @@
+use crate::{
+    board::{Board, Undo},
+    player::PlayerColor,
+    r#move::Move,
+    rules::base::{mirror_point, Landing, Rules},
+    start_position::StartPosition,
+};
+
+/// Backgammon, where a lone checker is hit to the bar
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Standard;
+
+impl Rules for Standard {
+    fn name(&self) -> &'static str { "standard" }
+
+    fn start(&self) -> StartPosition { StartPosition::Standard }
+
+    fn opponent_point(&self, point: u8) -> u8 { mirror_point(point) }
+
+    fn landing(&self, opponent: u8, _opponent_pinned: bool, _pinned: bool) -> Landing {
+        match opponent {
+            0 => Landing::Open,
+            1 => Landing::Hit,
+            _ => Landing::Blocked,
+        }
+    }
+
+    fn make_move(&self, board: &mut Board, player: &PlayerColor, mve: &Move) -> Undo {
+        board.make_move(player, mve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#mod::HOME;
+
+    #[test]
+    fn hits() {
+        let mut board = Board::new();
+        Standard.make_move(&mut board, &PlayerColor::White, &Move { start: 12, end: 18 });
+        Standard.make_move(&mut board, &PlayerColor::Black, &Move { start: 24, end: 18 });
+        assert_eq!(board.white[0], 0);
+        assert_eq!(Standard.landing(2, false, false), Landing::Blocked);
+        assert_eq!(Standard.opponent_point(6), 19);
+        assert_eq!(Standard.opponent_point(HOME), HOME);
+        assert_eq!(Standard.winner(&board), None);
+    }
+}
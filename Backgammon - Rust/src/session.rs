@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde_json::json;
+
+use crate::channel::{ChannelListener, MessageChannel};
+
+/// how long a disconnected player has to reconnect before they are treated as a cheater
+pub const RECONNECT_GRACE: Duration = Duration::from_secs(30);
+/// how long a new connection has to say which session it is resuming
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// each session's token, with the new connection once its player reconnects
+type Sessions = Mutex<HashMap<String, Option<Box<dyn MessageChannel>>>>;
+
+/**
+Tracks the session tokens handed to remote players, so a player whose connection
+drops can reconnect with {"reconnect": token} and resume their game.
+Cloning the registry shares its sessions.
+*/
+#[derive(Clone)]
+pub struct SessionRegistry {
+    sessions: Arc<(Sessions, Condvar)>,
+    grace: Duration,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> SessionRegistry {
+        SessionRegistry::new(RECONNECT_GRACE)
+    }
+}
+
+impl SessionRegistry {
+    pub fn new(grace: Duration) -> SessionRegistry {
+        SessionRegistry {
+            sessions: Arc::new((Mutex::new(HashMap::new()), Condvar::new())),
+            grace,
+        }
+    }
+
+    pub fn issue(&self) -> String {
+        //! creates a new session and returns its token
+        let mut rng = rand::thread_rng();
+        let token = format!("{:016x}{:016x}", rng.gen::<u64>(), rng.gen::<u64>());
+        self.sessions.0.lock().unwrap().insert(token.clone(), None);
+        token
+    }
+
+    pub fn end(&self, token: &str) {
+        self.sessions.0.lock().unwrap().remove(token);
+    }
+
+    pub fn listen(&self, listener: ChannelListener) -> JoinHandle<()> {
+        //! accepts reconnecting players on a background thread for as long as the listener is open
+        let registry = self.clone();
+        thread::spawn(move || {
+            let transport = listener.transport();
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let registry = registry.clone();
+                        thread::spawn(move || {
+                            if let Ok(channel) = transport.wrap(stream) {
+                                registry.accept(channel);
+                            }
+                        });
+                    }
+                    Err(_) => break,
+                }
+            }
+        })
+    }
+
+    pub fn accept(&self, mut stream: Box<dyn MessageChannel>) {
+        /*!
+        reads {"reconnect": token} from a new connection and hands the connection
+        to the player waiting on that session. Unknown sessions are refused.
+        */
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT));
+        let token = stream.recv().ok()
+            .and_then(|msg| msg.get("reconnect").and_then(|t| t.as_str()).map(|t| t.to_string()));
+        stream.set_read_timeout(None);
+
+        let (lock, cvar) = &*self.sessions;
+        let mut sessions = lock.lock().unwrap();
+        match token.and_then(|token| sessions.get_mut(&token)) {
+            Some(slot) => {
+                if stream.send(&json!("okay")).is_ok() {
+                    *slot = Some(stream);
+                    cvar.notify_all();
+                }
+            }
+            None => {
+                stream.send(&json!("unknown session")).ok();
+            }
+        }
+    }
+
+    pub fn await_reconnect(&self, token: &str) -> Option<Box<dyn MessageChannel>> {
+        //! waits up to the grace period for the session's player to reconnect
+        let deadline = Instant::now() + self.grace;
+        let (lock, cvar) = &*self.sessions;
+        let mut sessions = lock.lock().unwrap();
+        loop {
+            match sessions.get_mut(token) {
+                None => return None,
+                Some(slot) => {
+                    if let Some(stream) = slot.take() {
+                        return Some(stream);
+                    }
+                }
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            sessions = cvar.wait_timeout(sessions, deadline - now).unwrap().0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use serde_json::Value;
+    use crate::{
+        board::Board,
+        tcp_handler::TcpHandler,
+        local_remote::{IPlayer, RemotePlayer},
+        player::PlayerColor,
+        protocol::Protocol,
+    };
+
+    fn read(reader: &mut BufReader<TcpStream>) -> Value {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[test]
+    fn reconnect() {
+        let registry = SessionRegistry::new(Duration::from_secs(5));
+        let listener = TcpListener::bind("localhost:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        registry.listen(listener.into());
+
+        let token = registry.issue();
+        let client_token = token.clone();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            writeln!(stream, "{}", json!({ "reconnect": client_token })).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            read(&mut reader)
+        });
+        assert!(registry.await_reconnect(&token).is_some());
+        assert_eq!(client.join().unwrap(), json!("okay"));
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        writeln!(stream, "{}", json!({ "reconnect": "wrong" })).unwrap();
+        assert_eq!(read(&mut BufReader::new(stream)), json!("unknown session"));
+    }
+
+    #[test]
+    fn expires() {
+        let registry = SessionRegistry::new(Duration::from_millis(50));
+        let token = registry.issue();
+        assert!(registry.await_reconnect(&token).is_none());
+        registry.end(&token);
+        assert!(registry.await_reconnect(&token).is_none());
+    }
+
+    #[test]
+    fn resume_turn() {
+        //! the player drops while taking a turn, reconnects, and is sent the turn again
+        let registry = SessionRegistry::new(Duration::from_secs(5));
+        let game_listener = TcpListener::bind("localhost:0").unwrap();
+        let game_addr = game_listener.local_addr().unwrap();
+        let reconnect_listener = TcpListener::bind("localhost:0").unwrap();
+        let reconnect_addr = reconnect_listener.local_addr().unwrap();
+        registry.listen(reconnect_listener.into());
+
+        let client = thread::spawn(move || {
+            let stream = TcpStream::connect(game_addr).unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            assert!(Protocol::from_hello(&read(&mut reader)).is_some());
+            writeln!(writer, "{}", Protocol::current().hello()).unwrap();
+            assert_eq!(read(&mut reader), json!("name"));
+            writeln!(writer, "{}", json!({ "name": "dropper" })).unwrap();
+            let token = read(&mut reader)["session"].as_str().unwrap().to_string();
+            writeln!(writer, "{}", json!("okay")).unwrap();
+            assert!(read(&mut reader)["start-game"].is_array());
+            writeln!(writer, "{}", json!("okay")).unwrap();
+            let first = read(&mut reader);
+            assert!(first["take-turn"].is_array());
+            drop(reader);
+            writer.shutdown(std::net::Shutdown::Both).unwrap();
+
+            let mut stream = TcpStream::connect(reconnect_addr).unwrap();
+            writeln!(stream, "{}", json!({ "reconnect": token })).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            assert_eq!(read(&mut reader), json!("okay"));
+            assert_eq!(read(&mut reader), first);
+            writeln!(stream, "{}", json!({ "turn": [[1, 4], [4, 5]] })).unwrap();
+        });
+
+        let mut player = RemotePlayer::with_sessions(Box::new(TcpHandler::new(game_listener.accept().unwrap().0)), registry);
+        assert_eq!(player.get_name().to_string(), "dropper");
+        assert!(player.start_game(PlayerColor::White, "opp".to_string()));
+        let moves = player.get_turn(&Board::new(), &vec!(3, 1));
+        client.join().unwrap();
+        assert!(!player.has_cheated());
+        assert_eq!(moves.len(), 2);
+    }
+
+    #[test]
+    fn legacy_client() {
+        //! a client that doesn't understand hello is never sent a session
+        let registry = SessionRegistry::new(Duration::from_secs(5));
+        let listener = TcpListener::bind("localhost:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let stream = TcpStream::connect(addr).unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            assert!(read(&mut reader)["hello"].is_object());
+            writeln!(writer, "{}", json!("error reading json input")).unwrap();
+            assert_eq!(read(&mut reader), json!("name"));
+            writeln!(writer, "{}", json!({ "name": "oldie" })).unwrap();
+            assert!(read(&mut reader)["start-game"].is_array());
+            writeln!(writer, "{}", json!("okay")).unwrap();
+        });
+
+        let mut player = RemotePlayer::with_sessions(Box::new(TcpHandler::new(listener.accept().unwrap().0)), registry);
+        assert_eq!(player.get_name().to_string(), "oldie");
+        assert_eq!(player.protocol(), &Protocol::legacy());
+        assert!(!player.check_secret(""));
+        assert!(player.start_game(PlayerColor::Black, "opp".to_string()));
+        client.join().unwrap();
+        assert!(!player.has_cheated());
+    }
+}
@@ -0,0 +1,206 @@
+use crossbeam::channel::unbounded;
+use serde_json::{json, Value};
+
+use crate::{
+    administrator::{Administrator, HandleCheater, Winner},
+    local_remote::LocalPlayer,
+    player::PlayerColor,
+    player_board::PlayerStrat,
+    r#mod::HOME,
+};
+
+/// z score for a two sided 95% confidence interval
+const Z_95: f64 = 1.959_964;
+
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    pub games: usize,
+    pub threads: usize,
+    pub seed: u64,
+}
+
+impl Default for SimConfig {
+    fn default() -> SimConfig {
+        SimConfig {
+            games: 1_000,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            seed: 0,
+        }
+    }
+}
+
+/// Outcome of a single simulated game, from the perspective of the first strategy
+#[derive(Clone, Debug)]
+struct GameResult {
+    won: bool,
+    gammon: bool,
+    turns: usize,
+}
+
+/// Aggregate results of a simulation, from the perspective of the first strategy
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimStats {
+    pub games: usize,
+    pub wins: usize,
+    pub gammons_won: usize,
+    pub gammons_lost: usize,
+    pub total_turns: usize,
+}
+
+impl SimStats {
+    fn add(&mut self, result: &GameResult) {
+        self.games += 1;
+        self.total_turns += result.turns;
+        if result.won {
+            self.wins += 1;
+            if result.gammon { self.gammons_won += 1; }
+        } else if result.gammon {
+            self.gammons_lost += 1;
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        ratio(self.wins, self.games)
+    }
+
+    pub fn gammon_rate(&self) -> f64 {
+        //! fraction of all games that ended in a gammon, won or lost
+        ratio(self.gammons_won + self.gammons_lost, self.games)
+    }
+
+    pub fn avg_game_length(&self) -> f64 {
+        //! mean number of turns per game
+        ratio(self.total_turns, self.games)
+    }
+
+    pub fn win_rate_ci95(&self) -> (f64, f64) {
+        //! Wilson score interval for the win rate
+        if self.games == 0 {
+            return (0.0, 1.0);
+        }
+        let n = self.games as f64;
+        let p = self.win_rate();
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+
+    pub fn report(&self) -> Value {
+        let (low, high) = self.win_rate_ci95();
+        json!({
+            "games": self.games,
+            "wins": self.wins,
+            "win-rate": self.win_rate(),
+            "win-rate-ci95": [low, high],
+            "gammons-won": self.gammons_won,
+            "gammons-lost": self.gammons_lost,
+            "gammon-rate": self.gammon_rate(),
+            "avg-game-length": self.avg_game_length(),
+        })
+    }
+}
+
+#[inline]
+fn ratio(num: usize, denom: usize) -> f64 {
+    if denom == 0 { 0.0 } else { num as f64 / denom as f64 }
+}
+
+pub fn simulate<A: PlayerStrat + Send + Sync, B: PlayerStrat + Send + Sync>(strat_a: &A, strat_b: &B, config: &SimConfig) -> SimStats {
+    /*!
+    plays config.games games of strat_a vs strat_b across config.threads threads.
+    Game i rolls dice seeded from config.seed + i, and the colors swap every game.
+    */
+    let (game_send, game_recv) = unbounded();
+    let (result_send, result_recv) = unbounded();
+    for i in 0..config.games {
+        game_send.send(i).unwrap();
+    }
+    drop(game_send);
+
+    crossbeam::scope(|s| {
+        for _ in 0..config.threads.max(1) {
+            let game_recv = game_recv.clone();
+            let result_send = result_send.clone();
+            s.spawn(move |_| {
+                while let Ok(i) = game_recv.recv() {
+                    let seed = config.seed.wrapping_add(i as u64);
+                    result_send.send(play_game(strat_a, strat_b, i, seed)).unwrap();
+                }
+            });
+        }
+    }).unwrap();
+    drop(result_send);
+
+    let mut stats = SimStats::default();
+    for result in result_recv.iter() {
+        stats.add(&result);
+    }
+    stats
+}
+
+fn play_game<A: PlayerStrat + Send, B: PlayerStrat + Send>(strat_a: &A, strat_b: &B, game_num: usize, seed: u64) -> GameResult {
+    let color_a = if game_num & 0x1 == 0 { PlayerColor::White } else { PlayerColor::Black };
+    let mut admin = Administrator::seeded(
+        Box::new(LocalPlayer::new("A".to_string(), strat_a.clone())),
+        Box::new(LocalPlayer::new("B".to_string(), strat_b.clone())),
+        color_a.clone(),
+        seed,
+    );
+    admin.moderate_game(HandleCheater::EndGame);
+
+    let won = matches!(admin.get_winner(), Winner::PlayerOne);
+    let loser_color = match (won, color_a) {
+        (true, PlayerColor::White) | (false, PlayerColor::Black) => PlayerColor::Black,
+        _ => PlayerColor::White,
+    };
+    GameResult {
+        won,
+        gammon: admin.get_board().count_occurrences(&loser_color, &HOME) == 0,
+        turns: admin.get_num_turns(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{bopsy::Bopsy, rando::Rando};
+
+    #[test]
+    fn stats() {
+        let mut stats = SimStats::default();
+        assert_eq!(stats.win_rate(), 0.0);
+        assert_eq!(stats.win_rate_ci95(), (0.0, 1.0));
+
+        stats.add(&GameResult { won: true, gammon: true, turns: 40 });
+        stats.add(&GameResult { won: false, gammon: false, turns: 50 });
+        stats.add(&GameResult { won: false, gammon: true, turns: 30 });
+        stats.add(&GameResult { won: true, gammon: false, turns: 60 });
+        assert_eq!(stats.games, 4);
+        assert_eq!(stats.win_rate(), 0.5);
+        assert_eq!(stats.gammon_rate(), 0.5);
+        assert_eq!(stats.avg_game_length(), 45.0);
+        let (low, high) = stats.win_rate_ci95();
+        assert!(low < 0.5 && high > 0.5);
+        assert!((0.5 - low - (high - 0.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn interval_narrows() {
+        let small = SimStats { games: 10, wins: 5, ..SimStats::default() };
+        let large = SimStats { games: 1_000, wins: 500, ..SimStats::default() };
+        let (s_low, s_high) = small.win_rate_ci95();
+        let (l_low, l_high) = large.win_rate_ci95();
+        assert!(l_high - l_low < s_high - s_low);
+    }
+
+    #[test]
+    fn play_games() {
+        let config = SimConfig { games: 20, threads: 4, seed: 9 };
+        let stats = simulate(&Bopsy, &Rando, &config);
+        assert_eq!(stats.games, 20);
+        assert!(stats.wins <= 20);
+        assert!(stats.avg_game_length() > 0.0);
+        assert!(stats.gammons_won + stats.gammons_lost <= 20);
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::HashMap;
+use std::sync::{mpsc::{self, SyncSender, TrySendError}, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::{
+    board::Board,
+    channel::{ChannelListener, MessageChannel, RecvError},
+    observer::AdminObserver,
+    player::PlayerColor,
+    r#move::Move,
+};
+
+/// how many events may wait for a spectator before it's dropped for not keeping up
+const SPECTATOR_BACKLOG: usize = 1024;
+/// how long a write to a spectator may block before the spectator is given up on
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Something that happened during a game, sent to spectators as one JSON message
+#[cfg_attr(debug_assertions, derive(Debug))]
+pub enum GameEvent<'a> {
+    GameStarted { black: &'a str, white: &'a str },
+    Roll { color: PlayerColor, dice: &'a [u8] },
+    /// board is the board after the moves were made
+    Turn { color: PlayerColor, moves: &'a [Move], board: &'a Board },
+    TurnRejected { color: PlayerColor, moves: &'a [Move] },
+    Cheated { color: PlayerColor },
+    Replaced { color: PlayerColor, name: &'a str },
+    /// winner is None when both players cheated
+    GameEnded { winner: Option<PlayerColor>, board: &'a Board },
+}
+
+impl GameEvent<'_> {
+    pub fn to_json(&self, game: u64) -> Value {
+        match self {
+            GameEvent::GameStarted { black, white } => json!({
+                "game": game, "event": "game-started", "black": black, "white": white,
+            }),
+            GameEvent::Roll { color, dice } => json!({
+                "game": game, "event": "roll", "color": color_str(color), "dice": dice,
+            }),
+            GameEvent::Turn { color, moves, board } => {
+                let moves: Vec<Value> = moves.iter().map(|mve| mve.to_json()).collect();
+                json!({
+                    "game": game, "event": "turn", "color": color_str(color), "turn": moves, "board": board,
+                })
+            }
+            GameEvent::TurnRejected { color, moves } => {
+                let moves: Vec<Value> = moves.iter().map(|mve| mve.to_json()).collect();
+                json!({
+                    "game": game, "event": "turn-rejected", "color": color_str(color), "turn": moves,
+                })
+            }
+            GameEvent::Cheated { color } => json!({
+                "game": game, "event": "cheated", "color": color_str(color),
+            }),
+            GameEvent::Replaced { color, name } => json!({
+                "game": game, "event": "replaced", "color": color_str(color), "name": name,
+            }),
+            GameEvent::GameEnded { winner, board } => json!({
+                "game": game, "event": "game-ended", "winner": winner.as_ref().map(color_str), "board": board,
+            }),
+        }
+    }
+}
+
+/**
+Forwards game events to every connected spectator.
+Spectators receive all games until they send {"watch": game id} or {"watch": player name},
+and "all" or {"watch": "all"} removes the filter. Each request is answered with {"watching": filter}.
+Each spectator is written to from its own thread, so one that stops reading can't hold up the games.
+Cloning the hub shares its spectators.
+*/
+#[derive(Clone)]
+pub struct SpectatorHub {
+    state: Arc<Mutex<HubState>>,
+}
+
+struct HubState {
+    spectators: Vec<Spectator>,
+    /// names of the black and white players of games in progress
+    games: HashMap<u64, (String, String)>,
+    next_game: u64,
+    next_spectator: usize,
+}
+
+struct Spectator {
+    id: usize,
+    /// events waiting for the spectator's writer thread
+    queue: SyncSender<Value>,
+    filter: Filter,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Filter {
+    All,
+    Game(u64),
+    Player(String),
+}
+
+impl Filter {
+    fn to_json(&self) -> Value {
+        match self {
+            Filter::All => json!("all"),
+            Filter::Game(game) => json!(game),
+            Filter::Player(name) => json!(name),
+        }
+    }
+
+    fn from_json(val: &Value) -> Option<Filter> {
+        if val.as_str() == Some("all") {
+            return Some(Filter::All);
+        }
+        let watch = val.as_object()?.get("watch")?;
+        if watch.as_str() == Some("all") {
+            Some(Filter::All)
+        } else if let Some(game) = watch.as_u64() {
+            Some(Filter::Game(game))
+        } else {
+            watch.as_str().map(|name| Filter::Player(name.to_string()))
+        }
+    }
+
+    fn matches(&self, game: u64, games: &HashMap<u64, (String, String)>) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Game(id) => *id == game,
+            Filter::Player(name) => games.get(&game).is_some_and(|(black, white)| black == name || white == name),
+        }
+    }
+}
+
+impl Default for SpectatorHub {
+    fn default() -> SpectatorHub {
+        SpectatorHub::new()
+    }
+}
+
+impl SpectatorHub {
+    pub fn new() -> SpectatorHub {
+        SpectatorHub {
+            state: Arc::new(Mutex::new(HubState {
+                spectators: Vec::new(),
+                games: HashMap::new(),
+                next_game: 1,
+                next_spectator: 0,
+            })),
+        }
+    }
+
+    pub fn listen(&self, listener: ChannelListener) -> JoinHandle<()> {
+        //! accepts spectators on a background thread for as long as the listener is open
+        let hub = self.clone();
+        thread::spawn(move || {
+            let transport = listener.transport();
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        stream.set_write_timeout(Some(WRITE_TIMEOUT)).ok();
+                        let hub = hub.clone();
+                        thread::spawn(move || {
+                            if let Ok(channel) = transport.wrap(stream) {
+                                hub.add_spectator(channel);
+                            }
+                        });
+                    }
+                    Err(_) => break,
+                }
+            }
+        })
+    }
+
+    pub fn add_spectator(&self, stream: Box<dyn MessageChannel>) {
+        /*!
+        registers a spectator, who is first sent the games in progress.
+        Background threads write the spectator's events and read its filter requests.
+        */
+        let mut reader = stream.duplicate();
+        let (queue, events) = mpsc::sync_channel(SPECTATOR_BACKLOG);
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            let in_progress: Vec<Value> = state.games.iter()
+                .map(|(game, (black, white))| json!({"game": game, "black": black, "white": white}))
+                .collect();
+            let spectator = Spectator { id: state.next_spectator, queue, filter: Filter::All };
+            spectator.send(json!({ "games": in_progress }));
+            state.next_spectator += 1;
+            state.spectators.push(spectator);
+            state.next_spectator - 1
+        };
+
+        let mut writer = stream;
+        thread::spawn(move || {
+            // ends when the spectator disconnects or the hub drops it, closing the queue
+            for event in events {
+                if writer.send(&event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let hub = self.clone();
+        thread::spawn(move || loop {
+            let request = match reader.recv() {
+                Ok(request) => request,
+                Err(RecvError::Malformed(_)) => continue,
+                Err(_) => break,
+            };
+            if let Some(filter) = Filter::from_json(&request) {
+                hub.set_filter(id, filter);
+            }
+        });
+    }
+
+    pub fn next_game_id(&self) -> u64 {
+        //! reserves a unique id for a new game
+        let mut state = self.state.lock().unwrap();
+        state.next_game += 1;
+        state.next_game - 1
+    }
+
+    pub fn feed(&self) -> SpectatorFeed {
+        //! creates an observer publishing a new game to this hub
+        SpectatorFeed { hub: self.clone(), game: self.next_game_id() }
+    }
+
+    pub fn num_spectators(&self) -> usize {
+        self.state.lock().unwrap().spectators.len()
+    }
+
+    pub fn publish(&self, game: u64, event: &GameEvent) {
+        //! queues event for every spectator watching game, dropping spectators that disconnected or fell behind
+        let mut state = self.state.lock().unwrap();
+        if let GameEvent::GameStarted { black, white } = event {
+            state.games.insert(game, (black.to_string(), white.to_string()));
+        }
+        let msg = event.to_json(game);
+        let HubState { spectators, games, .. } = &mut *state;
+        spectators.retain(|spectator| !spectator.filter.matches(game, games) || spectator.send(msg.clone()));
+        if let GameEvent::GameEnded { .. } = event {
+            state.games.remove(&game);
+        }
+    }
+
+    fn set_filter(&self, id: usize, filter: Filter) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(spectator) = state.spectators.iter_mut().find(|s| s.id == id) {
+            let reply = json!({ "watching": filter.to_json() });
+            spectator.filter = filter;
+            spectator.send(reply);
+        }
+    }
+}
+
+/// Observer that publishes one game's events to a SpectatorHub
+pub struct SpectatorFeed {
+    hub: SpectatorHub,
+    game: u64,
+}
+
+impl SpectatorFeed {
+    #[inline]
+    pub fn game(&self) -> u64 { self.game }
+}
+
+impl AdminObserver for SpectatorFeed {
+    fn game_started(&mut self, black: &str, white: &str) {
+        self.hub.publish(self.game, &GameEvent::GameStarted { black, white });
+    }
+
+    fn dice_rolled(&mut self, color: &PlayerColor, dice: &[u8]) {
+        self.hub.publish(self.game, &GameEvent::Roll { color: color.clone(), dice });
+    }
+
+    fn turn_submitted(&mut self, color: &PlayerColor, moves: &[Move], board: &Board) {
+        self.hub.publish(self.game, &GameEvent::Turn { color: color.clone(), moves, board });
+    }
+
+    fn turn_rejected(&mut self, color: &PlayerColor, moves: &[Move]) {
+        self.hub.publish(self.game, &GameEvent::TurnRejected { color: color.clone(), moves });
+    }
+
+    fn player_cheated(&mut self, color: &PlayerColor) {
+        self.hub.publish(self.game, &GameEvent::Cheated { color: color.clone() });
+    }
+
+    fn player_replaced(&mut self, color: &PlayerColor, replacement: &str) {
+        self.hub.publish(self.game, &GameEvent::Replaced { color: color.clone(), name: replacement });
+    }
+
+    fn game_over(&mut self, winner: Option<&PlayerColor>, board: &Board) {
+        self.hub.publish(self.game, &GameEvent::GameEnded { winner: winner.cloned(), board });
+    }
+}
+
+impl Spectator {
+    fn send(&self, val: Value) -> bool {
+        //! queues val without waiting on the connection, false if the spectator should be dropped
+        match self.queue.try_send(val) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
+
+fn color_str(color: &PlayerColor) -> &'static str {
+    match color {
+        PlayerColor::Black => "black",
+        PlayerColor::White => "white",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use crate::{
+        administrator::{Administrator, HandleCheater},
+        local_remote::LocalPlayer,
+        strategy::{bopsy::Bopsy, rando::Rando},
+        net_config::NetConfig,
+        tcp_handler::TcpHandler,
+        ws_handler::WsHandler,
+    };
+
+    fn connect(hub: &SpectatorHub) -> BufReader<TcpStream> {
+        let listener = TcpListener::bind("localhost:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        hub.add_spectator(Box::new(TcpHandler::new(listener.accept().unwrap().0)));
+        BufReader::new(stream)
+    }
+
+    fn read(reader: &mut BufReader<TcpStream>) -> Value {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    fn play(hub: &SpectatorHub, seed: u64) -> u64 {
+        let mut admin = Administrator::seeded(
+            Box::new(LocalPlayer::new("rando".to_string(), Rando)),
+            Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+            PlayerColor::Black, seed);
+        let feed = hub.feed();
+        let game = feed.game();
+        admin.add_observer(Box::new(feed));
+        admin.moderate_game(HandleCheater::EndGame);
+        game
+    }
+
+    #[test]
+    fn event_stream() {
+        let hub = SpectatorHub::new();
+        let mut reader = connect(&hub);
+        assert_eq!(read(&mut reader), json!({"games": []}));
+
+        let game = play(&hub, 3);
+        let started = read(&mut reader);
+        assert_eq!(started, json!({"game": game, "event": "game-started", "black": "rando", "white": "bopsy"}));
+
+        let mut turns = 0;
+        loop {
+            let roll = read(&mut reader);
+            if roll["event"] == "game-ended" {
+                assert!(roll["winner"].is_string());
+                break;
+            }
+            assert_eq!(roll["event"], "roll");
+            let turn = read(&mut reader);
+            assert_eq!(turn["event"], "turn");
+            assert_eq!(turn["color"], roll["color"]);
+            assert!(turn["board"]["black"].is_array());
+            turns += 1;
+        }
+        assert!(turns > 0);
+    }
+
+    #[test]
+    fn filter_by_game() {
+        let hub = SpectatorHub::new();
+        let mut all = connect(&hub);
+        let mut filtered = connect(&hub);
+        read(&mut all);
+        read(&mut filtered);
+
+        let first = hub.next_game_id();
+        filtered.get_mut().write_all(format!("{{\"watch\": {}}}\n", first + 2).as_bytes()).unwrap();
+        assert_eq!(read(&mut filtered), json!({"watching": first + 2}));
+
+        let skipped = play(&hub, 1);
+        let watched = play(&hub, 2);
+        assert_eq!(watched, first + 2);
+        assert_eq!(read(&mut all)["game"], json!(skipped));
+        let event = read(&mut filtered);
+        assert_eq!(event["game"], json!(watched));
+        assert_eq!(event["event"], "game-started");
+    }
+
+    #[test]
+    fn filter_by_player() {
+        let hub = SpectatorHub::new();
+        hub.publish(7, &GameEvent::GameStarted { black: "a", white: "b" });
+        hub.publish(8, &GameEvent::GameStarted { black: "c", white: "d" });
+
+        let mut reader = connect(&hub);
+        assert_eq!(read(&mut reader)["games"].as_array().unwrap().len(), 2);
+        reader.get_mut().write_all(b"{\"watch\": \"d\"}\n").unwrap();
+        assert_eq!(read(&mut reader), json!({"watching": "d"}));
+
+        hub.publish(7, &GameEvent::Cheated { color: PlayerColor::Black });
+        hub.publish(8, &GameEvent::Roll { color: PlayerColor::White, dice: &[6, 5] });
+        assert_eq!(read(&mut reader), json!({"game": 8, "event": "roll", "color": "white", "dice": [6, 5]}));
+    }
+
+    #[test]
+    fn drop_disconnected() {
+        let hub = SpectatorHub::new();
+        let reader = connect(&hub);
+        assert_eq!(hub.num_spectators(), 1);
+        drop(reader);
+        for _ in 0..10 {
+            hub.publish(1, &GameEvent::Cheated { color: PlayerColor::White });
+            if hub.num_spectators() == 0 {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(hub.num_spectators(), 0);
+    }
+
+    #[test]
+    fn filter_from_json() {
+        assert_eq!(Filter::from_json(&json!("all")), Some(Filter::All));
+        assert_eq!(Filter::from_json(&json!({"watch": "all"})), Some(Filter::All));
+        assert_eq!(Filter::from_json(&json!({"watch": 3})), Some(Filter::Game(3)));
+        assert_eq!(Filter::from_json(&json!({"watch": "bopsy"})), Some(Filter::Player("bopsy".to_string())));
+        assert_eq!(Filter::from_json(&json!({"watching": 3})), None);
+    }
+
+    #[test]
+    fn stalled_spectator() {
+        // a spectator that never reads mustn't block the games publishing to it
+        let hub = SpectatorHub::new();
+        let _stalled = connect(&hub);
+        let board = Board::new();
+        let moves = [Move { start: 1, end: 7 }, Move { start: 12, end: 17 }];
+        for _ in 0..100_000 {
+            hub.publish(1, &GameEvent::Turn { color: PlayerColor::White, moves: &moves, board: &board });
+            if hub.num_spectators() == 0 {
+                break;
+            }
+        }
+        assert_eq!(hub.num_spectators(), 0);
+    }
+
+    #[test]
+    fn websocket_spectator() {
+        let hub = SpectatorHub::new();
+        let listener = NetConfig::connect_websocket_listener(json!(0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        hub.listen(listener);
+
+        let stream = TcpStream::connect(addr).unwrap();
+        let mut client = WsHandler::connect(stream, &format!("ws://{}/", addr)).unwrap();
+        assert_eq!(client.recv().unwrap(), json!({"games": []}));
+        client.send(&json!({"watch": "bopsy"})).unwrap();
+        assert_eq!(client.recv().unwrap(), json!({"watching": "bopsy"}));
+
+        let game = play(&hub, 5);
+        let started = client.recv().unwrap();
+        assert_eq!(started["game"], json!(game));
+        assert_eq!(started["event"], "game-started");
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+
+use crate::{
+    board::Board,
+    player::PlayerColor,
+    r#mod::{BAR, HOME, MAX_CHECKERS},
+};
+
+const NACKGAMMON_BLACK: [u8; MAX_CHECKERS] = [6, 6, 6, 6, 8, 8, 8, 13, 13, 13, 13, 23, 23, 24, 24];
+const NACKGAMMON_WHITE: [u8; MAX_CHECKERS] = [1, 1, 2, 2, 12, 12, 12, 12, 17, 17, 17, 19, 19, 19, 19];
+/// black holds two anchors in white's home board against a white army that is nearly home
+const BACK_GAME_BLACK: [u8; MAX_CHECKERS] = [4, 4, 5, 5, 6, 6, 6, 8, 8, 13, 13, 20, 20, 22, 22];
+const BACK_GAME_WHITE: [u8; MAX_CHECKERS] = [14, 14, 15, 16, 17, 17, 18, 18, 19, 19, 21, 21, 23, 23, 24];
+/// both players have a five point prime with three checkers trapped behind the other's
+const PRIMING_BLACK: [u8; MAX_CHECKERS] = [4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 13, 13, 23, 23, 24];
+const PRIMING_WHITE: [u8; MAX_CHECKERS] = [1, 2, 2, 12, 12, 17, 17, 18, 18, 19, 19, 20, 20, 21, 21];
+/// three checkers each, on the opponent's one, two and three points
+const HYPERGAMMON_BLACK: [u8; 3] = [22, 23, 24];
+const HYPERGAMMON_WHITE: [u8; 3] = [1, 2, 3];
+/// every checker on the player's own one point, the start of Plakoto and Fevga
+const STACKED_BLACK: [u8; MAX_CHECKERS] = [24; MAX_CHECKERS];
+const STACKED_WHITE: [u8; MAX_CHECKERS] = [1; MAX_CHECKERS];
+
+/// Where the checkers are when a game starts
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum StartPosition {
+    #[default]
+    Standard,
+    /// two checkers each moved from the mid point and six point to the opponent's two point
+    Nackgammon,
+    BackGame,
+    PrimingBattle,
+    Hypergammon,
+    Stacked,
+    /// positions in world coordinates, the same number for both players
+    Custom { black: Vec<u8>, white: Vec<u8> },
+}
+
+impl StartPosition {
+    pub const NAMED: [StartPosition; 6] = [
+        StartPosition::Standard,
+        StartPosition::Nackgammon,
+        StartPosition::BackGame,
+        StartPosition::PrimingBattle,
+        StartPosition::Hypergammon,
+        StartPosition::Stacked,
+    ];
+
+    pub fn board(&self) -> Board {
+        match self {
+            StartPosition::Standard => Board::new(),
+            StartPosition::Nackgammon => Board::from_positions(NACKGAMMON_BLACK, NACKGAMMON_WHITE),
+            StartPosition::BackGame => Board::from_positions(BACK_GAME_BLACK, BACK_GAME_WHITE),
+            StartPosition::PrimingBattle => Board::from_positions(PRIMING_BLACK, PRIMING_WHITE),
+            StartPosition::Hypergammon => Board::with_checkers(&HYPERGAMMON_BLACK, &HYPERGAMMON_WHITE),
+            StartPosition::Stacked => Board::from_positions(STACKED_BLACK, STACKED_WHITE),
+            StartPosition::Custom { black, white } => Board::with_checkers(black, white),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StartPosition::Standard => "standard",
+            StartPosition::Nackgammon => "nackgammon",
+            StartPosition::BackGame => "back-game",
+            StartPosition::PrimingBattle => "priming-battle",
+            StartPosition::Hypergammon => "hypergammon",
+            StartPosition::Stacked => "stacked",
+            StartPosition::Custom { .. } => "custom",
+        }
+    }
+
+    pub fn custom(board: Board) -> Result<StartPosition, String> {
+        //! checks board can be played from, no point may hold checkers of both colors
+        for point in 1..HOME {
+            if board.count_occurrences(&PlayerColor::Black, &point) > 0 && board.count_occurrences(&PlayerColor::White, &point) > 0 {
+                return Err(format!("point {} holds both black and white checkers", point));
+            }
+        }
+        if board.black[0] == HOME || board.white[0] == HOME {
+            return Err("a player has already borne off every checker".to_string());
+        }
+        let checkers = board.num_checkers();
+        Ok(StartPosition::Custom { black: board.black[..checkers].to_vec(), white: board.white[..checkers].to_vec() })
+    }
+
+    pub fn from_json(json: &Value) -> Result<StartPosition, String> {
+        //! reads a name such as "nackgammon", or a board like {"black": [..], "white": [..]} with up to MAX_CHECKERS each
+        match json {
+            Value::String(name) => name.parse(),
+            Value::Object(map) => {
+                let side = |color: &str| -> Result<Vec<u8>, String> {
+                    let points = map.get(color).and_then(|points| points.as_array())
+                        .ok_or_else(|| format!("a custom start needs a list of {} checkers", color))?;
+                    read_points(points).map_err(|e| format!("bad {} checkers: {}", color, e))
+                };
+                if let Some(key) = map.keys().find(|key| *key != "black" && *key != "white") {
+                    return Err(format!("unknown key '{}' in a custom start, expected black and white", key));
+                }
+                let (black, white) = (side("black")?, side("white")?);
+                if black.len() != white.len() {
+                    return Err(format!("black has {} checkers but white has {}", black.len(), white.len()));
+                }
+                StartPosition::custom(Board::with_checkers(&black, &white))
+            }
+            other => Err(format!("{} is not a start position, expected a name or a board", other)),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "name": self.name(), "board": self.board() })
+    }
+}
+
+impl FromStr for StartPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<StartPosition, String> {
+        let name = s.to_lowercase();
+        StartPosition::NAMED.iter().find(|start| start.name() == name).cloned()
+            .ok_or_else(|| format!("unknown start '{}', expected standard, nackgammon, back-game, priming-battle, hypergammon, stacked or a board", s))
+    }
+}
+
+impl fmt::Display for StartPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn read_points(points: &[Value]) -> Result<Vec<u8>, String> {
+    //! reads checkers as "bar", "home" or a point from 1 to 24, in any order
+    if points.is_empty() || points.len() > MAX_CHECKERS {
+        return Err(format!("expected 1 to {} checkers, got {}", MAX_CHECKERS, points.len()));
+    }
+    points.iter().map(|point| match point {
+        Value::String(s) if s == "bar" => Ok(BAR),
+        Value::String(s) if s == "home" => Ok(HOME),
+        Value::Number(n) if n.as_u64().is_some_and(|n| n > BAR as u64 && n < HOME as u64) => Ok(n.as_u64().unwrap() as u8),
+        other => Err(format!("{} is not bar, home or a point from 1 to 24", other)),
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named() {
+        for start in StartPosition::NAMED.iter() {
+            let board = start.board();
+            // every named start is a legal custom board, with equal armies
+            assert_eq!(StartPosition::custom(board.clone()).unwrap().board(), board);
+            assert_eq!(start.name().parse::<StartPosition>().unwrap(), *start);
+            if *start != StartPosition::BackGame {
+                assert_eq!(board.pip_count(&PlayerColor::Black), board.pip_count(&PlayerColor::White));
+            }
+        }
+        assert_eq!(StartPosition::Nackgammon.board().pip_count(&PlayerColor::Black), 194);
+        let hyper = StartPosition::Hypergammon.board();
+        assert_eq!((hyper.num_checkers(), hyper.pip_count(&PlayerColor::Black)), (3, 69));
+        assert!("tiddlywinks".parse::<StartPosition>().is_err());
+    }
+
+    #[test]
+    fn custom() {
+        let json = json!({
+            "black": ["bar", 6, 6, 6, 6, 6, 8, 8, 8, 13, 13, 13, 13, 24, "home"],
+            "white": [1, 1, 12, 12, 12, 12, 12, 17, 17, 17, 19, 19, 19, 19, 19],
+        });
+        let start = StartPosition::from_json(&json).unwrap();
+        assert_eq!(start.board().black[0], BAR);
+        assert_eq!(start.board().black[14], HOME);
+        assert_eq!(start.to_json()["name"], "custom");
+
+        let mut shared = json.clone();
+        shared["white"][0] = json!(6);
+        assert!(StartPosition::from_json(&shared).unwrap_err().contains("point 6"));
+        let mut short = json.clone();
+        short["black"].as_array_mut().unwrap().pop();
+        assert!(StartPosition::from_json(&short).unwrap_err().contains("black has 14 checkers but white has 15"));
+        let two = StartPosition::from_json(&json!({"black": [6, 5], "white": [20, "bar"]})).unwrap();
+        assert_eq!(two.board(), Board::with_checkers(&[5, 6], &[BAR, 20]));
+        assert!(StartPosition::from_json(&json!({"black": [], "white": []})).unwrap_err().contains("expected 1 to 15"));
+        assert!(StartPosition::from_json(&json!({"black": [], "red": []})).unwrap_err().contains("red"));
+        assert_eq!(StartPosition::from_json(&json!("Nackgammon")).unwrap(), StartPosition::Nackgammon);
+    }
+}
@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+use crate::{
+    player_board::{PlayerStrat, TurnScore},
+    r#mod::MAX_CHECKERS,
+    r#move::Move,
+    strategy::{
+        bopsy::Bopsy,
+        rando::Rando,
+        smarty::{Smarty, SmartyWeights},
+    },
+};
+
+/// Strategy chosen at runtime, e.g. from a command line argument or config file
+#[derive(Clone)]
+pub enum AnyStrat {
+    Rando(Rando),
+    Bopsy(Bopsy),
+    Smarty(Smarty),
+}
+
+impl AnyStrat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnyStrat::Rando(_) => "Rando",
+            AnyStrat::Bopsy(_) => "Bopsy",
+            AnyStrat::Smarty(_) => "Smarty",
+        }
+    }
+}
+
+impl std::fmt::Debug for AnyStrat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl PlayerStrat for AnyStrat {
+    fn start_turn(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8]) -> TurnScore {
+        match self {
+            AnyStrat::Rando(strat) => strat.start_turn(player_pos, opp_frq),
+            AnyStrat::Bopsy(strat) => strat.start_turn(player_pos, opp_frq),
+            AnyStrat::Smarty(strat) => strat.start_turn(player_pos, opp_frq),
+        }
+    }
+
+    fn score_move(&self, before: &TurnScore, opp_frq: &[u8], pos: &[u8; MAX_CHECKERS], mve: &Move, new_pos: &[u8; MAX_CHECKERS]) -> TurnScore {
+        match self {
+            AnyStrat::Rando(strat) => strat.score_move(before, opp_frq, pos, mve, new_pos),
+            AnyStrat::Bopsy(strat) => strat.score_move(before, opp_frq, pos, mve, new_pos),
+            AnyStrat::Smarty(strat) => strat.score_move(before, opp_frq, pos, mve, new_pos),
+        }
+    }
+
+    fn score_end(&self, moves: &TurnScore, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8], end_pos: &[u8; MAX_CHECKERS]) -> usize {
+        match self {
+            AnyStrat::Rando(strat) => strat.score_end(moves, player_pos, opp_frq, end_pos),
+            AnyStrat::Bopsy(strat) => strat.score_end(moves, player_pos, opp_frq, end_pos),
+            AnyStrat::Smarty(strat) => strat.score_end(moves, player_pos, opp_frq, end_pos),
+        }
+    }
+}
+
+impl FromStr for AnyStrat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<AnyStrat, String> {
+        //! accepts a strategy name, or "Smarty:<path>" for Smarty with weights loaded from a JSON file
+        let mut parts = s.splitn(2, ':');
+        let name = parts.next().unwrap_or("").to_lowercase();
+        match (name.as_str(), parts.next()) {
+            ("rando", None) => Ok(AnyStrat::Rando(Rando)),
+            ("bopsy", None) => Ok(AnyStrat::Bopsy(Bopsy)),
+            ("smarty", None) => Ok(AnyStrat::Smarty(Smarty::new())),
+            ("smarty", Some(path)) => SmartyWeights::from_file(path)
+                .map(|w| AnyStrat::Smarty(Smarty::with_weights(w)))
+                .map_err(|e| format!("failed to load weights from '{}': {}", path, e)),
+            _ => Err(format!("unknown strategy '{}', expected Rando, Bopsy or Smarty", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!("Rando".parse::<AnyStrat>().unwrap().name(), "Rando");
+        assert_eq!("bopsy".parse::<AnyStrat>().unwrap().name(), "Bopsy");
+        assert_eq!("SMARTY".parse::<AnyStrat>().unwrap().name(), "Smarty");
+        assert!("Lou".parse::<AnyStrat>().is_err());
+        assert!("Bopsy:weights.json".parse::<AnyStrat>().is_err());
+        assert!("Smarty:/no/such/weights.json".parse::<AnyStrat>().is_err());
+    }
+}
@@ -1,74 +1,151 @@
-use rand::Rng;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    player_board::PlayerStrat,
-    r#mod::{HOME, NUM_CHECKERS, move_checker},
+    player_board::{PlayerStrat, TurnScore},
+    r#mod::{HOME, MAX_CHECKERS},
     r#move::Move,
-    turn_tree::TurnNode,
 };
 
 /// Smart Player
-#[derive(Clone)]
-pub struct Smarty;
+#[derive(Clone, Default)]
+pub struct Smarty {
+    weights: SmartyWeights,
+}
 
-impl PlayerStrat for Smarty {
-    fn choose_turn(&self, _player_pos: &[u8; NUM_CHECKERS], _opp_frq: &Vec<u8>, _turns: &mut Vec<Box<TurnNode>>) -> Vec<Move> {
-        if _turns.is_empty() { return vec!(); }
-        let mut max_score = 0;
-        for node in _turns.iter_mut() {
-            let score = self.score_tree_root(&_player_pos, &_opp_frq, node);
-            if score > max_score {
-                max_score = score;
-            }
+/**
+Tunable constants used to score turns.
+Missing fields take their default value when loaded from JSON.
+*/
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmartyWeights {
+    /// starting score of every leaf, allows subtraction without fear of overflow
+    pub base: usize,
+    /// per checker added to a point that held a single checker
+    pub cover_blot: usize,
+    /// for each point that held a single checker and still holds at most one
+    pub keep_blot: usize,
+    /// per checker taken from a point holding more than 3
+    pub unstack: usize,
+    /// penalty for a blot that an enemy checker could still hit
+    pub exposed_blot: usize,
+    /// penalty for a blot no enemy checker can reach
+    pub safe_blot: usize,
+    /// penalty for building a stack of more than 3 checkers
+    pub new_stack: usize,
+    /// per pip of progress the bopped enemy checker loses
+    pub bop: usize,
+    /// for bearing off from a point left with exactly 2 checkers
+    pub bear_off_pair: usize,
+    /// for bearing off from any other point
+    pub bear_off: usize,
+    /// for bearing off once neither player can bop the other
+    pub race_bear_off: usize,
+    /// for moving the rearmost checker once neither player can bop the other
+    pub race_back_checker: usize,
+}
+
+impl Default for SmartyWeights {
+    fn default() -> SmartyWeights {
+        SmartyWeights {
+            base: 10_000,
+            cover_blot: 100,
+            keep_blot: 20,
+            unstack: 90,
+            exposed_blot: 100,
+            safe_blot: 25,
+            new_stack: 75,
+            bop: 10,
+            bear_off_pair: 75,
+            bear_off: 200,
+            race_bear_off: 200,
+            race_back_checker: 100,
         }
-        let mut moves: Vec<Move> = Vec::with_capacity(_turns[0].get_max_depth() as usize);
-        self.select_moves(&_turns, &mut moves);
-        moves
     }
 }
 
-impl Smarty {
-    fn score_tree_root(&self, player_pos: &[u8; NUM_CHECKERS], opp_frq: &Vec<u8>, node: &mut TurnNode) -> usize {
-        //! scores tree knowing that the player is in their initial position
-        if !self.enemy_overlap(player_pos, opp_frq) {
-            self.score_no_overlap(player_pos, player_pos, opp_frq, node)
-        } else {
-            self.score_tree(player_pos, player_pos, opp_frq, node, &0)
-        }
+impl SmartyWeights {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<SmartyWeights> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
     }
 
-    fn score_tree(&self, orig_pos: &[u8; NUM_CHECKERS], cur_player_pos: &[u8; NUM_CHECKERS], opp_frq: &Vec<u8>, node: &mut TurnNode, bopped: &u32) -> usize {
-        let mut score: usize = 0;
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
 
-        let mve = node.get_move();
-        let mut new_pos = cur_player_pos.clone();
-        move_checker(&mut new_pos, &mve.start, &mve.end);
+    pub fn to_vec(&self) -> Vec<usize> {
+        //! lists every weight except base, in declaration order
+        vec!(
+            self.cover_blot, self.keep_blot, self.unstack, self.exposed_blot,
+            self.safe_blot, self.new_stack, self.bop, self.bear_off_pair,
+            self.bear_off, self.race_bear_off, self.race_back_checker,
+        )
+    }
+
+    pub fn from_vec(base: usize, vals: &[usize]) -> SmartyWeights {
+        //! inverse of to_vec
+        debug_assert_eq!(vals.len(), SmartyWeights::default().to_vec().len(), "wrong number of weights");
+        SmartyWeights {
+            base,
+            cover_blot: vals[0],
+            keep_blot: vals[1],
+            unstack: vals[2],
+            exposed_blot: vals[3],
+            safe_blot: vals[4],
+            new_stack: vals[5],
+            bop: vals[6],
+            bear_off_pair: vals[7],
+            bear_off: vals[8],
+            race_bear_off: vals[9],
+            race_back_checker: vals[10],
+        }
+    }
+}
 
-        let mut new_bopped = bopped.clone();
-        score += self.score_bop(opp_frq, &mve.end, &mut new_bopped);
-        score += self.score_risky_home(&new_pos, &mve);
+impl PlayerStrat for Smarty {
+    fn start_turn(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8]) -> TurnScore {
+        TurnScore { racing: !self.enemy_overlap(player_pos, opp_frq), ..TurnScore::default() }
+    }
 
-        if node.num_node_branches() == 0 {
-            score += self.score_leaf(&orig_pos, &new_pos, opp_frq, node);
+    fn score_move(&self, before: &TurnScore, opp_frq: &[u8], pos: &[u8; MAX_CHECKERS], mve: &Move, new_pos: &[u8; MAX_CHECKERS]) -> TurnScore {
+        let mut after = *before;
+        // once neither player can bop the other, the rest of the turn is scored as a race
+        if before.racing {
+            after.score += self.score_race(pos, mve);
         } else {
-            let mut max_child: usize = 0;
-            let is_safe: bool = self.enemy_overlap(&new_pos, opp_frq);
-            for child in node.get_mut_branches().iter_mut() {
-                let child_score = if is_safe {
-                    self.score_no_overlap(&orig_pos, &new_pos, opp_frq, child)
-                } else {
-                    self.score_tree(&orig_pos, &new_pos, opp_frq, child, &new_bopped)
-                };
-                if child_score > max_child { max_child = child_score; }
-            }
-            score += max_child
+            after.score += self.score_bop(opp_frq, &mve.end, &mut after.bopped);
+            after.score += self.score_risky_home(new_pos, mve);
+            after.racing = !self.enemy_overlap(new_pos, opp_frq);
         }
-        node.set_score(score);
-        score
+        after
+    }
+
+    fn score_end(&self, moves: &TurnScore, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8], end_pos: &[u8; MAX_CHECKERS]) -> usize {
+        moves.score + self.score_leaf(player_pos, end_pos, opp_frq)
+    }
+}
+
+impl Smarty {
+    pub fn new() -> Smarty {
+        Smarty::default()
+    }
+
+    pub fn with_weights(weights: SmartyWeights) -> Smarty {
+        Smarty { weights }
     }
 
-    fn score_leaf(&self, orig_pos: &[u8; NUM_CHECKERS], cur_player_pos: &[u8; NUM_CHECKERS], opp_frq: &Vec<u8>, _node: &mut TurnNode) -> usize {
-        let mut score: usize = 10_000; //allows subtraction without fear of overflow
+    #[inline]
+    pub fn weights(&self) -> &SmartyWeights { &self.weights }
+
+    fn score_leaf(&self, orig_pos: &[u8; MAX_CHECKERS], cur_player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8]) -> usize {
+        let w = &self.weights;
+        let mut score: usize = w.base;
 
         let original_freqs: [u8; HOME as usize + 1] = pos_to_freqs(orig_pos);
         let new_freqs: [u8; HOME as usize + 1] = pos_to_freqs(cur_player_pos);
@@ -76,12 +153,12 @@ impl Smarty {
         for i in 0..HOME as usize +1 {
             if original_freqs[i] == 1 {
                 if new_freqs[i] > 1 {
-                    score += (new_freqs[i] - original_freqs[i]) as usize * 100;
+                    score += (new_freqs[i] - original_freqs[i]) as usize * w.cover_blot;
                 } else {
-                    score += 20;
+                    score += w.keep_blot;
                 }
             } else if original_freqs[i] > 3 && new_freqs[i] < original_freqs[i] {
-                score += (original_freqs[i] - new_freqs[i]) as usize * 90;
+                score += (original_freqs[i] - new_freqs[i]) as usize * w.unstack;
             }
 
             if new_freqs[i] == 1 {
@@ -93,20 +170,20 @@ impl Smarty {
                     }
                 }
                 if can_be_bopped_soon {
-                    score -= 100;
+                    score = score.saturating_sub(w.exposed_blot);
                 } else {
-                    score -= 25;
+                    score = score.saturating_sub(w.safe_blot);
                 }
             }
 
             if new_freqs[i] > 3 && original_freqs[i] <= 3 {
-                score -= 75;
+                score = score.saturating_sub(w.new_stack);
             }
         }
 
         return score;
 
-        fn pos_to_freqs(pieces: &[u8; NUM_CHECKERS]) -> [u8; HOME as usize + 1] {
+        fn pos_to_freqs(pieces: &[u8; MAX_CHECKERS]) -> [u8; HOME as usize + 1] {
             //! tallies number of checkers at each position
             let mut frequencies: [u8; HOME as usize + 1] =  [0; HOME as usize + 1];
             for piece in pieces {
@@ -117,19 +194,19 @@ impl Smarty {
     }
 
     #[inline]
-    fn score_bop(&self, opp_frq: &Vec<u8>, end: &u8, bopped: &mut u32) -> usize {
+    fn score_bop(&self, opp_frq: &[u8], end: &u8, bopped: &mut u32) -> usize {
         if end != &HOME && opp_frq[*end as usize] == 1 {
             let been_bopped = 0x1 << *end;
             if *bopped & been_bopped == 0 {
                 *bopped |= been_bopped;
-                return (&HOME - end) as usize * 10;
+                return (&HOME - end) as usize * self.weights.bop;
             }
         }
         0
     }
 
     #[inline]
-    fn score_risky_home(&self, pos: &[u8; NUM_CHECKERS], mve: &Move) -> usize {
+    fn score_risky_home(&self, pos: &[u8; MAX_CHECKERS], mve: &Move) -> usize {
         let mut score = 0;
         if mve.end == HOME {
             let mut num_start_ind = 0;
@@ -139,81 +216,93 @@ impl Smarty {
                 }
             }
             if num_start_ind == 2 {
-                score += 75;
+                score += self.weights.bear_off_pair;
             } else {
-                score += 200;
+                score += self.weights.bear_off;
             }
         }
         score
     }
 
-    fn score_no_overlap(&self, orig_pos: &[u8; NUM_CHECKERS], player_pos: &[u8; NUM_CHECKERS], opp_frq: &Vec<u8>, node: &mut TurnNode) -> usize {
+    #[inline]
+    fn score_race(&self, player_pos: &[u8; MAX_CHECKERS], mve: &Move) -> usize {
         //! since there will never be an opportunity to bop or be bopped,
         //! the furthest piece from HOME will always be moved unless a piece can moved to HOME
         let mut score: usize = 0;
-
-        let mve = node.get_move();
         if mve.end == HOME {
-            score += 200;
+            score += self.weights.race_bear_off;
         }
         if mve.start == player_pos[0] {
-            score += 100;
-        }
-
-        let mut new_pos = player_pos.clone();
-        move_checker(&mut new_pos, &mve.start, &mve.end);
-        if node.num_node_branches() == 0 {
-            score += self.score_leaf(&orig_pos,&new_pos, opp_frq, node);
-        } else {
-            let mut max_child: usize = 0;
-            for child in node.get_mut_branches().iter_mut() {
-                let child_score = self.score_no_overlap(orig_pos, &new_pos,  opp_frq, child);
-                if child_score > max_child { max_child = child_score; }
-            }
-            score += max_child;
+            score += self.weights.race_back_checker;
         }
-        node.set_score(score);
         score
     }
 
-    fn enemy_overlap(&self, player_pos: &[u8; NUM_CHECKERS], opp_frq: &Vec<u8>) -> bool {
+    fn enemy_overlap(&self, player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8]) -> bool {
         //! returns true if it is possible for a blot to be bopped in current or future turn
-        for i in (player_pos[0] + 1) as usize..HOME as usize {
-            if opp_frq[i] > 0 { return true; }
-        }
-        return opp_frq[0] != 0;
-    }
-
-    fn select_moves(&self, turns: &Vec<Box<TurnNode>>, moves: &mut Vec<Move>) {
-        //! selects the turn with the highest score
-        let mut max_score: usize = 0;
-        let mut num_max: u8 = 0;
-        for node in turns.iter() {
-            let scr = *node.get_score();
-            if scr > max_score {
-                max_score = scr;
-                num_max = 1;
-            } else if scr == max_score {
-                num_max += 1;
+        opp_frq.iter().take(HOME as usize).skip(player_pos[0] as usize + 1).any(|count| *count > 0) || opp_frq[0] != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r#mod::move_checker;
+    use serde_json::json;
+
+    #[test]
+    fn overlap_changes_mid_turn() {
+        let smarty = Smarty::new();
+        let w = smarty.weights();
+        let mut player_pos = [20; MAX_CHECKERS];
+        player_pos[0] = 2;
+        let mut opp_frq = vec![0u8; HOME as usize + 1];
+        opp_frq[4] = 2;
+        opp_frq[6] = 1;
+        let end_of = |moves: &[Move]| {
+            let mut pos = player_pos;
+            for mve in moves {
+                move_checker(&mut pos, &mve.start, &mve.end);
             }
-        }
-        num_max = if num_max > 1 {
-            let mut rng = rand::thread_rng();
-            rng.gen_range(0..num_max)
-        } else {
-            0
+            pos
         };
 
-        for node in turns.iter() {
-            if node.get_score() == &max_score {
-                if num_max == 0 {
-                    moves.push(node.get_move().clone());
-                    if node.get_max_depth() > 1 {
-                        self.select_moves(node.get_branches(), moves);
-                    }
-                    break;
-                } else { num_max -= 1; }
-            }
-        }
+        // passing the opponent's last checkers makes the rest of the turn a race
+        let moves = [Move { start: 2, end: 10 }, Move { start: 10, end: 15 }];
+        assert_eq!(smarty.score_turn(&player_pos, &opp_frq, &moves),
+                   w.race_back_checker + smarty.score_leaf(&player_pos, &end_of(&moves), &opp_frq));
+
+        // while they can still be hit, moves are scored for bops, not as a race
+        let moves = [Move { start: 2, end: 3 }, Move { start: 3, end: 6 }];
+        assert_eq!(smarty.score_turn(&player_pos, &opp_frq, &moves),
+                   (HOME - 6) as usize * w.bop + smarty.score_leaf(&player_pos, &end_of(&moves), &opp_frq));
+    }
+
+    #[test]
+    fn weights_json() {
+        let weights: SmartyWeights = serde_json::from_value(json!({"bop": 12, "unstack": 80})).unwrap();
+        assert_eq!(weights.bop, 12);
+        assert_eq!(weights.unstack, 80);
+        assert_eq!(weights.base, SmartyWeights::default().base);
+        assert!(serde_json::from_value::<SmartyWeights>(json!({"bopp": 12})).is_err());
+
+        let round_trip: SmartyWeights = serde_json::from_value(json!(weights)).unwrap();
+        assert_eq!(round_trip, weights);
+    }
+
+    #[test]
+    fn weights_vec() {
+        let weights = SmartyWeights { race_back_checker: 3, ..SmartyWeights::default() };
+        assert_eq!(SmartyWeights::from_vec(weights.base, &weights.to_vec()), weights);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn weights_file() {
+        let path = std::env::temp_dir().join("smarty_weights_test.json");
+        let weights = SmartyWeights { cover_blot: 140, ..SmartyWeights::default() };
+        weights.to_file(&path).unwrap();
+        assert_eq!(SmartyWeights::from_file(&path).unwrap(), weights);
+        std::fs::remove_file(&path).ok();
+        assert!(SmartyWeights::from_file(&path).is_err());
+    }
+}
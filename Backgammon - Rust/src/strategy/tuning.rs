@@ -0,0 +1,172 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    player_board::PlayerStrat,
+    simulation::{simulate, SimConfig, SimStats},
+    strategy::smarty::{Smarty, SmartyWeights},
+};
+
+#[derive(Clone, Debug)]
+pub struct TuningConfig {
+    pub population: usize,
+    pub generations: usize,
+    /// games each candidate plays against the baseline per generation
+    pub games_per_eval: usize,
+    /// number of best candidates copied unchanged into the next generation
+    pub elite: usize,
+    /// chance that each weight of a child is mutated
+    pub mutation_rate: f64,
+    /// mutated weights are scaled by a random factor in [1 - scale, 1 + scale]
+    pub mutation_scale: f64,
+    pub seed: u64,
+    pub threads: usize,
+}
+
+impl Default for TuningConfig {
+    fn default() -> TuningConfig {
+        TuningConfig {
+            population: 16,
+            generations: 10,
+            games_per_eval: 200,
+            elite: 2,
+            mutation_rate: 0.3,
+            mutation_scale: 0.25,
+            seed: 0,
+            threads: SimConfig::default().threads,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TuningResult {
+    pub weights: SmartyWeights,
+    pub stats: SimStats,
+}
+
+pub fn tune<B: PlayerStrat + Send + Sync>(baseline: &B, start: &SmartyWeights, config: &TuningConfig,
+                                   on_generation: &mut dyn FnMut(usize, &TuningResult)) -> TuningResult {
+    /*!
+    Genetic algorithm over Smarty's weights. Each generation every candidate plays
+    the baseline with the same dice, the fittest survive, and the rest of the
+    population is bred from them. Returns the best candidate seen.
+    */
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let population_size = config.population.max(2);
+    let elite = config.elite.clamp(1, population_size);
+    let base = start.base;
+
+    let mut population: Vec<Vec<usize>> = Vec::with_capacity(population_size);
+    population.push(start.to_vec());
+    while population.len() < population_size {
+        population.push(mutate(&start.to_vec(), 1.0, config.mutation_scale, &mut rng));
+    }
+
+    let mut best: Option<TuningResult> = None;
+    for generation in 0..config.generations {
+        let sim_config = SimConfig {
+            games: config.games_per_eval,
+            threads: config.threads,
+            seed: config.seed.wrapping_add((generation as u64) << 32),
+        };
+        let mut scored: Vec<TuningResult> = population.iter()
+            .map(|vals| {
+                let weights = SmartyWeights::from_vec(base, vals);
+                let stats = simulate(&Smarty::with_weights(weights.clone()), baseline, &sim_config);
+                TuningResult { weights, stats }
+            })
+            .collect();
+        scored.sort_by(|a, b| b.stats.win_rate().partial_cmp(&a.stats.win_rate()).unwrap());
+
+        on_generation(generation, &scored[0]);
+        if best.as_ref().is_none_or(|b| scored[0].stats.win_rate() > b.stats.win_rate()) {
+            best = Some(scored[0].clone());
+        }
+
+        let parents: Vec<Vec<usize>> = scored.iter().map(|r| r.weights.to_vec()).collect();
+        population = parents[..elite].to_vec();
+        while population.len() < population_size {
+            let mother = select_parent(&parents, &mut rng);
+            let father = select_parent(&parents, &mut rng);
+            let child = crossover(mother, father, &mut rng);
+            population.push(mutate(&child, config.mutation_rate, config.mutation_scale, &mut rng));
+        }
+    }
+
+    best.unwrap_or(TuningResult { weights: start.clone(), stats: SimStats::default() })
+}
+
+fn select_parent<'a>(ranked: &'a [Vec<usize>], rng: &mut StdRng) -> &'a Vec<usize> {
+    //! tournament selection of size 2, ranked must be sorted best first
+    let a = rng.gen_range(0..ranked.len());
+    let b = rng.gen_range(0..ranked.len());
+    &ranked[a.min(b)]
+}
+
+fn crossover(mother: &[usize], father: &[usize], rng: &mut StdRng) -> Vec<usize> {
+    //! uniform crossover
+    mother.iter().zip(father.iter())
+        .map(|(m, f)| if rng.gen_bool(0.5) { *m } else { *f })
+        .collect()
+}
+
+fn mutate(vals: &[usize], rate: f64, scale: f64, rng: &mut StdRng) -> Vec<usize> {
+    vals.iter()
+        .map(|val| {
+            if !rng.gen_bool(rate.clamp(0.0, 1.0)) {
+                return *val;
+            }
+            if *val == 0 {
+                return rng.gen_range(0..3);
+            }
+            let factor = 1.0 + rng.gen_range(-scale..=scale);
+            (*val as f64 * factor).round().max(0.0) as usize
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::rando::Rando;
+
+    #[test]
+    fn mutation_bounds() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let vals = SmartyWeights::default().to_vec();
+        assert_eq!(mutate(&vals, 0.0, 0.5, &mut rng), vals);
+        for _ in 0..100 {
+            let child = mutate(&vals, 1.0, 0.5, &mut rng);
+            for (c, v) in child.iter().zip(vals.iter()) {
+                assert!(*c as f64 >= (*v as f64 * 0.5).floor());
+                assert!(*c as f64 <= (*v as f64 * 1.5).ceil());
+            }
+        }
+    }
+
+    #[test]
+    fn crossover_genes() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mother = vec![1, 2, 3, 4, 5];
+        let father = vec![10, 20, 30, 40, 50];
+        let child = crossover(&mother, &father, &mut rng);
+        for i in 0..child.len() {
+            assert!(child[i] == mother[i] || child[i] == father[i]);
+        }
+    }
+
+    #[test]
+    fn tune_small() {
+        let config = TuningConfig {
+            population: 3,
+            generations: 2,
+            games_per_eval: 4,
+            threads: 2,
+            ..TuningConfig::default()
+        };
+        let mut generations_seen = 0;
+        let result = tune(&Rando, &SmartyWeights::default(), &config, &mut |_, _| generations_seen += 1);
+        assert_eq!(generations_seen, 2);
+        assert_eq!(result.stats.games, 4);
+        assert_eq!(result.weights.base, SmartyWeights::default().base);
+    }
+}
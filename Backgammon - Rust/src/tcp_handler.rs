@@ -1,8 +1,11 @@
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
 use std::net::TcpStream;
+use std::time::Duration;
 
 use serde_json::Value;
 
+use crate::channel::{MessageChannel, RecvError};
+
 const DEFAULT_BUF_SIZE: usize = 512;
 
 /// Wraps a TcpStream and allows easy writing and reading of serde_json::Value
@@ -28,12 +31,13 @@ impl TcpHandler {
         }
     }
 
-    pub fn read_line(&mut self) -> Value {
+    pub fn read_line(&mut self) -> Result<Value, RecvError> {
         //! read one line from socket
         let out_val = match self.reader.read_line(&mut self.in_buffer) {
-            Ok(0) => Value::Null,
-            Ok(_) => serde_json::from_str(&self.in_buffer).unwrap(),
-            Err(e) => panic!("received error: {}", e),
+            Ok(0) => Err(RecvError::Closed),
+            Ok(_) => serde_json::from_str(&self.in_buffer)
+                .map_err(|_| RecvError::Malformed(self.in_buffer.trim_end().to_string())),
+            Err(e) => Err(RecvError::Io(e)),
         };
         self.in_buffer.clear();
         out_val
@@ -45,4 +49,40 @@ impl TcpHandler {
         self.writer.write(output_str.as_bytes()).ok();
         self.writer.flush().ok();
     }
-}
\ No newline at end of file
+}
+
+impl MessageChannel for TcpHandler {
+    fn recv(&mut self) -> Result<Value, RecvError> { self.read_line() }
+
+    fn send(&mut self, val: &Value) -> io::Result<()> {
+        self.writer.write_all(format!("{}\n", val).as_bytes())?;
+        self.writer.flush()
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.reader.get_ref().set_read_timeout(timeout).ok();
+    }
+
+    fn duplicate(&self) -> Box<dyn MessageChannel> { Box::new(self.clone()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use serde_json::json;
+
+    #[test]
+    fn malformed_lines() {
+        let listener = TcpListener::bind("localhost:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let mut handler = TcpHandler::new(listener.accept().unwrap().0);
+
+        client.write_all(b"not json\n[1, 2]\n").unwrap();
+        drop(client);
+        // a bad line is reported as it was sent, and the connection is still read after it
+        assert!(matches!(handler.recv(), Err(RecvError::Malformed(line)) if line == "not json"));
+        assert_eq!(handler.recv().unwrap(), json!([1, 2]));
+        assert!(matches!(handler.recv(), Err(RecvError::Closed)));
+    }
+}
@@ -0,0 +1,360 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    administrator::{GameLimits, HandleCheater, LimitResult, Replacement},
+    channel::Transport,
+    log::{LogLevel, Logger},
+    rules::any_rules::AnyRules,
+    start_position::StartPosition,
+    strategy::any_strat::AnyStrat,
+    tournament::tournament::{GameSettings, NamePolicy, Registration, Timeouts, TournConfig, TType},
+};
+
+/**
+A tournament config as written in its file, before it is checked.
+Only players, port and type are required, e.g. in TOML:
+```toml
+players = 8
+port = 8000
+type = "round robin"
+bind = "0.0.0.0"
+cheaters = "replace"
+replacement = { name = "Malnati", strategy = "Smarty" }
+games-per-pairing = 3
+seed = 42
+rules = "standard"
+start = "nackgammon"
+
+[timeouts]
+response = 10
+reconnect = 30
+
+[limits]
+turns = 1000
+time = 300
+result = "pip-count"
+
+[output]
+results = "results.json"
+records = "records"
+
+[log]
+level = "info"
+file = "tournament.log"
+```
+*/
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    players: u64,
+    port: u16,
+    #[serde(rename = "type")]
+    ev_type: String,
+    /// defaults to localhost
+    bind: Option<String>,
+    transport: Option<String>,
+    spectator_port: Option<u16>,
+    duplicate_names: Option<String>,
+    secret: Option<String>,
+    cheaters: Option<String>,
+    replacement: Option<ReplacementFile>,
+    filler: Option<String>,
+    games_per_pairing: Option<usize>,
+    seed: Option<u64>,
+    rules: Option<String>,
+    /// a named start, or a board like { black = [..], white = [..] }, where the rules start by default
+    start: Option<Value>,
+    #[serde(default)]
+    timeouts: TimeoutsFile,
+    #[serde(default)]
+    limits: LimitsFile,
+    #[serde(default)]
+    output: OutputFile,
+    #[serde(default)]
+    log: LogFile,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ReplacementFile {
+    name: Option<String>,
+    strategy: Option<String>,
+}
+
+/// both in seconds
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct TimeoutsFile {
+    response: Option<f64>,
+    reconnect: Option<f64>,
+}
+
+/// time is in seconds, result is pip-count or void
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct LimitsFile {
+    turns: Option<usize>,
+    time: Option<f64>,
+    result: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct OutputFile {
+    results: Option<PathBuf>,
+    /// a directory, which gets one file per game
+    records: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct LogFile {
+    level: Option<String>,
+    /// logs go to stderr without one
+    file: Option<PathBuf>,
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<TournConfig, String> {
+    //! reads the config at path, which is TOML if it ends in .toml and JSON otherwise
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        from_toml(&text)
+    } else {
+        let json: Value = serde_json::from_str(&text).map_err(|e| format!("{} is not JSON: {}", path.display(), e))?;
+        from_json(&json)
+    }
+}
+
+pub fn from_toml(text: &str) -> Result<TournConfig, String> {
+    let file: ConfigFile = toml::from_str(text).map_err(|e| e.to_string())?;
+    file.check()
+}
+
+pub fn from_json(json: &Value) -> Result<TournConfig, String> {
+    let file: ConfigFile = serde_json::from_value(json.clone()).map_err(|e| e.to_string())?;
+    file.check()
+}
+
+impl ConfigFile {
+    fn check(self) -> Result<TournConfig, String> {
+        //! turns the file into a config, or explains the first thing wrong with it
+        if self.players < 2 {
+            return Err(format!("players must be at least 2, not {}", self.players));
+        }
+        if self.spectator_port == Some(self.port) {
+            return Err(format!("players and spectators can't share port {}", self.port));
+        }
+        let ev_type = match self.ev_type.to_lowercase().as_str() {
+            "round robin" => TType::RndRbn,
+            "single elimination" => TType::SingleElim,
+            other => return Err(format!("unknown type '{}', expected round robin or single elimination", other)),
+        };
+        let transport = match self.transport.as_deref() {
+            None | Some("tcp") => Transport::Tcp,
+            Some("websocket") => Transport::WebSocket,
+            Some(other) => return Err(format!("unknown transport '{}', expected tcp or websocket", other)),
+        };
+        let duplicate_names = match self.duplicate_names.as_deref() {
+            None | Some("rename") => NamePolicy::Rename,
+            Some("reject") => NamePolicy::Reject,
+            Some(other) => return Err(format!("unknown duplicate-names policy '{}', expected rename or reject", other)),
+        };
+
+        let defaults = GameSettings::default();
+        let cheaters = match self.cheaters.as_deref() {
+            None | Some("end-game") => HandleCheater::EndGame,
+            Some("replace") => HandleCheater::Replace,
+            Some(other) => return Err(format!("unknown cheaters policy '{}', expected end-game or replace", other)),
+        };
+        let replacement = match self.replacement {
+            Some(replacement) => Replacement {
+                name: replacement.name.unwrap_or(defaults.replacement.name),
+                strategy: parse_strategy(replacement.strategy, defaults.replacement.strategy, "replacement")?,
+            },
+            None => defaults.replacement,
+        };
+        let games_per_pairing = self.games_per_pairing.unwrap_or(defaults.games_per_pairing);
+        if games_per_pairing & 1 == 0 {
+            return Err(format!("games-per-pairing must be odd so every pairing has a winner, not {}", games_per_pairing));
+        }
+        let timeouts = Timeouts {
+            response: self.timeouts.response.map(|secs| seconds(secs, "timeouts.response")).transpose()?,
+            reconnect: match self.timeouts.reconnect {
+                Some(secs) => seconds(secs, "timeouts.reconnect")?,
+                None => defaults.timeouts.reconnect,
+            },
+        };
+        if self.limits.turns == Some(0) {
+            return Err("limits.turns must be at least 1".to_string());
+        }
+        let limits = GameLimits {
+            max_turns: self.limits.turns,
+            time: self.limits.time.map(|secs| seconds(secs, "limits.time")).transpose()?,
+            result: match self.limits.result.as_deref() {
+                None | Some("pip-count") => LimitResult::PipCount,
+                Some("void") => LimitResult::Void,
+                Some(other) => return Err(format!("unknown limits.result '{}', expected pip-count or void", other)),
+            },
+        };
+        let rules = match &self.rules {
+            Some(rules) => rules.parse().map_err(|e| format!("bad rules: {}", e))?,
+            None => AnyRules::default(),
+        };
+        let start = self.start.as_ref()
+            .map(|start| StartPosition::from_json(start).map_err(|e| format!("bad start: {}", e)))
+            .transpose()?;
+        let settings = GameSettings {
+            cheaters,
+            replacement,
+            filler: parse_strategy(self.filler, defaults.filler, "filler")?,
+            games_per_pairing,
+            seed: self.seed,
+            timeouts,
+            limits,
+            rules,
+            start,
+            records: self.output.records,
+            logger: self.log.logger()?,
+        };
+
+        let mut config = TournConfig::new(self.players, json!(self.port), ev_type)
+            .with_registration(Registration { duplicate_names, secret: self.secret })
+            .with_transport(transport)
+            .with_settings(settings);
+        if let Some(bind) = self.bind {
+            config = config.with_bind(bind);
+        }
+        if let Some(port) = self.spectator_port {
+            config = config.with_spectator_port(json!(port));
+        }
+        if let Some(results) = self.output.results {
+            config = config.with_results(results);
+        }
+        Ok(config)
+    }
+}
+
+impl LogFile {
+    fn logger(self) -> Result<Logger, String> {
+        //! logs at info by default once a file is given, otherwise not at all
+        let level = match self.level {
+            Some(level) => level.parse()?,
+            None if self.file.is_some() => LogLevel::Info,
+            None => LogLevel::Off,
+        };
+        match self.file {
+            Some(path) => Logger::file(level, &path).map_err(|e| format!("failed to open log {}: {}", path.display(), e)),
+            None => Ok(Logger::stderr(level)),
+        }
+    }
+}
+
+fn parse_strategy(name: Option<String>, default: AnyStrat, key: &str) -> Result<AnyStrat, String> {
+    match name {
+        Some(name) => name.parse().map_err(|e| format!("bad {}: {}", key, e)),
+        None => Ok(default),
+    }
+}
+
+fn seconds(secs: f64, key: &str) -> Result<Duration, String> {
+    if secs.is_finite() && secs > 0.0 {
+        Ok(Duration::from_secs_f64(secs))
+    } else {
+        Err(format!("{} must be a positive number of seconds, not {}", key, secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_and_json_agree() {
+        let toml = r#"
+            players = 4
+            port = 8000
+            type = "Single Elimination"
+            bind = "0.0.0.0"
+            cheaters = "replace"
+            replacement = { name = "Sub", strategy = "Bopsy" }
+            filler = "Smarty"
+            games-per-pairing = 3
+            seed = 7
+            rules = "plakoto"
+            start = "back-game"
+
+            [timeouts]
+            response = 2.5
+
+            [limits]
+            turns = 400
+            result = "void"
+
+            [output]
+            results = "results.json"
+            records = "records"
+        "#;
+        let json = json!({
+            "players": 4, "port": 8000, "type": "single elimination", "bind": "0.0.0.0",
+            "cheaters": "replace", "replacement": {"name": "Sub", "strategy": "Bopsy"}, "filler": "Smarty",
+            "games-per-pairing": 3, "seed": 7, "rules": "plakoto", "start": "back-game", "timeouts": {"response": 2.5}, "limits": {"turns": 400, "result": "void"},
+            "output": {"results": "results.json", "records": "records"},
+        });
+        for config in vec!(from_toml(toml).unwrap(), from_json(&json).unwrap()) {
+            let settings = config.get_settings();
+            assert_eq!(settings.cheaters, HandleCheater::Replace);
+            assert_eq!((settings.replacement.name.as_str(), settings.replacement.strategy.name()), ("Sub", "Bopsy"));
+            assert_eq!(settings.filler.name(), "Smarty");
+            assert_eq!((settings.games_per_pairing, settings.seed), (3, Some(7)));
+            assert_eq!(settings.timeouts.response, Some(Duration::from_millis(2500)));
+            assert_eq!(settings.timeouts.reconnect, Timeouts::default().reconnect);
+            assert_eq!(settings.limits, GameLimits { max_turns: Some(400), time: None, result: LimitResult::Void });
+            assert_eq!((settings.rules.to_string().as_str(), settings.start.clone()), ("plakoto", Some(StartPosition::BackGame)));
+            assert_eq!(settings.records, Some(PathBuf::from("records")));
+            assert_eq!(config.get_results(), Some(Path::new("results.json")));
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        let config = from_json(&json!({"players": 2, "port": 8000, "type": "round robin"})).unwrap();
+        let settings = config.get_settings();
+        assert_eq!(settings.cheaters, HandleCheater::EndGame);
+        assert_eq!(settings.replacement.name, "Malnati");
+        assert_eq!((settings.games_per_pairing, settings.seed), (1, None));
+        assert_eq!((settings.rules, settings.start.clone()), (AnyRules::default(), None));
+        assert_eq!(settings.logger.level(), LogLevel::Off);
+        assert!(config.get_results().is_none());
+    }
+
+    #[test]
+    fn invalid() {
+        let bad = |extra: Value| {
+            let mut json = json!({"players": 4, "port": 8000, "type": "round robin"});
+            json.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            from_json(&json).unwrap_err()
+        };
+        assert!(bad(json!({"players": 1})).contains("at least 2"));
+        assert!(bad(json!({"type": "swiss"})).contains("unknown type"));
+        assert!(bad(json!({"games-per-pairing": 2})).contains("must be odd"));
+        assert!(bad(json!({"cheaters": "ignore"})).contains("cheaters"));
+        assert!(bad(json!({"filler": "Genius"})).contains("bad filler"));
+        assert!(bad(json!({"timeouts": {"response": 0}})).contains("timeouts.response"));
+        assert!(bad(json!({"spectator-port": 8000})).contains("share port"));
+        assert!(bad(json!({"limits": {"turns": 0}})).contains("limits.turns"));
+        assert!(bad(json!({"limits": {"time": -1}})).contains("limits.time"));
+        assert!(bad(json!({"limits": {"result": "draw"}})).contains("limits.result"));
+        assert!(bad(json!({"rules": "tavla"})).contains("bad rules"));
+        assert!(bad(json!({"start": "tiddlywinks"})).contains("bad start"));
+        assert!(bad(json!({"start": {"black": [6], "white": [1, 2]}})).contains("bad start"));
+        assert!(bad(json!({"log": {"level": "loud"}})).contains("log level"));
+        assert!(bad(json!({"colour": "black"})).contains("unknown field"));
+        assert!(from_toml("players = 4\nport = 8000").unwrap_err().contains("type"));
+    }
+}
@@ -1,28 +1,36 @@
-use std::net::TcpListener;
-
 use crossbeam::channel::bounded;
 use serde_json::{json, Value};
 
 use crate::{
-    administrator::{Administrator, Winner, HandleCheater},
+    channel::ChannelListener,
+    administrator::Winner,
     local_remote::IPlayer,
-    tournament::tournament::{Tournament, accept_players},
+    spectator::SpectatorHub,
+    tournament::tournament::{Tournament, Registration, GameSettings, Series, accept_players},
 };
 
 pub struct RoundRobin {
-    tcp_listener: TcpListener,
+    tcp_listener: ChannelListener,
     player_count: usize,
     players: Vec<Box<dyn IPlayer>>,
     matches: Vec<Vec<Match>>,
+    spectators: Option<SpectatorHub>,
+    registration: Registration,
+    settings: GameSettings,
+    infractions: Vec<Value>,
 }
 
 impl RoundRobin {
-    pub fn new(player_count: usize, listener: TcpListener) -> RoundRobin {
+    pub fn new(player_count: usize, listener: ChannelListener) -> RoundRobin {
         RoundRobin {
             tcp_listener: listener,
             player_count,
             players: Vec::with_capacity(player_count),
             matches: Vec::new(),
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         }
     }
 
@@ -48,23 +56,9 @@ impl RoundRobin {
                 player_two = self.players.get_unchecked(indices.1);
             }
 
-            if player_one.has_cheated() {
-                if player_two.has_cheated() {
-                    self.matches[row_ind][i].set_winner(Winner::None);
-                } else {
-                    self.matches[row_ind][i].set_winner(Winner::PlayerTwo);
-                }
-                s1.send((i, None)).unwrap();
-                continue;
-            } else if player_two.has_cheated() {
-                self.matches[row_ind][i].set_winner(Winner::PlayerOne);
-                s1.send((i, None)).unwrap();
-                continue;
-            }
-
+            // a series with a cheater in it ends before its first game
             s1.send(
-                (i, Some(Administrator::new(
-                    player_one.duplicate(), player_two.duplicate())))
+                (i, Series::new(player_one.duplicate(), player_two.duplicate(), &self.settings, &self.spectators, (row_ind, i)))
             ).unwrap();
         }
 
@@ -72,14 +66,9 @@ impl RoundRobin {
             let mut threads = Vec::with_capacity(self.matches[row_ind].len());
             for _ in 0..self.matches[row_ind].len() {
                 threads.push(s.spawn(|_| {
-                    let (ind, admin) = r1.recv().unwrap();
-                    if let Some(mut game) = admin {
-                        game.moderate_game(HandleCheater::EndGame);
-                        s1.send((ind, Some(game))).unwrap();
-                    } else {
-                        s1.send((ind, None)).unwrap();
-                    }
-
+                    let (ind, mut series) = r1.recv().unwrap();
+                    series.play();
+                    s1.send((ind, series)).unwrap();
                 }));
             }
             for child in threads {
@@ -88,14 +77,13 @@ impl RoundRobin {
         }).unwrap();
 
         for _ in 0..self.matches[row_ind].len() {
-            let (ind, opt_admin) = r1.recv().unwrap();
-            if let Some(game) = opt_admin {
-                let indices = self.matches[row_ind][ind].get_indices();
-                let players = game.get_players();
-                self.players[indices.0] = players.0;
-                self.players[indices.1] = players.1;
-                self.matches[row_ind][ind].set_winner(game.get_winner());
-            }
+            let (ind, series) = r1.recv().unwrap();
+            self.infractions.extend(series.report_infractions());
+            let indices = self.matches[row_ind][ind].get_indices();
+            let players = series.get_players();
+            self.players[indices.0] = players.0;
+            self.players[indices.1] = players.1;
+            self.matches[row_ind][ind].set_winner(series.get_winner());
         }
     }
 
@@ -131,7 +119,7 @@ impl RoundRobin {
 
 impl Tournament for RoundRobin {
     fn moderate_tournament(&mut self) {
-        accept_players(&mut self.players, &self.player_count, &self.tcp_listener);
+        accept_players(&mut self.players, &self.player_count, &self.tcp_listener, &self.registration, &self.settings);
         self.generate_matches();
 
         for i in 0..self.matches.len() {
@@ -171,6 +159,22 @@ impl Tournament for RoundRobin {
 
         json!(player_results)
     }
+
+    fn set_spectators(&mut self, hub: SpectatorHub) {
+        self.spectators = Some(hub);
+    }
+
+    fn set_registration(&mut self, registration: Registration) {
+        self.registration = registration;
+    }
+
+    fn set_settings(&mut self, settings: GameSettings) {
+        self.settings = settings;
+    }
+
+    fn report_infractions(&self) -> Value {
+        json!(self.infractions)
+    }
 }
 
 #[derive(Debug)]
@@ -225,6 +229,7 @@ impl Match {
 mod tests {
     use super::*;
     use std::cmp::Ordering;
+    use std::net::TcpListener;
     use crate::board::Board;
     use crate::player::{PlayerName, PlayerColor};
     use crate::net_config::NetConfig;
@@ -261,10 +266,14 @@ mod tests {
 
     fn new_local(player_count: usize) -> RoundRobin {
         RoundRobin {
-            tcp_listener: TcpListener::bind("localhost:8888").unwrap(),
+            tcp_listener: TcpListener::bind("localhost:8888").unwrap().into(),
             player_count,
             players: Vec::with_capacity(player_count),
             matches: Vec::new(),
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         }
     }
 
@@ -349,10 +358,14 @@ mod tests {
     #[test]
     fn one_cheater() {
         let mut test_rr = RoundRobin {
-            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap(),
+            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap().into(),
             players: Vec::with_capacity(2),
             matches: Vec::new(),
-            player_count: 2
+            player_count: 2,
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         };
         //push cheating local player
         test_rr.players.push(
@@ -368,10 +381,14 @@ mod tests {
     #[test]
     fn two_cheaters() {
         let mut test_rr = RoundRobin {
-            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap(),
+            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap().into(),
             players: Vec::with_capacity(2),
             matches: Vec::new(),
-            player_count: 2
+            player_count: 2,
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         };
         //push cheating local player
         test_rr.players.push(
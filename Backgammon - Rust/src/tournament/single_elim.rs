@@ -1,28 +1,39 @@
 use serde_json::{json, Value};
-use std::net::TcpListener;
 use crossbeam::channel::bounded;
 
 use crate::{
-    administrator::{Administrator, HandleCheater},
+    channel::ChannelListener,
     local_remote::{IPlayer, LocalPlayer},
-    strategy::rando::Rando,
-    tournament::tournament::{Tournament, accept_players},
+    player_board::PlayerStrat,
+    spectator::SpectatorHub,
+    tournament::tournament::{Tournament, Registration, GameSettings, Series, accept_players},
 };
 
 pub struct SingleElim {
-    tcp_listener: TcpListener,
+    tcp_listener: ChannelListener,
     remote_count: usize,
     local_count: usize,
+    /// rounds played so far
+    round: usize,
     players: Vec<Box<dyn IPlayer>>,
+    spectators: Option<SpectatorHub>,
+    registration: Registration,
+    settings: GameSettings,
+    infractions: Vec<Value>,
 }
 
 impl SingleElim {
-    pub fn new(player_count: usize, listener: TcpListener) -> SingleElim {
+    pub fn new(player_count: usize, listener: ChannelListener) -> SingleElim {
         SingleElim {
             tcp_listener: listener,
             remote_count: player_count,
             local_count: 0,
+            round: 0,
             players: Vec::with_capacity(player_count.next_power_of_two()),
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         }
     }
 
@@ -47,7 +58,7 @@ impl SingleElim {
     fn run_one_round(&mut self) {
         let num_to_add = self.players.len().next_power_of_two() - self.players.len();
         for _ in 0..num_to_add {
-            self.players.push(make_local_player(self.local_count));
+            self.players.push(make_local_player(self.local_count, self.settings.filler.clone()));
             self.local_count += 1;
         }
 
@@ -63,7 +74,7 @@ impl SingleElim {
                 player_two = self.players.get_unchecked(i + 1); //players.len must be pow of 2
             }
 
-            s1.send(Administrator::new(player_one.duplicate(), player_two.duplicate())).unwrap();
+            s1.send(Series::new(player_one.duplicate(), player_two.duplicate(), &self.settings, &self.spectators, (self.round, i / 2))).unwrap();
         }
 
 
@@ -71,9 +82,9 @@ impl SingleElim {
             let mut threads = Vec::with_capacity(num_matches);
             for _ in 0..num_matches {
                 threads.push(s.spawn(|_| {
-                    let mut admin = r1.recv().unwrap();
-                    admin.moderate_game(HandleCheater::EndGame);
-                    s1.send(admin).unwrap();
+                    let mut series = r1.recv().unwrap();
+                    series.play();
+                    s1.send(series).unwrap();
                 }));
             }
             for child in threads {
@@ -83,19 +94,22 @@ impl SingleElim {
 
         let mut valid_index = 0;
         for _ in 0..num_matches {
-            if let Some(winner) = r1.recv().unwrap().get_winning_player() {
+            let series = r1.recv().unwrap();
+            self.infractions.extend(series.report_infractions());
+            if let Some(winner) = series.get_winning_player() {
                 self.players[valid_index] = winner;
                 valid_index += 1;
             }
         }
 
         self.players.truncate(valid_index);
+        self.round += 1;
     }
 }
 
 impl Tournament for SingleElim {
     fn moderate_tournament(&mut self) {
-        accept_players(&mut self.players, &self.remote_count, &self.tcp_listener);
+        accept_players(&mut self.players, &self.remote_count, &self.tcp_listener, &self.registration, &self.settings);
         while self.players.len() > 1 {
             self.run_one_round();
         }
@@ -108,11 +122,27 @@ impl Tournament for SingleElim {
             _ => panic!("more than 1 player won single elimination"),
         }
     }
+
+    fn set_spectators(&mut self, hub: SpectatorHub) {
+        self.spectators = Some(hub);
+    }
+
+    fn set_registration(&mut self, registration: Registration) {
+        self.registration = registration;
+    }
+
+    fn set_settings(&mut self, settings: GameSettings) {
+        self.settings = settings;
+    }
+
+    fn report_infractions(&self) -> Value {
+        json!(self.infractions)
+    }
 }
 
-fn make_local_player(id: usize) -> Box<LocalPlayer<Rando>> {
+fn make_local_player<S: PlayerStrat + Clone + 'static>(id: usize, strategy: S) -> Box<LocalPlayer<S>> {
     //! creates LocalPlayer with name Filler_{id}
-    Box::new(LocalPlayer::new(String::from("Filler_") + &*id.to_string(), Rando))
+    Box::new(LocalPlayer::new(String::from("Filler_") + &*id.to_string(), strategy))
 }
 
 #[cfg(test)]
@@ -122,6 +152,7 @@ mod tests {
     use crate::board::Board;
     use crate::player::{PlayerColor, PlayerName};
     use crate::r#move::Move;
+    use crate::strategy::rando::Rando;
 
     struct CheatingLocal {
         player: Box<dyn IPlayer>,
@@ -177,23 +208,28 @@ mod tests {
     #[test]
     fn one_cheaters() {
         let mut test_se = SingleElim {
-            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap(),
+            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap().into(),
             remote_count: 0,
             local_count: 2,
+            round: 0,
             players: Vec::new(),
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         };
         //push cheating local player
         test_se.players.push(
-            Box::new(CheatingLocal { player: make_local_player(0) })
+            Box::new(CheatingLocal { player: make_local_player(0, Rando) })
         );
-        test_se.players.push(make_local_player(1));
+        test_se.players.push(make_local_player(1, Rando));
 
         test_se.run_one_round();
         assert_eq!(test_se.report_winner(), json!("Filler_1"));
 
         test_se.players.clear();
-        test_se.players.push(make_local_player(0));
-        test_se.players.push(Box::new(CheatingLocal { player: make_local_player(1) }));
+        test_se.players.push(make_local_player(0, Rando));
+        test_se.players.push(Box::new(CheatingLocal { player: make_local_player(1, Rando) }));
         test_se.moderate_tournament();
         assert_eq!(test_se.report_winner(), json!("Filler_0"));
     }
@@ -201,17 +237,22 @@ mod tests {
     #[test]
     fn two_cheaters() {
         let mut test_se = SingleElim {
-            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap(),
+            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap().into(),
             remote_count: 0,
             local_count: 2,
+            round: 0,
             players: Vec::new(),
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         };
         //push cheating local player
         test_se.players.push(
-            Box::new(CheatingLocal { player: make_local_player(0) })
+            Box::new(CheatingLocal { player: make_local_player(0, Rando) })
         );
         test_se.players.push(
-            Box::new(CheatingLocal { player: make_local_player(1) })
+            Box::new(CheatingLocal { player: make_local_player(1, Rando) })
         );
 
         test_se.moderate_tournament();
@@ -221,20 +262,25 @@ mod tests {
     #[test]
     fn three_cheaters() {
         let mut test_se = SingleElim {
-            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap(),
+            tcp_listener: NetConfig::connect_listener(json!(9203)).unwrap().into(),
             remote_count: 0,
             local_count: 3,
+            round: 0,
             players: Vec::new(),
+            spectators: None,
+            registration: Registration::default(),
+            settings: GameSettings::default(),
+            infractions: Vec::new(),
         };
         //push cheating local player
         test_se.players.push(
-            Box::new(CheatingLocal { player: make_local_player(0) })
+            Box::new(CheatingLocal { player: make_local_player(0, Rando) })
         );
         test_se.players.push(
-            Box::new(CheatingLocal { player: make_local_player(1) })
+            Box::new(CheatingLocal { player: make_local_player(1, Rando) })
         );
         test_se.players.push(
-            Box::new(CheatingLocal { player: make_local_player(2) })
+            Box::new(CheatingLocal { player: make_local_player(2, Rando) })
         );
         test_se.moderate_tournament();
         assert_eq!(test_se.report_winner(), json!("Filler_3"));
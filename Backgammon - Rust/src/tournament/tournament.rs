@@ -1,21 +1,44 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 use serde::{Deserialize, Deserializer, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use crate::{
+    administrator::{Administrator, GameLimits, HandleCheater, Replacement, Winner},
+    channel::{ChannelListener, Transport},
+    cheat::Infraction,
+    log::Logger,
     net_config::NetConfig,
+    player::PlayerColor,
+    record::GameRecorder,
+    rules::any_rules::AnyRules,
+    spectator::SpectatorHub,
+    start_position::StartPosition,
+    strategy::{any_strat::AnyStrat, rando::Rando},
     tournament::{
         round_robin::RoundRobin,
         single_elim::SingleElim,
     },
     local_remote::{IPlayer, RemotePlayer},
+    session::{SessionRegistry, RECONNECT_GRACE},
 };
-use std::net::TcpListener;
 
 #[derive(Debug)]
 pub struct TournConfig {
     players: u64,
     port: Value,
     ev_type: TType,
+    spectator_port: Option<Value>,
+    registration: Registration,
+    /// used by players and spectators alike
+    transport: Transport,
+    /// the address players and spectators connect to
+    bind: String,
+    settings: GameSettings,
+    /// where the final results are written, if anywhere
+    results: Option<PathBuf>,
 }
 
 impl TournConfig {
@@ -24,15 +47,71 @@ impl TournConfig {
             players,
             port,
             ev_type,
+            spectator_port: None,
+            registration: Registration::default(),
+            transport: Transport::Tcp,
+            bind: "localhost".to_string(),
+            settings: GameSettings::default(),
+            results: None,
         }
     }
 
+    pub fn with_bind(mut self, host: String) -> TournConfig {
+        self.bind = host;
+        self
+    }
+
+    pub fn with_settings(mut self, settings: GameSettings) -> TournConfig {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_results(mut self, path: PathBuf) -> TournConfig {
+        self.results = Some(path);
+        self
+    }
+
+    #[inline]
+    pub fn get_settings(&self) -> &GameSettings { &self.settings }
+
+    #[inline]
+    pub fn get_results(&self) -> Option<&Path> { self.results.as_deref() }
+
+    pub fn with_transport(mut self, transport: Transport) -> TournConfig {
+        self.transport = transport;
+        self
+    }
+
+    pub fn with_registration(mut self, registration: Registration) -> TournConfig {
+        self.registration = registration;
+        self
+    }
+
+    pub fn with_spectator_port(mut self, port: Value) -> TournConfig {
+        //! spectators connecting to port receive every game's events
+        self.spectator_port = Some(port);
+        self
+    }
+
     pub fn to_tournament(&self) -> Box<dyn Tournament> {
-        let listener = NetConfig::connect_listener(self.port.clone()).unwrap();
-        match self.ev_type {
+        let listener = self.listen(&self.port);
+        let mut tournament: Box<dyn Tournament> = match self.ev_type {
             TType::SingleElim => Box::new(SingleElim::new(self.players as usize, listener)),
             TType::RndRbn => Box::new(RoundRobin::new(self.players as usize, listener))
+        };
+        if let Some(port) = &self.spectator_port {
+            let hub = SpectatorHub::new();
+            hub.listen(self.listen(port));
+            tournament.set_spectators(hub);
         }
+        tournament.set_registration(self.registration.clone());
+        tournament.set_settings(self.settings.clone());
+        tournament
+    }
+
+    fn listen(&self, port: &Value) -> ChannelListener {
+        let listener = NetConfig::bind_listener(&self.bind, port.clone()).unwrap();
+        ChannelListener::new(listener, self.transport)
     }
 }
 
@@ -62,13 +141,461 @@ impl<'de> Deserialize<'de> for TType {
 pub trait Tournament {
     fn moderate_tournament(&mut self);
     fn report_winner(&mut self) -> Value;
+    fn set_spectators(&mut self, hub: SpectatorHub);
+    fn set_registration(&mut self, registration: Registration);
+    fn set_settings(&mut self, settings: GameSettings);
+    /// every time a player was caught cheating, and why
+    fn report_infractions(&self) -> Value;
+}
+
+/// Limits on how long remote players can take
+#[derive(Clone, Copy, Debug)]
+pub struct Timeouts {
+    /// how long a player has to answer each message, None waits forever
+    pub response: Option<Duration>,
+    /// how long a dropped player has to reconnect
+    pub reconnect: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Timeouts {
+        Timeouts { response: None, reconnect: RECONNECT_GRACE }
+    }
 }
 
-pub fn accept_players(players: &mut Vec<Box<dyn IPlayer>>, count: &usize, listener: &TcpListener) {
-    for _ in 0..*count {
-        match listener.accept() {
-            Ok((socket, _)) => players.push(Box::new(RemotePlayer::new(socket))),
+/// How a tournament plays its games, once its players have joined
+#[derive(Clone, Debug)]
+pub struct GameSettings {
+    pub cheaters: HandleCheater,
+    pub replacement: Replacement,
+    /// plays the empty seats in a single elimination bracket
+    pub filler: AnyStrat,
+    /// each pairing plays the best of this many games, always odd
+    pub games_per_pairing: usize,
+    /// makes every game's dice and colors reproducible
+    pub seed: Option<u64>,
+    pub timeouts: Timeouts,
+    pub limits: GameLimits,
+    /// the game every pairing plays, backgammon by default
+    pub rules: AnyRules,
+    /// where the checkers are when every game starts, where rules start them if None
+    pub start: Option<StartPosition>,
+    /// directory every game is recorded to, if set
+    pub records: Option<PathBuf>,
+    pub logger: Logger,
+}
+
+impl Default for GameSettings {
+    fn default() -> GameSettings {
+        GameSettings {
+            cheaters: HandleCheater::EndGame,
+            replacement: Replacement::default(),
+            filler: AnyStrat::Rando(Rando),
+            games_per_pairing: 1,
+            seed: None,
+            timeouts: Timeouts::default(),
+            limits: GameLimits::default(),
+            rules: AnyRules::default(),
+            start: None,
+            records: None,
+            logger: Logger::off(),
+        }
+    }
+}
+
+/**
+The best of settings.games_per_pairing games between two players.
+The series stops early once a player has won most of its games or cheats.
+Void games count for nobody, so a series can end without a winner.
+*/
+pub struct Series {
+    player_one: Box<dyn IPlayer>,
+    player_two: Box<dyn IPlayer>,
+    settings: GameSettings,
+    spectators: Option<SpectatorHub>,
+    /// round and pairing, which name the series' records and seed its games
+    id: (usize, usize),
+    wins: (usize, usize),
+    /// with the game they happened in
+    infractions: Vec<(usize, Infraction)>,
+}
+
+impl Series {
+    pub fn new(player_one: Box<dyn IPlayer>, player_two: Box<dyn IPlayer>, settings: &GameSettings,
+               spectators: &Option<SpectatorHub>, id: (usize, usize)) -> Series {
+        Series {
+            player_one,
+            player_two,
+            settings: settings.clone(),
+            spectators: spectators.clone(),
+            id,
+            wins: (0, 0),
+            infractions: Vec::new(),
+        }
+    }
+
+    pub fn play(&mut self) {
+        let needed = self.settings.games_per_pairing / 2 + 1;
+        for game in 0..self.settings.games_per_pairing {
+            if self.wins.0 >= needed || self.wins.1 >= needed || self.player_one.has_cheated() || self.player_two.has_cheated() {
+                break;
+            }
+            let mut admin = self.new_game(game);
+            admin.moderate_game(self.settings.cheaters);
+            // players come back with whether they cheated, or as their replacement
+            let players = admin.get_players();
+            self.player_one = players.0;
+            self.player_two = players.1;
+            match admin.get_winner() {
+                Winner::PlayerOne => self.wins.0 += 1,
+                Winner::PlayerTwo => self.wins.1 += 1,
+                Winner::None => {}
+            }
+            if let Some(cutoff) = admin.get_cutoff() {
+                self.settings.logger.info(format_args!("round {} pairing {} game {}: stopped after {} turns, {}",
+                    self.id.0, self.id.1, game, admin.get_num_turns(), cutoff));
+            }
+            for infraction in admin.get_infractions() {
+                self.settings.logger.info(format_args!("round {} pairing {} game {}: {} cheated, {}",
+                    self.id.0, self.id.1, game, infraction.name, infraction.reason));
+                self.infractions.push((game, infraction));
+            }
+            self.settings.logger.debug(format_args!("round {} pairing {} game {}: {} {} - {} {}", self.id.0, self.id.1, game,
+                self.player_one.get_name().to_string(), self.wins.0, self.wins.1, self.player_two.get_name().to_string()));
+        }
+        let winner = match self.get_winning_player() {
+            Some(mut player) => player.get_name().to_string(),
+            None => "nobody".to_string(),
+        };
+        self.settings.logger.info(format_args!("round {} pairing {}: {} won {} - {}", self.id.0, self.id.1, winner, self.wins.0, self.wins.1));
+    }
+
+    fn new_game(&self, game: usize) -> Administrator {
+        let player_one = self.player_one.duplicate();
+        let player_two = self.player_two.duplicate();
+        let mut admin = match self.settings.seed {
+            Some(seed) => {
+                // players take turns being black
+                let color = if game & 1 == 0 { PlayerColor::Black } else { PlayerColor::White };
+                Administrator::seeded(player_one, player_two, color, game_seed(seed, self.id, game))
+            }
+            None => Administrator::new(player_one, player_two),
+        };
+        admin.set_replacement(self.settings.replacement.clone());
+        admin.set_limits(self.settings.limits);
+        admin.set_rules(self.settings.rules);
+        if let Some(start) = &self.settings.start {
+            admin.set_start(start.clone());
+        }
+        if let Some(hub) = &self.spectators {
+            admin.add_observer(Box::new(hub.feed()));
+        }
+        if let Some(dir) = &self.settings.records {
+            let path = dir.join(format!("round-{}-pairing-{}-game-{}.jsonl", self.id.0, self.id.1, game));
+            match fs::create_dir_all(dir).and_then(|_| GameRecorder::create(&path, game as u64)) {
+                Ok(recorder) => admin.add_observer(Box::new(recorder)),
+                Err(e) => self.settings.logger.info(format_args!("can't record to {}: {}", path.display(), e)),
+            }
+        }
+        admin
+    }
+
+    pub fn get_winner(&self) -> Winner {
+        //! a player who cheated loses the series, whatever the score
+        match (self.player_one.has_cheated(), self.player_two.has_cheated()) {
+            (true, true) => Winner::None,
+            (true, false) => Winner::PlayerTwo,
+            (false, true) => Winner::PlayerOne,
+            _ if self.wins.0 > self.wins.1 => Winner::PlayerOne,
+            _ if self.wins.1 > self.wins.0 => Winner::PlayerTwo,
+            _ => Winner::None,
+        }
+    }
+
+    pub fn get_winning_player(&self) -> Option<Box<dyn IPlayer>> {
+        match self.get_winner() {
+            Winner::PlayerOne => Some(self.player_one.duplicate()),
+            Winner::PlayerTwo => Some(self.player_two.duplicate()),
+            Winner::None => None,
+        }
+    }
+
+    pub fn get_players(&self) -> (Box<dyn IPlayer>, Box<dyn IPlayer>) {
+        (self.player_one.duplicate(), self.player_two.duplicate())
+    }
+
+    #[inline]
+    pub fn get_wins(&self) -> (usize, usize) { self.wins }
+
+    pub fn report_infractions(&self) -> Vec<Value> {
+        //! each infraction with the round, pairing and game it happened in
+        self.infractions.iter().map(|(game, infraction)| {
+            let mut report = infraction.to_json();
+            report["round"] = json!(self.id.0);
+            report["pairing"] = json!(self.id.1);
+            report["game"] = json!(game);
+            report
+        }).collect()
+    }
+}
+
+fn game_seed(seed: u64, id: (usize, usize), game: usize) -> u64 {
+    //! gives every game of a seeded tournament its own dice
+    seed ^ ((id.0 as u64) << 40) ^ ((id.1 as u64) << 20) ^ game as u64
+}
+
+/// What to do when a player joins with a name that is already taken
+#[derive(Clone, PartialEq, Debug)]
+pub enum NamePolicy {
+    Reject,
+    /// appends a number, so the second "Lou" becomes "Lou (2)"
+    Rename,
+}
+
+/// Rules players must pass to join a tournament
+#[derive(Clone, Debug)]
+pub struct Registration {
+    pub duplicate_names: NamePolicy,
+    /// players must answer "secret" with this, if set
+    pub secret: Option<String>,
+}
+
+impl Default for Registration {
+    fn default() -> Registration {
+        Registration {
+            duplicate_names: NamePolicy::Rename,
+            secret: None,
+        }
+    }
+}
+
+impl Registration {
+    pub fn register(&self, player: &mut RemotePlayer, taken: &[String]) -> Result<String, String> {
+        //! returns the player's unique name, or why they can't join
+        let name = player.get_name().to_string();
+        if player.has_cheated() {
+            return Err("no name given".to_string());
+        }
+        if let Some(secret) = &self.secret {
+            if !player.check_secret(secret) {
+                return Err("wrong secret".to_string());
+            }
+        }
+        if !taken.contains(&name) {
+            return Ok(name);
+        }
+        match self.duplicate_names {
+            NamePolicy::Reject => Err(format!("name '{}' is taken", name)),
+            NamePolicy::Rename => {
+                let unique = (2..).map(|i| format!("{} ({})", name, i)).find(|n| !taken.contains(n)).unwrap();
+                player.rename(unique.clone());
+                Ok(unique)
+            }
+        }
+    }
+}
+
+pub fn new_game(player_one: Box<dyn IPlayer>, player_two: Box<dyn IPlayer>, spectators: &Option<SpectatorHub>) -> Administrator {
+    //! creates an administrator which publishes to spectators, if there are any
+    let mut admin = Administrator::new(player_one, player_two);
+    if let Some(hub) = spectators {
+        admin.add_observer(Box::new(hub.feed()));
+    }
+    admin
+}
+
+pub fn accept_players(players: &mut Vec<Box<dyn IPlayer>>, count: &usize, listener: &ChannelListener, registration: &Registration,
+                      settings: &GameSettings) {
+    /*!
+    accepts connections until count players have registered, rejected connections and
+    failed WebSocket handshakes don't count. Once every player has joined, the listener only accepts players reconnecting to a session.
+    */
+    let sessions = SessionRegistry::new(settings.timeouts.reconnect);
+    let mut taken: Vec<String> = players.iter_mut().map(|p| p.get_name().to_string()).collect();
+    let mut accepted = 0;
+    while accepted < *count {
+        let mut player = match listener.accept() {
+            Ok(channel) => RemotePlayer::with_sessions(channel, sessions.clone()),
+            Err(_) if listener.transport() == Transport::WebSocket => continue,
             Err(_) => panic!("failed to connect")
         };
+        player.set_response_timeout(settings.timeouts.response);
+        match registration.register(&mut player, &taken) {
+            Ok(name) => {
+                settings.logger.info(format_args!("{} joined", name));
+                taken.push(name);
+                players.push(Box::new(player));
+                accepted += 1;
+            }
+            Err(reason) => {
+                settings.logger.info(format_args!("rejected a player: {}", reason));
+                player.reject(&reason);
+            }
+        }
+    }
+    if *count > 0 {
+        sessions.listen(listener.try_clone().unwrap());
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use crate::{
+        local_remote::LocalPlayer,
+        network_player::NetworkPlayer,
+        player::Player,
+        record::{GameRecord, RecordStep},
+        strategy::{bopsy::Bopsy, smarty::Smarty},
+        tcp_handler::TcpHandler,
+        ws_handler::WsHandler,
+    };
+
+    fn join_all(listener: &ChannelListener, clients: Vec<(&str, Option<&str>)>) -> Vec<thread::JoinHandle<()>> {
+        //! connects every client in order, each answering the server on its own thread
+        let addr = listener.local_addr().unwrap();
+        let transport = listener.transport();
+        let mut threads = Vec::new();
+        for (name, secret) in clients {
+            let stream = TcpStream::connect(addr).unwrap();
+            let player = Player::new(name.to_string(), Rando);
+            let secret = secret.map(|s| s.to_string());
+            threads.push(thread::spawn(move || {
+                let mut client = match transport {
+                    Transport::Tcp => NetworkPlayer::new(player, TcpHandler::new(stream)),
+                    // the handshake waits for the server to accept, so it happens on the client's thread
+                    Transport::WebSocket => NetworkPlayer::new(player, WsHandler::connect(stream, &format!("ws://{}/", addr)).unwrap()),
+                };
+                if let Some(secret) = secret {
+                    client = client.with_secret(secret);
+                }
+                client.handle_stream().ok();
+            }));
+        }
+        threads
+    }
+
+    fn names(players: &mut Vec<Box<dyn IPlayer>>) -> Vec<String> {
+        players.iter_mut().map(|p| p.get_name().to_string()).collect()
+    }
+
+    #[test]
+    fn rename_duplicates() {
+        let listener: ChannelListener = TcpListener::bind("localhost:0").unwrap().into();
+        let _clients = join_all(&listener, vec!(("Lou", None), ("Lou", None), ("Lou", None)));
+        let mut players = Vec::new();
+        accept_players(&mut players, &3, &listener, &Registration::default(), &GameSettings::default());
+        assert_eq!(names(&mut players), vec!("Lou", "Lou (2)", "Lou (3)"));
+    }
+
+    #[test]
+    fn reject_duplicates_and_secret() {
+        let listener: ChannelListener = TcpListener::bind("localhost:0").unwrap().into();
+        let clients = join_all(&listener, vec!(
+            ("Lou", Some("pass")),
+            ("Lou", Some("pass")),
+            ("Bob", Some("wrong")),
+            ("Bob", None),
+            ("Bob", Some("pass")),
+        ));
+        let registration = Registration {
+            duplicate_names: NamePolicy::Reject,
+            secret: Some("pass".to_string()),
+        };
+        let mut players = Vec::new();
+        accept_players(&mut players, &2, &listener, &registration, &GameSettings::default());
+        assert_eq!(names(&mut players), vec!("Lou", "Bob"));
+
+        // rejected clients are disconnected
+        let mut clients = clients.into_iter();
+        clients.next();
+        for rejected in clients.take(3) {
+            rejected.join().unwrap();
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn websocket_players() {
+        let listener = NetConfig::connect_websocket_listener(serde_json::json!(0)).unwrap();
+        let clients = join_all(&listener, vec!(("Lou", None), ("Bob", None)));
+        let mut players = Vec::new();
+        accept_players(&mut players, &2, &listener, &Registration::default(), &GameSettings::default());
+        assert_eq!(names(&mut players), vec!("Lou", "Bob"));
+
+        let two = players.pop().unwrap();
+        let one = players.pop().unwrap();
+        let mut admin = new_game(one, two, &None);
+        admin.moderate_game(crate::administrator::HandleCheater::EndGame);
+        assert!(admin.get_num_turns() > 0);
+        drop(admin);
+        for client in clients {
+            client.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn slow_player_times_out() {
+        let listener: ChannelListener = TcpListener::bind("localhost:0").unwrap().into();
+        // connects but never answers
+        let _silent = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let _clients = join_all(&listener, vec!(("Lou", None)));
+        let settings = GameSettings {
+            timeouts: Timeouts { response: Some(Duration::from_millis(200)), ..Timeouts::default() },
+            ..GameSettings::default()
+        };
+        let mut players = Vec::new();
+        accept_players(&mut players, &1, &listener, &Registration::default(), &settings);
+        assert_eq!(names(&mut players), vec!("Lou"));
+    }
+
+    #[test]
+    fn seeded_series() {
+        let play = |records: &PathBuf| {
+            let settings = GameSettings {
+                games_per_pairing: 3,
+                seed: Some(5),
+                records: Some(records.clone()),
+                ..GameSettings::default()
+            };
+            let mut series = Series::new(
+                Box::new(LocalPlayer::new("smarty".to_string(), Smarty::new())),
+                Box::new(LocalPlayer::new("bopsy".to_string(), Bopsy)),
+                &settings, &None, (1, 2));
+            series.play();
+            let wins = series.get_wins();
+            assert_eq!(wins.0.max(wins.1), 2);
+
+            let mut games = Vec::new();
+            for game in 0..3 {
+                let path = records.join(format!("round-1-pairing-2-game-{}.jsonl", game));
+                assert_eq!(path.exists(), game < wins.0 + wins.1);
+                if path.exists() {
+                    games.extend(GameRecord::read_all(std::io::BufReader::new(fs::File::open(path).unwrap())).unwrap());
+                }
+            }
+            fs::remove_dir_all(records).ok();
+            games
+        };
+        let dir = std::env::temp_dir().join(format!("series-{}", std::process::id()));
+        let first = play(&dir.join("first"));
+        let second = play(&dir.join("second"));
+
+        // the strategies can break ties either way, but the dice and colors are the same
+        let dice = |game: &GameRecord| -> Vec<(PlayerColor, Vec<u8>)> {
+            game.steps.iter().filter_map(|step| match step {
+                RecordStep::Turn { color, dice, .. } => Some((color.clone(), dice.clone())),
+                _ => None,
+            }).collect()
+        };
+        for (one, two) in first.iter().zip(second.iter()) {
+            assert!(one.finished && two.finished);
+            assert_eq!((&one.black, &one.white), (&two.black, &two.white));
+            let (one, two) = (dice(one), dice(two));
+            let shorter = one.len().min(two.len());
+            assert_eq!(one[..shorter], two[..shorter]);
+        }
+        assert_eq!(first[0].black, "smarty");
+        fs::remove_dir_all(dir).ok();
+    }
+}
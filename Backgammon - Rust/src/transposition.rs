@@ -0,0 +1,147 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    player::PlayerColor,
+    zobrist::with_side,
+};
+
+/// TranspositionTable shared between clones of a PlayerStrat
+pub type SharedTable = Arc<Mutex<TranspositionTable>>;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TtEntry {
+    pub key: u64,
+    pub score: usize,
+    pub depth: u8,
+}
+
+/**
+Fixed size table of position evaluations, keyed by zobrist hash plus side to move.
+Entries are indexed by the low bits of the key. When two positions share a slot,
+the evaluation searched to the greater depth is kept.
+*/
+pub struct TranspositionTable {
+    entries: Vec<Option<TtEntry>>,
+    mask: usize,
+    stored: usize,
+}
+
+impl TranspositionTable {
+    pub fn new(capacity: usize) -> TranspositionTable {
+        //! creates table holding at most capacity entries (rounded down to a power of 2)
+        let size = match capacity {
+            0 | 1 => 1,
+            _ if capacity.is_power_of_two() => capacity,
+            _ => capacity.next_power_of_two() >> 1,
+        };
+        TranspositionTable {
+            entries: vec![None; size],
+            mask: size - 1,
+            stored: 0,
+        }
+    }
+
+    pub fn shared(capacity: usize) -> SharedTable {
+        Arc::new(Mutex::new(TranspositionTable::new(capacity)))
+    }
+
+    pub fn probe(&self, hash: u64, to_move: &PlayerColor) -> Option<&TtEntry> {
+        //! returns stored evaluation of position, if present
+        self.probe_key(with_side(hash, to_move))
+    }
+
+    pub fn probe_key(&self, key: u64) -> Option<&TtEntry> {
+        //! returns stored evaluation for a key that already includes the side to move
+        match &self.entries[key as usize & self.mask] {
+            Some(entry) if entry.key == key => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn store(&mut self, hash: u64, to_move: &PlayerColor, score: usize, depth: u8) {
+        //! stores evaluation, replacing the slot's entry unless it was searched deeper
+        self.store_key(with_side(hash, to_move), score, depth);
+    }
+
+    pub fn store_key(&mut self, key: u64, score: usize, depth: u8) {
+        let slot = &mut self.entries[key as usize & self.mask];
+        match slot {
+            Some(entry) if entry.key != key && entry.depth > depth => return,
+            Some(_) => (),
+            None => self.stored += 1,
+        }
+        *slot = Some(TtEntry { key, score, depth });
+    }
+
+    pub fn clear(&mut self) {
+        for entry in self.entries.iter_mut() {
+            *entry = None;
+        }
+        self.stored = 0;
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize { self.entries.len() }
+
+    #[inline]
+    pub fn len(&self) -> usize { self.stored }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.stored == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Board,
+        r#move::Move,
+    };
+
+    #[test]
+    fn sizes() {
+        assert_eq!(TranspositionTable::new(0).capacity(), 1);
+        assert_eq!(TranspositionTable::new(1).capacity(), 1);
+        assert_eq!(TranspositionTable::new(1024).capacity(), 1024);
+        assert_eq!(TranspositionTable::new(1000).capacity(), 512);
+    }
+
+    #[test]
+    fn store_probe() {
+        let mut tt = TranspositionTable::new(64);
+        let board = Board::new();
+        assert!(tt.probe(board.zobrist_hash(), &PlayerColor::Black).is_none());
+
+        tt.store(board.zobrist_hash(), &PlayerColor::Black, 42, 2);
+        let entry = tt.probe(board.zobrist_hash(), &PlayerColor::Black).unwrap();
+        assert_eq!(entry.score, 42);
+        assert_eq!(entry.depth, 2);
+        assert!(tt.probe(board.zobrist_hash(), &PlayerColor::White).is_none());
+        assert_eq!(tt.len(), 1);
+
+        tt.store(board.zobrist_hash(), &PlayerColor::Black, 7, 1);
+        assert_eq!(tt.probe(board.zobrist_hash(), &PlayerColor::Black).unwrap().score, 7);
+        assert_eq!(tt.len(), 1);
+
+        tt.clear();
+        assert!(tt.is_empty());
+        assert!(tt.probe(board.zobrist_hash(), &PlayerColor::Black).is_none());
+    }
+
+    #[test]
+    fn depth_preferred() {
+        let mut tt = TranspositionTable::new(1);
+        let board = Board::new();
+        let mut other = Board::new();
+        other.make_move(&PlayerColor::Black, &Move { start: 13, end: 10 });
+
+        tt.store(board.zobrist_hash(), &PlayerColor::Black, 1, 3);
+        tt.store(other.zobrist_hash(), &PlayerColor::Black, 2, 1);
+        assert_eq!(tt.probe(board.zobrist_hash(), &PlayerColor::Black).unwrap().score, 1);
+        assert!(tt.probe(other.zobrist_hash(), &PlayerColor::Black).is_none());
+
+        tt.store(other.zobrist_hash(), &PlayerColor::Black, 2, 3);
+        assert!(tt.probe(board.zobrist_hash(), &PlayerColor::Black).is_none());
+        assert_eq!(tt.probe(other.zobrist_hash(), &PlayerColor::Black).unwrap().score, 2);
+    }
+}
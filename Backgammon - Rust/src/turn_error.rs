@@ -0,0 +1,130 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::r#move::Move;
+
+/// What is wrong with a single move, given the moves before it
+#[derive(Clone, PartialEq, Debug)]
+pub enum MoveError {
+    /// starts or ends past home or the bar
+    OffBoard,
+    NoChecker,
+    /// a checker is on the bar, and this move doesn't enter it
+    MustEnter,
+    Backwards,
+    /// no unused die is the distance moved
+    NoSuchDie,
+    /// the opponent has two or more checkers there
+    Blocked,
+    BearOffEarly,
+    /// bears off with a larger die while a checker is further from home
+    NotFurthest,
+    /// moves a checker pinned by the opponent
+    Pinned,
+    /// breaks a rule of the game being played, such as Fevga's one checker leaving the start at a time
+    Forbidden,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            MoveError::OffBoard => "isn't on the board",
+            MoveError::NoChecker => "starts from a point with no checker",
+            MoveError::MustEnter => "must enter from the bar first",
+            MoveError::Backwards => "moves backwards",
+            MoveError::NoSuchDie => "doesn't match an unused die",
+            MoveError::Blocked => "lands on a blocked point",
+            MoveError::BearOffEarly => "bears off before all checkers are home",
+            MoveError::NotFurthest => "bears off with a larger die while a checker is further from home",
+            MoveError::Pinned => "moves a pinned checker",
+            MoveError::Forbidden => "isn't allowed by the rules of this game",
+        };
+        f.write_str(msg)
+    }
+}
+
+/// Why a turn isn't legal, as precisely as can be told
+#[derive(Clone, PartialEq, Debug)]
+pub enum TurnError {
+    /// index counts from 1, as players would
+    Move { index: usize, error: MoveError },
+    /// every move is legal, but more dice could have been played
+    UnusedDice { used: usize, possible: usize },
+    /// only one die can be played, and the smaller one was
+    MustUseLarger,
+    /// every move is legal on its own, but the turn isn't
+    NotLegal,
+}
+
+impl fmt::Display for TurnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TurnError::Move { index, error } => write!(f, "move {} {}", index, error),
+            TurnError::UnusedDice { used: 1, possible: 2 } => write!(f, "does not use both dice when possible"),
+            TurnError::UnusedDice { used, possible } => write!(f, "uses {} dice when {} can be used", used, possible),
+            TurnError::MustUseLarger => write!(f, "must use the larger die when only one can be used"),
+            TurnError::NotLegal => write!(f, "isn't a legal turn"),
+        }
+    }
+}
+
+/// A turn that was rejected, with a legal turn that could have been played instead
+#[derive(Clone, PartialEq, Debug)]
+pub struct IllegalTurn {
+    pub error: TurnError,
+    /// shares as many leading moves with the rejected turn as possible, empty if the player can't move
+    pub alternative: Vec<Move>,
+}
+
+impl IllegalTurn {
+    pub fn to_json(&self) -> Value {
+        let alternative: Vec<Value> = self.alternative.iter().map(|mve| mve.to_json()).collect();
+        json!({ "error": self.error.to_string(), "alternative": alternative })
+    }
+}
+
+impl fmt::Display for IllegalTurn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+pub fn closest_turn(moves: &[Move], legal: Vec<Vec<Move>>) -> Vec<Move> {
+    //! the legal turn that keeps the most of moves' opening
+    let shared = |turn: &Vec<Move>| turn.iter().zip(moves).take_while(|(a, b)| a == b).count();
+    let mut best: Option<(usize, Vec<Move>)> = None;
+    for turn in legal {
+        let n = shared(&turn);
+        if best.as_ref().is_none_or(|(most, _)| n > *most) {
+            best = Some((n, turn));
+        }
+    }
+    best.map(|(_, turn)| turn).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        let error = TurnError::Move { index: 3, error: MoveError::NoChecker };
+        assert_eq!(error.to_string(), "move 3 starts from a point with no checker");
+        assert_eq!(TurnError::UnusedDice { used: 1, possible: 2 }.to_string(), "does not use both dice when possible");
+        assert_eq!(TurnError::UnusedDice { used: 2, possible: 4 }.to_string(), "uses 2 dice when 4 can be used");
+        let illegal = IllegalTurn { error, alternative: vec!(Move { start: 0, end: 3 }) };
+        assert_eq!(illegal.to_json(), json!({ "error": "move 3 starts from a point with no checker", "alternative": [["bar", 3]] }));
+    }
+
+    #[test]
+    fn closest() {
+        let moves = vec!(Move { start: 1, end: 3 }, Move { start: 5, end: 9 });
+        let legal = vec!(
+            vec!(Move { start: 12, end: 14 }, Move { start: 12, end: 16 }),
+            vec!(Move { start: 1, end: 3 }, Move { start: 3, end: 7 }),
+        );
+        assert_eq!(closest_turn(&moves, legal)[1], Move { start: 3, end: 7 });
+        assert!(closest_turn(&moves, vec!()).is_empty());
+    }
+}
@@ -1,6 +1,9 @@
+use std::ops::Range;
+
 use crate::{
     dice_tracker::DiceTracker,
     player_board::flip_move,
+    r#mod::{move_checker, MAX_CHECKERS},
     r#move::Move,
 };
 
@@ -30,6 +33,8 @@ impl TurnNode {
 
     #[inline]
     pub fn set_branches(&mut self, branches: Vec<Box<TurnNode>>) {
+        //! trees are built from the leaves up, so max_depth follows from the branches
+        self.max_depth = branches.iter().map(|node| node.max_depth).max().unwrap_or(0) + 1;
         self.next = branches;
     }
 
@@ -56,32 +61,6 @@ impl TurnNode {
         self.max_depth
     }
 
-    pub fn compute_depth(&mut self) -> u8 {
-        //! sets max_depth recursively, based on depth of children
-        if self.next.is_empty() {
-            self.max_depth = 1;
-            return self.max_depth;
-        } else {
-            let mut max: u8 = 0;
-            for node in self.next.iter_mut() {
-                let depth = node.compute_depth();
-                if depth > max {
-                    max = depth;
-                }
-            }
-            self.max_depth = max + 1;
-            return self.max_depth;
-        }
-    }
-
-    pub fn prune(&mut self, depth: &u8) {
-        //! removes all branches that are of lesser depth
-        self.next.retain(|node| &node.get_max_depth() == &(depth - &1));
-        for node in self.next.iter_mut() {
-            node.prune(&(depth - &1));
-        }
-    }
-
     pub fn to_array(&self, arr: &mut Vec<Vec<Move>>) {
         self.add_to_array(&mut Vec::with_capacity(self.max_depth as usize), arr);
     }
@@ -130,16 +109,255 @@ impl TurnNode {
     }
 
     #[inline]
+    #[deprecated(note = "strategies score turns with PlayerStrat::score_move, nothing reads the score of a node")]
     pub fn set_score(&mut self, score: usize) {
         self.scr = score;
     }
 
     #[inline]
+    #[deprecated(note = "strategies score turns with PlayerStrat::score_move, nothing reads the score of a node")]
     pub fn get_score(&self) -> &usize {
         &self.scr
     }
 }
 
+/// A node of a turn tree in a TurnArena
+#[derive(Clone)]
+struct ArenaNode {
+    mve: Move,
+    dice: DiceTracker,
+    /// indices of the node's branches, which are stored next to each other
+    next: Range<usize>,
+    max_depth: u8,
+}
+
+/**
+Turn trees built in one flat vector, with branches linked by index, so generating
+the tree for a roll of doubles doesn't allocate a box for every node.
+Only the turns kept after pruning become TurnNodes.
+Clear and reuse the arena to keep its capacity
+*/
+#[derive(Default)]
+pub struct TurnArena {
+    nodes: Vec<ArenaNode>,
+}
+
+impl TurnArena {
+    #[inline]
+    pub fn clear(&mut self) { self.nodes.clear(); }
+
+    #[inline]
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    #[inline]
+    pub fn push(&mut self, mve: Move, tracker: DiceTracker) {
+        //! adds a node without branches, nodes pushed together can become the branches of another
+        self.nodes.push(ArenaNode { mve, dice: tracker, next: 0..0, max_depth: 1 });
+    }
+
+    #[inline]
+    pub fn get_move(&self, node: usize) -> &Move { &self.nodes[node].mve }
+
+    #[inline]
+    pub fn get_dice_tracker(&self, node: usize) -> &DiceTracker { &self.nodes[node].dice }
+
+    #[inline]
+    pub fn get_max_depth(&self, node: usize) -> u8 { self.nodes[node].max_depth }
+
+    pub fn set_branches(&mut self, node: usize, branches: Range<usize>) -> u8 {
+        //! links node to its branches, which must already have their depths, returning node's depth
+        let deepest = self.nodes[branches.clone()].iter().map(|branch| branch.max_depth).max().unwrap_or(0);
+        let node = &mut self.nodes[node];
+        node.next = branches;
+        node.max_depth = deepest + 1;
+        node.max_depth
+    }
+
+    pub fn to_tree(&self, node: usize) -> Box<TurnNode> {
+        //! copies node's tree out of the arena, without the branches that are shorter than the rest
+        let ArenaNode { mve, dice, next, max_depth } = &self.nodes[node];
+        let deepest = |branch: &usize| self.nodes[*branch].max_depth + 1 == *max_depth;
+        let mut branches = Vec::with_capacity(next.clone().filter(deepest).count());
+        branches.extend(next.clone().filter(deepest).map(|branch| self.to_tree(branch)));
+        Box::new(TurnNode { max_depth: *max_depth, mve: mve.clone(), dice: dice.clone(), scr: 0, next: branches })
+    }
+
+    pub fn contains(&self, roots: &[usize], turn: &[Move]) -> bool {
+        //! whether turn is one of the turns walked from roots, following only the branches that match it
+        match turn.split_first() {
+            Some((mve, rest)) => roots.iter().any(|root| self.contains_from(*root, mve, rest)),
+            None => roots.is_empty(),
+        }
+    }
+
+    fn contains_from(&self, node: usize, mve: &Move, rest: &[Move]) -> bool {
+        let ArenaNode { mve: played, next, max_depth, .. } = &self.nodes[node];
+        if played != mve || *max_depth as usize != rest.len() + 1 {
+            return false;
+        }
+        match rest.split_first() {
+            Some((mve, rest)) => next.clone().any(|branch| self.contains_from(branch, mve, rest)),
+            None => true,
+        }
+    }
+
+    fn branch(&self, node: usize) -> (&Move, Option<Level<'_>>) {
+        //! node's move, and the level of its branches to walk, unless it ends a turn
+        let ArenaNode { mve, next, max_depth, .. } = &self.nodes[node];
+        (mve, (*max_depth > 1).then(|| Level::Arena { next: next.clone(), depth: max_depth - 1 }))
+    }
+
+    pub fn turns<'a>(&'a self, roots: &'a [usize]) -> Turns<'a> {
+        //! walks the turns to_tree would keep, in place, roots must all be the same depth
+        Turns { arena: Some(self), stack: vec!(Level::Roots(roots, 0)), ..Turns::new(&[]) }
+    }
+}
+
+/// the branches at one depth of the path Turns is on, and which to visit next
+enum Level<'a> {
+    Tree(&'a [Box<TurnNode>], usize),
+    Roots(&'a [usize], usize),
+    /// arena branches, skipping those that aren't depth moves deep
+    Arena { next: Range<usize>, depth: u8 },
+}
+
+/**
+Walks every complete turn in a set of turn trees, or in a TurnArena, depth first,
+borrowing the turns rather than collecting every turn like to_array does
+*/
+pub struct Turns<'a> {
+    arena: Option<&'a TurnArena>,
+    stack: Vec<Level<'a>>,
+    path: Vec<Move>,
+    /// the position before the turn and after each move of path, empty unless positions are tracked
+    positions: Vec<[u8; MAX_CHECKERS]>,
+    /// the last turn given out, its final move is dropped before moving on
+    given: bool,
+    /// how many moves the current turn shares with the turn given before it
+    kept: usize,
+}
+
+impl<'a> Turns<'a> {
+    pub fn new(roots: &'a [Box<TurnNode>]) -> Turns<'a> {
+        Turns { arena: None, stack: vec!(Level::Tree(roots, 0)), path: Vec::with_capacity(4), positions: Vec::new(), given: false, kept: 0 }
+    }
+
+    pub fn with_position(roots: &'a [Box<TurnNode>], pos: &[u8; MAX_CHECKERS]) -> Turns<'a> {
+        Turns::new(roots).track_position(pos)
+    }
+
+    pub fn track_position(mut self, pos: &[u8; MAX_CHECKERS]) -> Turns<'a> {
+        //! walks turns played from pos, in the same coordinates as the turns, for next_turn_with_position
+        self.positions.clear();
+        self.positions.reserve(5);
+        self.positions.push(*pos);
+        self
+    }
+
+    pub fn next_turn(&mut self) -> Option<&[Move]> {
+        //! the next turn, without allocating, valid until the next call
+        self.advance().then_some(self.path.as_slice())
+    }
+
+    pub fn next_turn_with_position(&mut self) -> Option<(&[Move], &[u8; MAX_CHECKERS])> {
+        //! the next turn and the position it leaves, only for Turns made with_position
+        debug_assert!(!self.positions.is_empty(), "positions aren't tracked");
+        if !self.advance() {
+            return None;
+        }
+        Some((&self.path, self.positions.last().unwrap()))
+    }
+
+    pub fn next_turn_scored<P, F>(&mut self, scores: &mut Vec<P>, mut score_move: F) -> Option<(&[Move], &[u8; MAX_CHECKERS])>
+        where F: FnMut(&P, &[u8; MAX_CHECKERS], &Move, &[u8; MAX_CHECKERS]) -> P
+    {
+        /*!
+        like next_turn_with_position, and leaves the running score after each move of the turn in scores,
+        which starts with the score before the turn. Only the moves that differ from the last turn are scored,
+        score_move gets the score before a move, the position before it, the move and the position after
+        */
+        debug_assert!(!self.positions.is_empty() && !scores.is_empty(), "positions aren't tracked or there's no starting score");
+        if !self.advance() {
+            return None;
+        }
+        scores.truncate(self.kept + 1);
+        for i in scores.len() - 1..self.path.len() {
+            let score = score_move(&scores[i], &self.positions[i], &self.path[i], &self.positions[i + 1]);
+            scores.push(score);
+        }
+        Some((&self.path, self.positions.last().unwrap()))
+    }
+
+    fn advance(&mut self) -> bool {
+        //! moves path on to the next turn, false once every turn has been given
+        self.kept = self.path.len();
+        if self.given {
+            self.pop();
+            self.given = false;
+        }
+        let arena = self.arena;
+        loop {
+            let top = match self.stack.last_mut() {
+                Some(top) => top,
+                None => return false,
+            };
+            let found = match top {
+                Level::Tree(nodes, i) => nodes.get(*i).map(|node| {
+                    *i += 1;
+                    (&node.mve, (!node.next.is_empty()).then(|| Level::Tree(&node.next, 0)))
+                }),
+                Level::Roots(roots, i) => roots.get(*i).map(|root| {
+                    *i += 1;
+                    arena.unwrap().branch(*root)
+                }),
+                Level::Arena { next, depth } => {
+                    let arena = arena.unwrap();
+                    next.find(|branch| arena.nodes[*branch].max_depth == *depth).map(|branch| arena.branch(branch))
+                }
+            };
+            match found {
+                None => {
+                    self.stack.pop();
+                    self.pop();
+                }
+                Some((mve, None)) => {
+                    self.push(mve);
+                    self.given = true;
+                    return true;
+                }
+                Some((mve, Some(level))) => {
+                    self.push(mve);
+                    self.stack.push(level);
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, mve: &Move) {
+        if let Some(pos) = self.positions.last() {
+            let mut pos = *pos;
+            move_checker(&mut pos, &mve.start, &mve.end);
+            self.positions.push(pos);
+        }
+        self.path.push(mve.clone());
+    }
+
+    fn pop(&mut self) {
+        self.kept = self.kept.min(self.path.len().saturating_sub(1));
+        if self.path.pop().is_some() && self.positions.len() > 1 {
+            self.positions.pop();
+        }
+    }
+}
+
+impl Iterator for Turns<'_> {
+    type Item = Vec<Move>;
+
+    fn next(&mut self) -> Option<Vec<Move>> {
+        self.next_turn().map(|turn| turn.to_vec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +439,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn scores() {
         let mut node = testing_tree();
         assert_eq!(node.get_score(), &0usize);
@@ -240,47 +459,120 @@ mod tests {
 
     #[test]
     fn counting_stuff() {
-        let mut node = testing_tree();
+        let node = testing_tree();
         assert_eq!(node.actual_tree_size(), 4);
-        node.compute_depth();
         assert_eq!(node.max_depth, 2);
         assert_eq!(node.num_paths(), 3);
     }
 
     #[test]
     fn depth() {
-        let mut tt = testing_tree();
-        assert_eq!(tt.get_max_depth(), 1);
-        tt.compute_depth();
+        let tt = testing_tree();
         assert_eq!(tt.get_max_depth(), 2);
         assert_eq!(tt.next[0].max_depth, 1);
 
-        let mut ast = asym_tree();
-        assert_eq!(ast.get_max_depth(), 1);
-        ast.compute_depth();
+        let ast = asym_tree();
         assert_eq!(ast.get_max_depth(), 3);
         assert_eq!(ast.next[0].max_depth, 1);
         assert_eq!(ast.next[1].max_depth, 2);
         assert_eq!(ast.next[2].max_depth, 1);
         assert_eq!(ast.next[1].next[0].max_depth, 1);
         assert_eq!(ast.next[1].next[1].max_depth, 1);
+        assert_eq!(TurnNode::new(Move { start: 1, end: 3 }, DiceTracker::new(&testing_dice())).get_max_depth(), 1);
+    }
+
+    #[test]
+    fn turns() {
+        let roots = vec!(Box::new(asym_tree()), Box::new(testing_tree()));
+        let mut paths = Vec::new();
+        for node in roots.iter() {
+            node.to_array(&mut paths);
+        }
+        assert_eq!(Turns::new(&roots).collect::<Vec<Vec<Move>>>(), paths);
+        assert_eq!(Turns::new(&roots).count(), 7);
+        assert!(Turns::new(&[]).next().is_none());
+
+        let mut turns = Turns::new(&roots);
+        assert_eq!(turns.next_turn().unwrap(), [Move { start: 13, end: 11 }, Move { start: 11, end: 9 }]);
+        assert_eq!(turns.next_turn().unwrap().len(), 3);
     }
 
     #[test]
-    fn pruning() {
-        let cmp_tt = testing_tree();
-        let mut tt = testing_tree();
-        tt.compute_depth();
-        tt.prune(&tt.get_max_depth());
-        assert_eq!(tt.next.len(), cmp_tt.next.len());
-        tt.prune(&10);
-        assert_eq!(tt.next.len(), 0);
-
-        let mut ast = asym_tree();
-        ast.compute_depth();
-        ast.prune(&ast.get_max_depth());
-        assert_eq!(ast.next.len(), 1);
-        assert_eq!(ast.next[0].mve, asym_tree().next[1].mve);
-        assert_eq!(ast.next[0].next.len(), 2);
-    }
-}
\ No newline at end of file
+    fn turns_scored() {
+        let roots = vec!(Box::new(asym_tree()));
+        let start = [5, 5, 8, 10, 11, 12, 13, 13, 13, 14, 15, 16, 19, 21, 24];
+        let mut turns = Turns::with_position(&roots, &start);
+        let mut scores = vec!(0usize);
+        let mut calls = 0;
+        let mut count = 0;
+        while let Some((moves, end_pos)) = turns.next_turn_scored(&mut scores, |score, pos, mve, new_pos| {
+            calls += 1;
+            let mut played = *pos;
+            move_checker(&mut played, &mve.start, &mve.end);
+            assert_eq!(played, *new_pos);
+            score + mve.start as usize
+        }) {
+            assert_eq!(scores.len(), moves.len() + 1);
+            assert_eq!(*scores.last().unwrap(), moves.iter().map(|mve| mve.start as usize).sum::<usize>());
+            let mut played = start;
+            for mve in moves {
+                move_checker(&mut played, &mve.start, &mve.end);
+            }
+            assert_eq!(*end_pos, played);
+            count += 1;
+        }
+        assert_eq!(count, 4);
+        // every node is scored once, however many turns pass through it
+        assert_eq!(calls, 6);
+    }
+
+    #[test]
+    fn turns_with_position() {
+        let roots = vec!(Box::new(asym_tree()));
+        let start = [5, 5, 8, 10, 11, 12, 13, 13, 13, 14, 15, 16, 19, 21, 24];
+        let mut turns = Turns::with_position(&roots, &start);
+        let mut count = 0;
+        while let Some((moves, pos)) = turns.next_turn_with_position() {
+            let mut played = start;
+            for mve in moves {
+                move_checker(&mut played, &mve.start, &mve.end);
+            }
+            assert_eq!(*pos, played);
+            count += 1;
+        }
+        assert_eq!(count, Turns::new(&roots).count());
+        assert!(Turns::with_position(&[], &start).next_turn_with_position().is_none());
+    }
+
+    #[test]
+    fn arena() {
+        let dice = DiceTracker::new(&testing_dice());
+        let mut arena = TurnArena::default();
+        // a root with a branch that ends early and a branch that plays both dice
+        arena.push(Move { start: 1, end: 3 }, dice.clone());
+        arena.push(Move { start: 3, end: 6 }, dice.clone());
+        arena.push(Move { start: 5, end: 8 }, dice.clone());
+        arena.push(Move { start: 8, end: 10 }, dice.clone());
+        assert_eq!(arena.set_branches(2, 3..4), 2);
+        assert_eq!(arena.set_branches(0, 1..3), 3);
+        assert_eq!(arena.get_max_depth(1), 1);
+
+        let tree = arena.to_tree(0);
+        assert_eq!(tree.get_max_depth(), 3);
+        assert_eq!(tree.next.len(), 1);
+        assert_eq!(tree.next[0].mve, Move { start: 5, end: 8 });
+
+        assert_eq!(arena.turns(&[0]).collect::<Vec<Vec<Move>>>(), Turns::new(&[tree]).collect::<Vec<Vec<Move>>>());
+        assert!(arena.contains(&[0], &[Move { start: 1, end: 3 }, Move { start: 5, end: 8 }, Move { start: 8, end: 10 }]));
+        assert!(!arena.contains(&[0], &[Move { start: 1, end: 3 }, Move { start: 3, end: 6 }]));
+        assert!(!arena.contains(&[0], &[]));
+        assert!(arena.contains(&[], &[]));
+        let start = [1, 2, 2, 2, 3, 4, 5, 6, 6, 7, 9, 11, 12, 13, 14];
+        let mut walk = arena.turns(&[0]).track_position(&start);
+        assert_eq!(*walk.next_turn_with_position().unwrap().1, [2, 2, 2, 3, 3, 4, 6, 6, 7, 9, 10, 11, 12, 13, 14]);
+        assert!(walk.next_turn_with_position().is_none());
+
+        arena.clear();
+        assert_eq!(arena.len(), 0);
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::{self, ErrorKind};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tungstenite::{Message, WebSocket};
+
+use crate::channel::{MessageChannel, RecvError};
+
+/// how long a read waits for data before checking its timeout
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// how long a read holds the socket waiting for the rest of a frame
+const FRAME_WAIT: Duration = Duration::from_millis(1);
+
+/**
+Wraps a WebSocket and carries one serde_json::Value per text frame.
+Duplicates share the socket, so a spectator can be written to while another thread reads it.
+Reads wait for data outside the socket's lock, so they only hold it to read a frame.
+*/
+pub struct WsHandler {
+    socket: Arc<Mutex<WebSocket<TcpStream>>>,
+    /// the same connection, to wait for data without locking the socket
+    waiter: Arc<TcpStream>,
+    read_timeout: Option<Duration>,
+}
+
+impl WsHandler {
+    pub fn accept(stream: TcpStream) -> io::Result<WsHandler> {
+        //! completes the server side of the handshake
+        let socket = tungstenite::accept(stream).map_err(|e| io::Error::other(e.to_string()))?;
+        WsHandler::new(socket)
+    }
+
+    pub fn connect(stream: TcpStream, url: &str) -> io::Result<WsHandler> {
+        //! completes the client side of the handshake, url is like ws://localhost:9000/
+        let (socket, _) = tungstenite::client(url, stream).map_err(|e| io::Error::other(e.to_string()))?;
+        WsHandler::new(socket)
+    }
+
+    fn new(socket: WebSocket<TcpStream>) -> io::Result<WsHandler> {
+        socket.get_ref().set_read_timeout(Some(FRAME_WAIT))?;
+        let waiter = Arc::new(socket.get_ref().try_clone()?);
+        Ok(WsHandler { socket: Arc::new(Mutex::new(socket)), waiter, read_timeout: None })
+    }
+
+    fn wait_for_data(&self) {
+        //! blocks until data arrives or POLL_INTERVAL passes, without holding the socket
+        self.waiter.set_read_timeout(Some(POLL_INTERVAL)).ok();
+        self.waiter.peek(&mut [0]).ok();
+        self.waiter.set_read_timeout(Some(FRAME_WAIT)).ok();
+    }
+}
+
+impl MessageChannel for WsHandler {
+    fn recv(&mut self) -> Result<Value, RecvError> {
+        let started = Instant::now();
+        loop {
+            let result = self.socket.lock().unwrap().read();
+            match result {
+                Ok(Message::Text(text)) => return serde_json::from_str(&text).map_err(|_| RecvError::Malformed(text)),
+                Ok(Message::Binary(bytes)) => {
+                    return serde_json::from_slice(&bytes)
+                        .map_err(|_| RecvError::Malformed(String::from_utf8_lossy(&bytes).into_owned()));
+                }
+                Ok(Message::Close(_)) => return Err(RecvError::Closed),
+                // pings are answered by tungstenite itself
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    if self.read_timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+                        return Err(RecvError::Io(e));
+                    }
+                    self.wait_for_data();
+                }
+                Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => return Err(RecvError::Closed),
+                Err(tungstenite::Error::Io(e)) => return Err(RecvError::Io(e)),
+                Err(e) => return Err(RecvError::Io(io::Error::other(e.to_string()))),
+            }
+        }
+    }
+
+    fn send(&mut self, val: &Value) -> io::Result<()> {
+        self.socket.lock().unwrap().send(Message::Text(val.to_string()))
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+
+    fn duplicate(&self) -> Box<dyn MessageChannel> {
+        Box::new(WsHandler { socket: self.socket.clone(), waiter: self.waiter.clone(), read_timeout: self.read_timeout })
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    player::PlayerColor,
+    r#mod::{HOME, MAX_CHECKERS},
+};
+
+const NUM_POINTS: usize = HOME as usize + 1;
+const KEY_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/**
+Zobrist keys indexed by [color][point][checkers on point].
+The key for an empty point is 0, so a position's hash is the xor of
+one key for every occupied point of each color.
+*/
+static KEYS: [[[u64; MAX_CHECKERS + 1]; NUM_POINTS]; 2] = gen_keys();
+
+/// xor'd into a position hash when White is the side to move
+pub const SIDE_KEY: u64 = splitmix64(KEY_SEED ^ 0xFFFF_FFFF_FFFF_FFFF).1;
+
+/// keys indexed by [color][point], xor'd in for each pinned checker, as in Plakoto
+static PIN_KEYS: [[u64; NUM_POINTS]; 2] = gen_pin_keys();
+
+/**
+Keys indexed by how many checkers each player started with. A full set's key is 0,
+so a game with fewer checkers, whose missing checkers are padded as HOME,
+doesn't share hashes with a standard game that has borne those checkers off
+*/
+static CHECKERS_KEYS: [u64; MAX_CHECKERS + 1] = gen_checkers_keys();
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    //! returns (next state, output), see http://xoshiro.di.unimi.it/splitmix64.c
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+const fn gen_keys() -> [[[u64; MAX_CHECKERS + 1]; NUM_POINTS]; 2] {
+    let mut keys = [[[0u64; MAX_CHECKERS + 1]; NUM_POINTS]; 2];
+    let mut state = KEY_SEED;
+    let mut color = 0;
+    while color < 2 {
+        let mut point = 0;
+        while point < NUM_POINTS {
+            let mut count = 1;
+            while count <= MAX_CHECKERS {
+                let (next, key) = splitmix64(state);
+                state = next;
+                keys[color][point][count] = key;
+                count += 1;
+            }
+            point += 1;
+        }
+        color += 1;
+    }
+    keys
+}
+
+const fn gen_pin_keys() -> [[u64; NUM_POINTS]; 2] {
+    let mut keys = [[0u64; NUM_POINTS]; 2];
+    let mut state = KEY_SEED ^ 0x5049_4E53;
+    let mut color = 0;
+    while color < 2 {
+        let mut point = 0;
+        while point < NUM_POINTS {
+            let (next, key) = splitmix64(state);
+            state = next;
+            keys[color][point] = key;
+            point += 1;
+        }
+        color += 1;
+    }
+    keys
+}
+
+const fn gen_checkers_keys() -> [u64; MAX_CHECKERS + 1] {
+    let mut keys = [0u64; MAX_CHECKERS + 1];
+    let mut state = KEY_SEED ^ 0x4348_4B52;
+    let mut checkers = 0;
+    while checkers < MAX_CHECKERS {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys[checkers] = key;
+        checkers += 1;
+    }
+    keys
+}
+
+#[inline]
+const fn color_ind(color: &PlayerColor) -> usize {
+    match color {
+        PlayerColor::Black => 0,
+        PlayerColor::White => 1,
+    }
+}
+
+#[inline]
+pub const fn point_key(color: &PlayerColor, point: u8, count: u8) -> u64 {
+    //! returns key for a point holding count of color's checkers
+    KEYS[color_ind(color)][point as usize][count as usize]
+}
+
+#[inline]
+pub fn update_point(hash: u64, color: &PlayerColor, point: u8, old_count: u8, new_count: u8) -> u64 {
+    //! swaps the key for a point whose checker count changed from old_count to new_count
+    hash ^ point_key(color, point, old_count) ^ point_key(color, point, new_count)
+}
+
+#[inline]
+pub fn pin_key(color: &PlayerColor, point: u8) -> u64 {
+    //! returns key for color's checker pinned on point
+    PIN_KEYS[color_ind(color)][point as usize]
+}
+
+pub fn hash_pins(pinned: &[u32; 2]) -> u64 {
+    //! hashes both players' pins, bit p of black's (then white's) mask set for a checker pinned on point p
+    let mut hash = 0;
+    for (color, pins) in [PlayerColor::Black, PlayerColor::White].iter().zip(pinned.iter()) {
+        for point in 0..NUM_POINTS as u8 {
+            if pins & (1 << point) != 0 {
+                hash ^= pin_key(color, point);
+            }
+        }
+    }
+    hash
+}
+
+#[inline]
+pub const fn checkers_key(checkers: u8) -> u64 {
+    //! returns key for a game where each player started with checkers checkers, 0 for a full set
+    CHECKERS_KEYS[checkers as usize]
+}
+
+pub const fn hash_positions(color: &PlayerColor, pieces: &[u8; MAX_CHECKERS]) -> u64 {
+    //! hashes a single player's sorted positions from scratch
+    let mut hash = 0;
+    let mut i = 0;
+    while i < MAX_CHECKERS {
+        let point = pieces[i];
+        let mut count = 0;
+        while i < MAX_CHECKERS && pieces[i] == point {
+            count += 1;
+            i += 1;
+        }
+        hash ^= point_key(color, point, count);
+    }
+    hash
+}
+
+pub const fn hash_board(black: &[u8; MAX_CHECKERS], white: &[u8; MAX_CHECKERS]) -> u64 {
+    //! hashes both players' positions from scratch
+    hash_positions(&PlayerColor::Black, black) ^ hash_positions(&PlayerColor::White, white)
+}
+
+pub fn hash_local(player_pos: &[u8; MAX_CHECKERS], opp_frq: &[u8]) -> u64 {
+    /*!
+    hashes a position in PlayerBoard's local coordinates.
+    Local coordinates are White's world coordinates, so the result matches
+    the world hash of the same position with White to move,
+    when it has a full set of checkers and no pins.
+    */
+    let mut hash = hash_positions(&PlayerColor::White, player_pos) ^ SIDE_KEY;
+    for (point, count) in opp_frq.iter().enumerate() {
+        hash ^= point_key(&PlayerColor::Black, point as u8, *count);
+    }
+    hash
+}
+
+#[inline]
+pub fn with_side(hash: u64, to_move: &PlayerColor) -> u64 {
+    //! combines a board hash with the side to move
+    match to_move {
+        PlayerColor::Black => hash,
+        PlayerColor::White => hash ^ SIDE_KEY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const TEST_POSITIONS: [u8; MAX_CHECKERS] = [3, 3, 5, 5, 7, 8, 9, 11, 11, 12, 13, 13, 13, 13, 15];
+
+    #[test]
+    fn keys_unique() {
+        let mut seen = HashSet::new();
+        for color in KEYS.iter() {
+            for point in color.iter() {
+                assert_eq!(point[0], 0);
+                for key in point[1..].iter() {
+                    assert!(seen.insert(*key), "duplicate zobrist key");
+                }
+            }
+        }
+        assert!(seen.insert(SIDE_KEY));
+        for key in PIN_KEYS.iter().flatten() {
+            assert!(seen.insert(*key), "duplicate pin key");
+        }
+        assert_eq!(checkers_key(MAX_CHECKERS as u8), 0);
+        for key in CHECKERS_KEYS[..MAX_CHECKERS].iter() {
+            assert!(seen.insert(*key), "duplicate checkers key");
+        }
+    }
+
+    #[test]
+    fn pins() {
+        assert_eq!(hash_pins(&[0, 0]), 0);
+        let pinned = [1 << 4 | 1 << 9, 1 << 4];
+        assert_eq!(hash_pins(&pinned),
+                   pin_key(&PlayerColor::Black, 4) ^ pin_key(&PlayerColor::Black, 9) ^ pin_key(&PlayerColor::White, 4));
+    }
+
+    #[test]
+    fn colors_differ() {
+        assert_ne!(
+            hash_positions(&PlayerColor::Black, &TEST_POSITIONS),
+            hash_positions(&PlayerColor::White, &TEST_POSITIONS)
+        );
+    }
+
+    #[test]
+    fn incremental_point() {
+        let mut moved = TEST_POSITIONS;
+        moved[4] = 6;
+        moved.sort();
+        let mut hash = hash_positions(&PlayerColor::Black, &TEST_POSITIONS);
+        hash = update_point(hash, &PlayerColor::Black, 7, 1, 0);
+        hash = update_point(hash, &PlayerColor::Black, 6, 0, 1);
+        assert_eq!(hash, hash_positions(&PlayerColor::Black, &moved));
+    }
+
+    #[test]
+    fn local_matches_world() {
+        let opp: [u8; MAX_CHECKERS] = [1, 1, 2, 4, 4, 4, 16, 18, 20, 20, 21, 22, 23, 25, 25];
+        let mut opp_frq = vec![0u8; NUM_POINTS];
+        for p in opp.iter() {
+            opp_frq[*p as usize] += 1;
+        }
+        assert_eq!(
+            hash_local(&TEST_POSITIONS, &opp_frq),
+            with_side(hash_board(&opp, &TEST_POSITIONS), &PlayerColor::White)
+        );
+    }
+}
@@ -1,7 +1,13 @@
 use crate::cheating_players::{CheatStep, CheatOn};
 use backgammon_lib::local_remote::LocalPlayer;
 use backgammon_lib::strategy::rando::Rando;
-use backgammon_lib::administrator::{Administrator, HandleCheater, Winner};
+use backgammon_lib::administrator::{Administrator, HandleCheater, Replacement, Winner};
+use backgammon_lib::board::Board;
+use backgammon_lib::cheat::CheatReason;
+use backgammon_lib::observer::AdminObserver;
+use backgammon_lib::player::PlayerColor;
+use backgammon_lib::r#move::Move;
+use std::sync::{Arc, Mutex};
 
 mod cheating_players;
 
@@ -145,4 +151,142 @@ fn two_cheater_start() {
     admin.moderate_game(HandleCheater::Replace);
     assert_ne!(admin.get_winner(), Winner::None);
     assert_eq!(admin.get_players().0.get_name().to_string(), admin.get_players().1.get_name().to_string());
-}
\ No newline at end of file
+}
+/// records a short description of each callback
+struct EventLog {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl AdminObserver for EventLog {
+    fn game_started(&mut self, black: &str, white: &str) {
+        self.events.lock().unwrap().push(format!("start {} {}", black, white));
+    }
+
+    fn dice_rolled(&mut self, _color: &PlayerColor, _dice: &[u8]) {
+        self.events.lock().unwrap().push("roll".to_string());
+    }
+
+    fn turn_submitted(&mut self, _color: &PlayerColor, _moves: &[Move], _board: &Board) {
+        self.events.lock().unwrap().push("turn".to_string());
+    }
+
+    fn turn_rejected(&mut self, _color: &PlayerColor, _moves: &[Move]) {
+        self.events.lock().unwrap().push("rejected".to_string());
+    }
+
+    fn player_cheated(&mut self, _color: &PlayerColor) {
+        self.events.lock().unwrap().push("cheated".to_string());
+    }
+
+    fn player_replaced(&mut self, _color: &PlayerColor, replacement: &str) {
+        self.events.lock().unwrap().push(format!("replaced {}", replacement));
+    }
+
+    fn game_over(&mut self, winner: Option<&PlayerColor>, _board: &Board) {
+        self.events.lock().unwrap().push(format!("over {:?}", winner));
+    }
+}
+
+#[test]
+fn observed_replace() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut admin = Administrator::new(
+        Box::new(CheatStep::local(3, CheatOn::Turn)),
+        Box::new(LocalPlayer::new(String::from("normie"), Rando)),
+    );
+    admin.add_observer(Box::new(EventLog { events: events.clone() }));
+    admin.add_observer(Box::new(EventLog { events: events.clone() }));
+    admin.moderate_game(HandleCheater::Replace);
+
+    let events = events.lock().unwrap();
+    // each event is seen by both observers in turn
+    for pair in events.chunks(2) {
+        assert_eq!(pair[0], pair[1]);
+    }
+    assert!(events[0].starts_with("start "));
+    let rejected = events.iter().position(|e| e == "rejected").unwrap();
+    assert_eq!(events[rejected + 2], "cheated");
+    assert_eq!(events[rejected + 4], "replaced Malnati");
+    assert_eq!(events.iter().filter(|e| *e == "roll").count(), events.iter().filter(|e| *e == "turn").count());
+    assert!(events.last().unwrap().starts_with("over Some"));
+}
+
+#[test]
+fn custom_replacement() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut admin = Administrator::new(
+        Box::new(CheatStep::local(3, CheatOn::Turn)),
+        Box::new(LocalPlayer::new(String::from("normie"), Rando)),
+    );
+    admin.set_replacement(Replacement { name: String::from("Sub"), strategy: "Bopsy".parse().unwrap() });
+    admin.add_observer(Box::new(EventLog { events: events.clone() }));
+    admin.moderate_game(HandleCheater::Replace);
+
+    assert!(events.lock().unwrap().contains(&String::from("replaced Sub")));
+    assert_eq!(admin.get_players().0.get_name().to_string(), "Sub");
+}
+
+#[test]
+fn observed_end_game() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut admin = Administrator::new(
+        Box::new(CheatStep::local(0, CheatOn::Start)),
+        Box::new(LocalPlayer::new(String::from("normie"), Rando)),
+    );
+    admin.add_observer(Box::new(EventLog { events: events.clone() }));
+    admin.moderate_game(HandleCheater::EndGame);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1], "cheated");
+    assert!(events[2].starts_with("over Some"));
+}
+
+#[test]
+fn observed_cheat_mid_game() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut admin = Administrator::seeded(
+        Box::new(CheatStep::local(3, CheatOn::Turn)),
+        Box::new(LocalPlayer::new(String::from("normie"), Rando)),
+        PlayerColor::Black, 1);
+    admin.add_observer(Box::new(EventLog { events: events.clone() }));
+    admin.moderate_game(HandleCheater::EndGame);
+
+    let events = events.lock().unwrap();
+    let rejected = events.iter().position(|e| e == "rejected").unwrap();
+    assert_eq!(events[rejected + 1], "cheated");
+    assert_eq!(events.last().unwrap(), "over Some(White)");
+}
+
+#[test]
+fn observed_cheat_at_end() {
+    // the cheater may have just won, but the game is over for them once they cheat
+    for seed in 0..5 {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut admin = Administrator::seeded(
+            Box::new(CheatStep::local(0, CheatOn::End)),
+            Box::new(LocalPlayer::new(String::from("normie"), Rando)),
+            PlayerColor::Black, seed);
+        admin.add_observer(Box::new(EventLog { events: events.clone() }));
+        admin.moderate_game(HandleCheater::EndGame);
+
+        assert_eq!(admin.get_winner(), Winner::PlayerTwo);
+        let events = events.lock().unwrap();
+        assert!(events.contains(&String::from("cheated")));
+        assert_eq!(events.last().unwrap(), "over Some(White)");
+    }
+}
+
+#[test]
+fn infractions_name_the_cheater() {
+    let mut admin = Administrator::new(
+        Box::new(CheatStep::local(0, CheatOn::Name)),
+        Box::new(LocalPlayer::new(String::from("normie"), Rando)),
+    );
+    admin.moderate_game(HandleCheater::Replace);
+    let infractions = admin.get_infractions();
+    assert_eq!(infractions.len(), 1);
+    assert_eq!(infractions[0].name, "pre-cheat");
+    assert_eq!(infractions[0].reason, CheatReason::Unexplained);
+    assert_eq!(infractions[0].to_json()["cheat"]["reason"], "unexplained");
+}
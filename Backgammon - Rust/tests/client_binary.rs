@@ -0,0 +1,38 @@
+use backgammon_lib::administrator::HandleCheater;
+use backgammon_lib::channel::ChannelListener;
+use backgammon_lib::local_remote::IPlayer;
+use backgammon_lib::tournament::tournament::{accept_players, new_game, GameSettings, Registration};
+use std::net::TcpListener;
+use std::process::{Command, Stdio};
+
+#[test]
+fn clients_play_until_server_closes() {
+    let listener: ChannelListener = TcpListener::bind("localhost:0").unwrap().into();
+    let port = listener.local_addr().unwrap().port().to_string();
+    let clients: Vec<_> = [("lou", "Bopsy"), ("bob", "Rando")].iter()
+        .map(|&(name, strategy)| Command::new(env!("CARGO_BIN_EXE_backgammon-client"))
+            .args(["localhost", port.as_str(), name, strategy])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap())
+        .collect();
+
+    let mut players: Vec<Box<dyn IPlayer>> = Vec::new();
+    accept_players(&mut players, &2, &listener, &Registration::default(), &GameSettings::default());
+    let mut names: Vec<String> = players.iter_mut().map(|p| p.get_name().to_string()).collect();
+    names.sort();
+    assert_eq!(names, vec!("bob", "lou"));
+
+    let two = players.pop().unwrap();
+    let one = players.pop().unwrap();
+    let mut admin = new_game(one, two, &None);
+    admin.moderate_game(HandleCheater::EndGame);
+    assert!(admin.get_num_turns() > 0);
+    drop(admin);
+
+    for client in clients {
+        let output = client.wait_with_output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "server closed the connection");
+    }
+}